@@ -0,0 +1,303 @@
+//! Ashfall reports resolved to places on the map.
+//!
+//! `ashfall_reports` holds whatever phrase CENAPRED used that day, which can
+//! name several towns in one breath: "Amecameca, Ecatzingo, Ozumba y
+//! Tepetlixpa, Estado de México y Tetela del Volcán, Morelos". Each phrase is
+//! split into municipalities, each state named after a run of municipalities
+//! is applied to that run, and every name is looked up in the bundled
+//! [`gazetteer`](crate::gazetteer). Anything that will not resolve is kept,
+//! as written, rather than dropped.
+
+use crate::format::Tabular;
+use crate::gazetteer::{self, State};
+use crate::geo::Coordinates;
+use crate::models::VolcanoReport;
+use chrono::NaiveDate;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// A municipality where ash was reported to fall.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AshfallLocation {
+    /// The gazetteer's spelling, e.g. "Tetela del Volcán".
+    pub municipality: String,
+    pub state: State,
+    /// "Tetela del Volcán, Morelos", stable across spellings.
+    pub canonical: String,
+    pub coordinates: Coordinates,
+}
+
+/// The places in a day's ashfall reports.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AshfallPlaces {
+    pub resolved: Vec<AshfallLocation>,
+    /// Names that are not in the gazetteer, as written.
+    pub unresolved: Vec<String>,
+}
+
+impl AshfallPlaces {
+    /// Resolve every phrase, dropping repeats.
+    pub fn resolve<S: AsRef<str>>(reports: &[S]) -> Self {
+        let mut places = AshfallPlaces::default();
+        for report in reports {
+            for (name, state) in split(report.as_ref()) {
+                match gazetteer::lookup(&name, state) {
+                    Some(m) => {
+                        let location = AshfallLocation {
+                            municipality: m.name.to_string(),
+                            state: m.state,
+                            canonical: m.canonical_name(),
+                            coordinates: m.coordinates,
+                        };
+                        if !places.resolved.contains(&location) {
+                            places.resolved.push(location);
+                        }
+                    }
+                    None => {
+                        if !places.unresolved.contains(&name) {
+                            places.unresolved.push(name);
+                        }
+                    }
+                }
+            }
+        }
+        places
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.resolved.is_empty() && self.unresolved.is_empty()
+    }
+
+    /// Canonical names of the resolved places, then the unresolved ones as
+    /// written.
+    pub fn names(self) -> Vec<String> {
+        self.resolved
+            .into_iter()
+            .map(|p| p.canonical)
+            .chain(self.unresolved)
+            .collect()
+    }
+}
+
+/// What one full report said about ashfall, as the search index keeps it
+/// between runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AshfallDay {
+    pub date: NaiveDate,
+    /// `ashfall_reports` as published, empty when none fell.
+    pub reports: Vec<String>,
+}
+
+impl AshfallDay {
+    /// `None` for a partial record, which cannot say whether ash fell.
+    pub fn of(report: &VolcanoReport) -> Option<Self> {
+        report.is_full().then(|| AshfallDay {
+            date: report.date,
+            reports: report.ashfall_reports.clone(),
+        })
+    }
+
+    pub fn places(&self) -> AshfallPlaces {
+        AshfallPlaces::resolve(&self.reports)
+    }
+}
+
+/// How often one place was named in ashfall reports.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TownCount {
+    /// Canonical name, or the name as written when it did not resolve.
+    pub name: String,
+    pub state: Option<State>,
+    pub days: usize,
+    pub first: NaiveDate,
+    pub last: NaiveDate,
+}
+
+impl Tabular for TownCount {
+    type Row<'a> = &'a TownCount;
+
+    const COLUMNS: &'static [&'static str] = &["name", "state", "days", "first", "last"];
+    const RECORD: bool = true;
+
+    fn rows(&self) -> Vec<&TownCount> {
+        vec![self]
+    }
+}
+
+/// Every place named across `days`, most often first. Ties go to the most
+/// recently named.
+pub fn rank(days: &[AshfallDay]) -> Vec<TownCount> {
+    let mut counts: HashMap<String, TownCount> = HashMap::new();
+    for day in days {
+        let places = day.places();
+        let named = places
+            .resolved
+            .into_iter()
+            .map(|l| (l.canonical, Some(l.state)))
+            .chain(places.unresolved.into_iter().map(|n| (n, None)));
+        for (name, state) in named {
+            let count = counts.entry(name.clone()).or_insert(TownCount {
+                name,
+                state,
+                days: 0,
+                first: day.date,
+                last: day.date,
+            });
+            count.days += 1;
+            count.first = count.first.min(day.date);
+            count.last = count.last.max(day.date);
+        }
+    }
+
+    let mut ranked: Vec<TownCount> = counts.into_values().collect();
+    ranked.sort_by(|a, b| {
+        b.days
+            .cmp(&a.days)
+            .then(b.last.cmp(&a.last))
+            .then(a.name.cmp(&b.name))
+    });
+    ranked
+}
+
+/// Trailing attributions, never part of a place name.
+fn attribution() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)\s+(?:por parte|reportad[oa]s?|seg[uú]n)\b.*$|\s*\(.*$").unwrap()
+    })
+}
+
+/// Commas, "y"/"e" and the phrases that introduce a state.
+fn separator() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"(?i),|\s+[ye]\s+|\s+pertenecientes?\s+al?\s+|\s+(?:del|en el)\s+estado\s+de\s+",
+        )
+        .unwrap()
+    })
+}
+
+/// Split one phrase into municipality names, each with the state the phrase
+/// puts it in, if any.
+fn split(report: &str) -> Vec<(String, Option<State>)> {
+    let report = attribution().replace(report, "");
+    let mut out: Vec<(String, Option<State>)> = Vec::new();
+    // Start of the run of names still waiting for a state.
+    let mut pending = 0;
+
+    for piece in separator().split(&report) {
+        let piece = piece
+            .trim()
+            .trim_start_matches("del ")
+            .trim_start_matches("el ")
+            .trim_end_matches('.')
+            .trim();
+        if piece.is_empty() {
+            continue;
+        }
+        match State::from_spanish(piece) {
+            // "Puebla" alone is the city; after a list it is the state.
+            Some(state) if pending < out.len() => {
+                for entry in &mut out[pending..] {
+                    entry.1 = Some(state);
+                }
+                pending = out.len();
+            }
+            _ => out.push((piece.to_string(), None)),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canonical(reports: &[&str]) -> Vec<String> {
+        let places = AshfallPlaces::resolve(reports);
+        assert!(places.unresolved.is_empty(), "{:?}", places.unresolved);
+        places.resolved.into_iter().map(|l| l.canonical).collect()
+    }
+
+    #[test]
+    fn lists_split_and_take_the_state_that_follows() {
+        assert_eq!(
+            canonical(&[
+                "Amecameca, Ecatzingo, Ozumba y Tepetlixpa, Estado de México y Tetela del Volcán, Morelos"
+            ]),
+            vec![
+                "Amecameca, Estado de México",
+                "Ecatzingo, Estado de México",
+                "Ozumba, Estado de México",
+                "Tepetlixpa, Estado de México",
+                "Tetela del Volcán, Morelos",
+            ]
+        );
+        assert_eq!(
+            canonical(&["Chiautzingo, Quimixtlán, Chichiquila y Chilchota, del estado de Puebla"]),
+            vec![
+                "Chiautzingo, Puebla",
+                "Quimixtlán, Puebla",
+                "Chichiquila, Puebla",
+                "Chilchotla, Puebla",
+            ]
+        );
+    }
+
+    #[test]
+    fn spelling_and_attribution_are_tidied_away() {
+        assert_eq!(
+            canonical(&[
+                "Tétela del Volcán, Morelos",
+                "Yecapixtla y Tetela del volcán",
+                "Amecameca y Ozumba pertenecientes al Estado de México",
+                "Ecatzingo, Estado de México por parte del Centro Nacional de Comunicaciones y \
+                 Operaciones de Protección Civil (CENACOM)",
+            ]),
+            vec![
+                "Tetela del Volcán, Morelos",
+                "Yecapixtla, Morelos",
+                "Amecameca, Estado de México",
+                "Ozumba, Estado de México",
+                "Ecatzingo, Estado de México",
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_places_land_in_the_unresolved_bucket() {
+        let places =
+            AshfallPlaces::resolve(&["Atlautla y San Juan Tehuixtitlán, Estado de México"]);
+        assert_eq!(places.resolved.len(), 1);
+        assert_eq!(places.unresolved, vec!["San Juan Tehuixtitlán"]);
+    }
+
+    #[test]
+    fn rank_counts_days_per_canonical_name() {
+        let mut a = VolcanoReport::blank("2023-05-20");
+        a.ashfall_reports = vec!["Amecameca y Ozumba pertenecientes al Estado de México".into()];
+        let mut b = VolcanoReport::blank("2023-05-21");
+        b.ashfall_reports = vec!["Amécameca, Estado de México".into(), "Xonacatlán".into()];
+
+        let ranked = rank(&[a, b].map(|r| AshfallDay::of(&r).unwrap()));
+        let summary: Vec<_> = ranked.iter().map(|t| (t.name.as_str(), t.days)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Amecameca, Estado de México", 2),
+                ("Xonacatlán", 1),
+                ("Ozumba, Estado de México", 1),
+            ]
+        );
+        assert_eq!(ranked[0].first.to_string(), "2023-05-20");
+        assert_eq!(ranked[1].state, None);
+    }
+
+    #[test]
+    fn a_state_name_on_its_own_is_the_city() {
+        assert_eq!(canonical(&["Puebla"]), vec!["Puebla, Puebla"]);
+    }
+}
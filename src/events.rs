@@ -0,0 +1,464 @@
+//! Facts the counters don't carry, pulled out of the Spanish narrative.
+//!
+//! The counters say how many explosions there were; the narrative says when,
+//! how large, whether incandescent fragments were thrown, how high the ash
+//! column rose and which photos show it. Extraction is a small set of rules
+//! over CENAPRED's recurring sentence shapes, not language understanding, so
+//! every event carries the span of text it came from and can be checked
+//! against the original.
+//!
+//! Activity events come only from the observations. The scenario list names
+//! explosions and incandescent fragments every day as things that *could*
+//! happen, and reading those as observations would invent activity.
+
+use crate::narrative::Narrative;
+use crate::text::sentences;
+use chrono::NaiveTime;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// Byte range of `summary_spanish` an event was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Intensity {
+    Minor,
+    Moderate,
+    Major,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaKind {
+    Image,
+    Video,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventKind {
+    /// Explosions mentioned in one sentence. `times` is local time, in the
+    /// order written; it may be shorter than `count` when not every explosion
+    /// was timed.
+    Explosion {
+        count: u32,
+        times: Vec<NaiveTime>,
+        intensity: Option<Intensity>,
+    },
+    /// An eruptive column, converted to kilometres whatever unit was used.
+    AshColumn { height_km: f64 },
+    /// Incandescent fragments were seen being thrown out.
+    IncandescentFragments,
+    /// Volcanotectonic earthquakes mentioned in one sentence.
+    Volcanotectonic {
+        count: u32,
+        times: Vec<NaiveTime>,
+        magnitudes: Vec<f64>,
+    },
+    /// The exclusion (or "safety") radius around the crater.
+    ExclusionRadius { km: f64 },
+    /// A numbered photo or video the narrative points at, e.g. "(imagen 2)".
+    Media { kind: MediaKind, number: u32 },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NarrativeEvent {
+    #[serde(flatten)]
+    pub kind: EventKind,
+    pub span: Span,
+}
+
+impl NarrativeEvent {
+    /// The text this event was read from.
+    pub fn source<'a>(&self, summary: &'a str) -> &'a str {
+        &summary[self.span.start..self.span.end]
+    }
+}
+
+struct Rules {
+    explosion: Regex,
+    explosion_count: Regex,
+    volcanotectonic: Regex,
+    volcanotectonic_count: Regex,
+    negation: Regex,
+    hypothetical: Regex,
+    times: Regex,
+    clock: Regex,
+    magnitudes: Regex,
+    number: Regex,
+    column: Regex,
+    fragments: Regex,
+    radius: Regex,
+    media: Regex,
+}
+
+fn rules() -> &'static Rules {
+    static RULES: OnceLock<Rules> = OnceLock::new();
+    RULES.get_or_init(|| {
+        let re = |pattern: &str| Regex::new(pattern).expect("narrative rule must compile");
+        Rules {
+            explosion: re(r"(?i)explosi[oó]n|explosiones"),
+            explosion_count: re(
+                r"(?i)\b(\d+|una?|dos|tres|cuatro|cinco|seis|siete|ocho|nueve|diez)\s+(?:explosi[oó]n|explosiones)",
+            ),
+            volcanotectonic: re(r"(?i)sismos?\s+volcanotect[oó]nicos?"),
+            volcanotectonic_count: re(
+                r"(?i)\b(\d+|una?|dos|tres|cuatro|cinco|seis|siete|ocho|nueve|diez)\s+sismos?\s+volcanotect",
+            ),
+            negation: re(r"(?i)\b(?:no se (?:registr|detect|present|observ)\w*|sin|ningun[ao]?)\b"),
+            // Warnings and background ("existe la posibilidad de que ocurran
+            // explosiones", "es probable que…") describe what could happen.
+            hypothetical: re(
+                r"(?i)posibilidad|probable|podr[ií]a|pueden|se espera|exhorta|recomendaci[oó]n|cualquier volc[aá]n|en caso de",
+            ),
+            // Only times introduced by "a las", so the report's own timestamp
+            // ("Mayo 3, 11:00 h (Mayo 3, 16:00 GMT)") is never read as an event.
+            times: re(r"(?i)\ba las?\s+(\d{1,2}:\d{2}(?:\s*h)?(?:(?:,\s*|\s+y\s+)\d{1,2}:\d{2}(?:\s*h)?)*)"),
+            clock: re(r"(\d{1,2}):(\d{2})"),
+            magnitudes: re(
+                r"(?i)magnitud(?:es)?(?:\s+calculadas?)?(?:\s+de)?\s+(\d+\.\d+(?:(?:,\s*|\s+y\s+)\d+\.\d+)*)",
+            ),
+            number: re(r"\d+\.\d+"),
+            column: re(
+                r"(?i)columna(?:\s+eruptiva|\s+de\s+ceniza)?\s+de\s+(?:aproximadamente\s+|hasta\s+)?(\d+(?:[.,]\d+)?)\s*(km|kil[oó]metros?|m|metros)\b",
+            ),
+            fragments: re(r"(?i)fragmentos\s+incandescentes"),
+            radius: re(
+                r"(?i)radio\s+de\s+(?:exclusi[oó]n|seguridad)\s+de\s+(\d+(?:[.,]\d+)?)\s*(?:km|kil[oó]metros)",
+            ),
+            media: re(r"(?i)\((imagen|v[ií]deo)\s+(\d+)\)"),
+        }
+    })
+}
+
+/// Read every event out of a `summary_spanish`, in order of appearance.
+pub fn extract(summary: &str) -> Vec<NarrativeEvent> {
+    let rules = rules();
+    let narrative = Narrative::parse(summary);
+    let mut events = Vec::new();
+
+    // Observations are slices of the summary; find each in turn so spans
+    // refer back to the original text.
+    let mut cursor = 0;
+    for observation in &narrative.observations {
+        let Some(found) = summary[cursor..].find(observation.as_str()) else {
+            continue;
+        };
+        let base = cursor + found;
+        cursor = base + observation.len();
+
+        for (start, sentence) in sentences(observation) {
+            extract_activity(rules, sentence, base + start, &mut events);
+        }
+    }
+
+    // The radius is stated in the scenarios and recommendations as often as in
+    // the observations, and means the same thing wherever it appears.
+    let mut radii: Vec<f64> = Vec::new();
+    for m in rules.radius.captures_iter(summary) {
+        let Some(km) = parse_number(&m[1]) else {
+            continue;
+        };
+        if !radii.contains(&km) {
+            radii.push(km);
+            let whole = m.get(0).unwrap();
+            events.push(NarrativeEvent {
+                kind: EventKind::ExclusionRadius { km },
+                span: Span {
+                    start: whole.start(),
+                    end: whole.end(),
+                },
+            });
+        }
+    }
+
+    events.sort_by_key(|e| e.span.start);
+    events
+}
+
+fn extract_activity(rules: &Rules, sentence: &str, offset: usize, out: &mut Vec<NarrativeEvent>) {
+    let span = |start: usize, end: usize| Span {
+        start: offset + start,
+        end: offset + end,
+    };
+    let whole = span(0, sentence.len());
+    let hypothetical = rules.hypothetical.is_match(sentence);
+
+    let vt = rules.volcanotectonic.find(sentence);
+    let explosion = rules.explosion.find(sentence);
+
+    if let Some(vt) = vt.filter(|m| !hypothetical && !negated(rules, sentence, m.start())) {
+        let count = rules
+            .volcanotectonic_count
+            .captures(sentence)
+            .and_then(|c| count_word(&c[1]))
+            .unwrap_or(1);
+        // Times and magnitudes after the mention belong to it.
+        let tail = &sentence[vt.start()..];
+        let times = times_in(rules, tail);
+        let magnitudes = rules
+            .magnitudes
+            .captures(tail)
+            .map(|c| {
+                rules
+                    .number
+                    .find_iter(&c[1])
+                    .filter_map(|n| n.as_str().parse().ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        out.push(NarrativeEvent {
+            kind: EventKind::Volcanotectonic {
+                count: count.max(times.len() as u32),
+                times,
+                magnitudes,
+            },
+            span: whole,
+        });
+    }
+
+    if let Some(explosion) =
+        explosion.filter(|m| !hypothetical && !negated(rules, sentence, m.start()))
+    {
+        let count = rules
+            .explosion_count
+            .captures(sentence)
+            .and_then(|c| count_word(&c[1]))
+            .unwrap_or(1);
+        // In a sentence that also reports earthquakes, only times written
+        // after the word "explosión" are the explosion's.
+        let scope = match vt {
+            Some(vt) if vt.start() > explosion.start() => &sentence[explosion.start()..vt.start()],
+            Some(_) => &sentence[explosion.start()..],
+            None => sentence,
+        };
+        let times = times_in(rules, scope);
+        out.push(NarrativeEvent {
+            kind: EventKind::Explosion {
+                count: count.max(times.len() as u32),
+                times,
+                intensity: intensity(&sentence[explosion.start()..]),
+            },
+            span: whole,
+        });
+    }
+
+    for m in rules.column.captures_iter(sentence) {
+        let Some(value) = parse_number(&m[1]) else {
+            continue;
+        };
+        let unit = m[2].to_lowercase();
+        let height_km = if unit == "m" || unit == "metros" {
+            value / 1000.0
+        } else {
+            value
+        };
+        let whole = m.get(0).unwrap();
+        out.push(NarrativeEvent {
+            kind: EventKind::AshColumn { height_km },
+            span: span(whole.start(), whole.end()),
+        });
+    }
+
+    if let Some(m) = rules
+        .fragments
+        .find(sentence)
+        .filter(|m| !hypothetical && !negated(rules, sentence, m.start()))
+    {
+        out.push(NarrativeEvent {
+            kind: EventKind::IncandescentFragments,
+            span: span(m.start(), m.end()),
+        });
+    }
+
+    for m in rules.media.captures_iter(sentence) {
+        let kind = if m[1].eq_ignore_ascii_case("imagen") {
+            MediaKind::Image
+        } else {
+            MediaKind::Video
+        };
+        let Ok(number) = m[2].parse() else {
+            continue;
+        };
+        let whole = m.get(0).unwrap();
+        out.push(NarrativeEvent {
+            kind: EventKind::Media { kind, number },
+            span: span(whole.start(), whole.end()),
+        });
+    }
+}
+
+/// Whether a negation ("no se registraron", "sin") shortly precedes `at`.
+/// Looking only a few words back keeps "sin embargo, se registró una
+/// explosión" and "sin visibilidad… una explosión" from cancelling the event.
+fn negated(rules: &Rules, sentence: &str, at: usize) -> bool {
+    let mut from = at.saturating_sub(30);
+    while !sentence.is_char_boundary(from) {
+        from -= 1;
+    }
+    let window = sentence[from..at].to_lowercase().replace("sin embargo", "");
+    rules.negation.is_match(&window)
+}
+
+fn times_in(rules: &Rules, text: &str) -> Vec<NaiveTime> {
+    rules
+        .times
+        .captures_iter(text)
+        .flat_map(|c| {
+            rules
+                .clock
+                .captures_iter(c.get(1).unwrap().as_str())
+                .filter_map(|t| NaiveTime::from_hms_opt(t[1].parse().ok()?, t[2].parse().ok()?, 0))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn intensity(sentence: &str) -> Option<Intensity> {
+    let lower = sentence.to_lowercase();
+    if lower.contains("mayor") || lower.contains("grande") {
+        Some(Intensity::Major)
+    } else if lower.contains("moderad") {
+        Some(Intensity::Moderate)
+    } else if lower.contains("menor") || lower.contains("pequeñ") {
+        Some(Intensity::Minor)
+    } else {
+        None
+    }
+}
+
+fn count_word(word: &str) -> Option<u32> {
+    let n = match word.to_lowercase().as_str() {
+        "un" | "una" => 1,
+        "dos" => 2,
+        "tres" => 3,
+        "cuatro" => 4,
+        "cinco" => 5,
+        "seis" => 6,
+        "siete" => 7,
+        "ocho" => 8,
+        "nueve" => 9,
+        "diez" => 10,
+        digits => return digits.parse().ok(),
+    };
+    Some(n)
+}
+
+fn parse_number(text: &str) -> Option<f64> {
+    text.replace(',', ".").parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    fn kinds(summary: &str) -> Vec<EventKind> {
+        extract(summary).into_iter().map(|e| e.kind).collect()
+    }
+
+    #[test]
+    fn explosion_with_time_column_and_fragments() {
+        let summary = "Mayo 3°, 11:00 h (Mayo 3°, 16:00 GMT) Se identificaron 85 exhalaciones. Además se registró una explosión menor a las 11:02 h del día de ayer que genero una columna de 1000 m (imagen 2), y una moderada hoy a las 01:35 h, que arrojo fragmentos incandescentes que cayeron sobre la ladera del volcán (vídeo 2).";
+        let events = extract(summary);
+
+        assert_eq!(
+            events[0].kind,
+            EventKind::Explosion {
+                count: 2,
+                times: vec![time(11, 2), time(1, 35)],
+                intensity: Some(Intensity::Moderate),
+            }
+        );
+        assert!(kinds(summary).contains(&EventKind::AshColumn { height_km: 1.0 }));
+        assert!(kinds(summary).contains(&EventKind::IncandescentFragments));
+        assert!(kinds(summary).contains(&EventKind::Media {
+            kind: MediaKind::Video,
+            number: 2
+        }));
+    }
+
+    #[test]
+    fn report_timestamp_is_not_an_event_time() {
+        let k = kinds(
+            "Diciembre 10, 11:00 h (Diciembre 10, 17:00 GMT) Se registró una explosión menor.",
+        );
+        assert_eq!(
+            k,
+            vec![EventKind::Explosion {
+                count: 1,
+                times: vec![],
+                intensity: Some(Intensity::Minor),
+            }]
+        );
+    }
+
+    #[test]
+    fn volcanotectonic_times_and_magnitudes() {
+        let k = kinds("Adicionalmente, ayer ocurrieron 3 sismos volcanotectónicos a las 12:24, 19:31 y 19:32 h, con magnitud calculada de 2.1, 1.5 y 1.1, respectivamente (imagen 2)");
+        assert_eq!(
+            k[0],
+            EventKind::Volcanotectonic {
+                count: 3,
+                times: vec![time(12, 24), time(19, 31), time(19, 32)],
+                magnitudes: vec![2.1, 1.5, 1.1],
+            }
+        );
+    }
+
+    #[test]
+    fn ash_column_in_kilometres() {
+        let k =
+            kinds("Se registró una explosión que generó una columna eruptiva de 1.5 km de altura.");
+        assert!(k.contains(&EventKind::AshColumn { height_km: 1.5 }));
+    }
+
+    /// The scenario list mentions explosions and fragments every day; they
+    /// are possibilities, not observations. The radius still counts.
+    #[test]
+    fn scenario_bullets_are_not_activity() {
+        let summary = "Se detectaron 6 exhalaciones.\n\nEl Semáforo de Alerta Volcánica del Popocatépetl se encuentra en Amarillo Fase 2.\n\nAlgunas explosiones de tamaño menor a moderado\n\nExpulsión de fragmentos incandescentes dentro del radio de exclusión de 12 kilómetros";
+        let events = extract(summary);
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, EventKind::ExclusionRadius { km: 12.0 });
+        assert_eq!(
+            events[0].source(summary),
+            "radio de exclusión de 12 kilómetros"
+        );
+    }
+
+    #[test]
+    fn negated_mentions_are_ignored() {
+        assert!(kinds("No se registraron explosiones.").is_empty());
+        assert!(kinds("Sin explosiones ni fragmentos incandescentes.").is_empty());
+    }
+
+    #[test]
+    fn warnings_about_possible_explosions_are_ignored() {
+        assert!(kinds("El CENAPRED reitera la recomendación de no ascender al cráter, toda vez que existe la posibilidad de que ocurran explosiones que implican la emisión de fragmentos incandescentes.").is_empty());
+    }
+
+    #[test]
+    fn radius_is_reported_once_per_value() {
+        let k = kinds("Continuar con el radio de seguridad de 12 km. Respetar el radio de exclusión de 12 km.");
+        assert_eq!(k, vec![EventKind::ExclusionRadius { km: 12.0 }]);
+    }
+
+    #[test]
+    fn spans_point_into_the_original_text() {
+        let summary = "Reporte del monitoreo del Cenapred al volcán Popocatépetl 22 de Agosto de 2026\n\nSe detectaron 6 exhalaciones (imagen 1),(imagen 2) (video 1).";
+        let events = extract(summary);
+        let sources: Vec<_> = events.iter().map(|e| e.source(summary)).collect();
+        assert_eq!(sources, vec!["(imagen 1)", "(imagen 2)", "(video 1)"]);
+    }
+}
@@ -0,0 +1,263 @@
+//! Map layers for GIS tools.
+//!
+//! Each report becomes up to three kinds of feature: the crater as a point,
+//! the plume as a wedge drawn from the crater along `wind_direction`, and one
+//! point per resolved ashfall place. Every feature carries the report's date,
+//! alert level and counters as properties, so a range of days can be loaded
+//! as one layer and filtered or animated by date. Ashfall names that do not
+//! resolve have no position and are left out.
+
+use crate::downwind::Sector;
+use crate::geo::{Coordinates, CRATER};
+use crate::models::{AlertLevel, VolcanoReport, WindDirection};
+use chrono::NaiveDate;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fmt::Write;
+
+/// Arc segments used to draw the far edge of a plume wedge.
+const ARC_STEPS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Layer {
+    Crater,
+    Plume,
+    Ashfall,
+}
+
+/// What every feature knows about the report it came from.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Properties {
+    pub layer: Layer,
+    pub name: String,
+    pub date: NaiveDate,
+    pub alert_level: Option<AlertLevel>,
+    pub alert_phase: Option<String>,
+    pub wind_direction: Option<WindDirection>,
+    pub exhalations: Option<u32>,
+    pub volcanotectonic_events: Option<u32>,
+    pub tremor_minutes_total: Option<u32>,
+    pub explosions: Option<u32>,
+    pub so2_emissions_tons_per_day: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Geometry {
+    Point(Coordinates),
+    /// A closed ring, first point repeated last, counter-clockwise.
+    Polygon(Vec<Coordinates>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Feature {
+    pub geometry: Geometry,
+    pub properties: Properties,
+}
+
+/// Every feature for `reports`, with plumes drawn as `sector`.
+pub fn features(reports: &[VolcanoReport], sector: &Sector) -> Vec<Feature> {
+    let mut features = Vec::new();
+    for report in reports {
+        let props = |layer, name: &str| Properties {
+            layer,
+            name: name.to_string(),
+            date: report.date,
+            alert_level: report.alert_level,
+            alert_phase: report.alert_phase.clone(),
+            wind_direction: report.wind_direction,
+            exhalations: report.exhalations,
+            volcanotectonic_events: report.volcanotectonic_events,
+            tremor_minutes_total: report.tremor_minutes_total,
+            explosions: report.explosions,
+            so2_emissions_tons_per_day: report.so2_emissions_tons_per_day,
+        };
+
+        features.push(Feature {
+            geometry: Geometry::Point(CRATER),
+            properties: props(Layer::Crater, "Popocatépetl"),
+        });
+        if let Some(dir) = report.wind_direction {
+            features.push(Feature {
+                geometry: Geometry::Polygon(plume(dir, sector)),
+                properties: props(Layer::Plume, &format!("Plume heading {:?}", dir)),
+            });
+        }
+        for place in report.ashfall_places().resolved {
+            features.push(Feature {
+                geometry: Geometry::Point(place.coordinates),
+                properties: props(Layer::Ashfall, &place.canonical),
+            });
+        }
+    }
+    features
+}
+
+/// The wedge a plume heading `direction` covers: out from the crater, round
+/// the far arc, and back.
+pub fn plume(direction: WindDirection, sector: &Sector) -> Vec<Coordinates> {
+    let half = sector.width_deg / 2.0;
+    // Bearings run clockwise, so walk the arc from the larger bearing down to
+    // keep the ring counter-clockwise as RFC 7946 asks.
+    let arc = (0..=ARC_STEPS).map(|i| {
+        let bearing = direction.degrees() + half - sector.width_deg * i as f64 / ARC_STEPS as f64;
+        CRATER.destination(bearing, sector.max_km)
+    });
+    std::iter::once(CRATER)
+        .chain(arc)
+        .chain(std::iter::once(CRATER))
+        .collect()
+}
+
+/// A GeoJSON `FeatureCollection`.
+pub fn to_geojson(features: &[Feature]) -> Value {
+    let position = |c: &Coordinates| json!([round(c.lon), round(c.lat)]);
+    let features: Vec<Value> = features
+        .iter()
+        .map(|f| {
+            let geometry = match &f.geometry {
+                Geometry::Point(c) => json!({"type": "Point", "coordinates": position(c)}),
+                Geometry::Polygon(ring) => json!({
+                    "type": "Polygon",
+                    "coordinates": [ring.iter().map(position).collect::<Vec<_>>()],
+                }),
+            };
+            json!({
+                "type": "Feature",
+                "geometry": geometry,
+                "properties": f.properties,
+            })
+        })
+        .collect();
+    json!({"type": "FeatureCollection", "features": features})
+}
+
+/// A KML document with one placemark per feature. Properties go in
+/// `ExtendedData` and the date in a `TimeStamp`, which Google Earth and QGIS
+/// both use for their time sliders.
+pub fn to_kml(features: &[Feature]) -> String {
+    let mut kml = String::new();
+    kml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    kml.push_str("<kml xmlns=\"http://www.opengis.net/kml/2.2\">\n<Document>\n");
+    kml.push_str("<name>Popocatépetl</name>\n");
+
+    for feature in features {
+        let props = &feature.properties;
+        kml.push_str("<Placemark>\n");
+        let _ = writeln!(kml, "<name>{}</name>", escape(&props.name));
+        let _ = writeln!(kml, "<TimeStamp><when>{}</when></TimeStamp>", props.date);
+        kml.push_str("<ExtendedData>\n");
+        if let Value::Object(fields) = json!(props) {
+            for (key, value) in fields {
+                let value = match value {
+                    Value::Null => continue,
+                    Value::String(s) => s,
+                    other => other.to_string(),
+                };
+                let _ = writeln!(
+                    kml,
+                    "<Data name=\"{}\"><value>{}</value></Data>",
+                    key,
+                    escape(&value)
+                );
+            }
+        }
+        kml.push_str("</ExtendedData>\n");
+        match &feature.geometry {
+            Geometry::Point(c) => {
+                let _ = writeln!(
+                    kml,
+                    "<Point><coordinates>{}</coordinates></Point>",
+                    kml_position(c)
+                );
+            }
+            Geometry::Polygon(ring) => {
+                let ring: Vec<String> = ring.iter().map(kml_position).collect();
+                let _ = writeln!(
+                    kml,
+                    "<Polygon><outerBoundaryIs><LinearRing><coordinates>{}</coordinates></LinearRing></outerBoundaryIs></Polygon>",
+                    ring.join(" ")
+                );
+            }
+        }
+        kml.push_str("</Placemark>\n");
+    }
+
+    kml.push_str("</Document>\n</kml>\n");
+    kml
+}
+
+fn kml_position(c: &Coordinates) -> String {
+    format!("{},{}", round(c.lon), round(c.lat))
+}
+
+/// Six decimal places is about 10 cm, far finer than the gazetteer.
+fn round(degrees: f64) -> f64 {
+    (degrees * 1e6).round() / 1e6
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> VolcanoReport {
+        let mut report = VolcanoReport::blank("2023-05-21");
+        report.alert_level = Some(AlertLevel::Yellow);
+        report.exhalations = Some(42);
+        report.wind_direction = Some(WindDirection::E);
+        report.ashfall_reports = vec!["San Pedro Cholula y Xoxocotla".into()];
+        report
+    }
+
+    #[test]
+    fn one_crater_one_plume_and_each_resolved_place() {
+        let features = features(&[report()], &Sector::default());
+        let layers: Vec<_> = features.iter().map(|f| f.properties.layer).collect();
+        assert_eq!(layers, vec![Layer::Crater, Layer::Plume, Layer::Ashfall]);
+        assert_eq!(features[2].properties.name, "San Pedro Cholula, Puebla");
+        assert!(features
+            .iter()
+            .all(|f| f.properties.exhalations == Some(42)));
+    }
+
+    #[test]
+    fn plume_is_a_closed_counter_clockwise_wedge() {
+        let ring = plume(WindDirection::E, &Sector::default());
+        assert_eq!(ring.first(), ring.last());
+        assert_eq!(ring.len(), ARC_STEPS + 3);
+        // Heading east, the ring goes out south of east and comes back north.
+        assert!(ring[1].lat < CRATER.lat && ring[ARC_STEPS + 1].lat > CRATER.lat);
+        assert!((CRATER.distance_km(ring[1]) - 100.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn geojson_puts_longitude_first() {
+        let geojson = to_geojson(&features(&[report()], &Sector::default()));
+        let crater = &geojson["features"][0];
+        assert_eq!(
+            crater["geometry"]["coordinates"],
+            json!([-98.6278, 19.0225])
+        );
+        assert_eq!(crater["properties"]["alert_level"], "YELLOW");
+        assert_eq!(crater["properties"]["date"], "2023-05-21");
+    }
+
+    #[test]
+    fn kml_escapes_names_and_skips_missing_values() {
+        let mut report = report();
+        report.wind_direction = None;
+        let kml = to_kml(&features(&[report], &Sector::default()));
+        assert!(kml.contains("<when>2023-05-21</when>"));
+        assert!(kml.contains("<Data name=\"exhalations\"><value>42</value></Data>"));
+        assert!(!kml.contains("name=\"explosions\""));
+        assert!(!kml.contains("<Polygon>"));
+        assert_eq!(escape("A & <B>"), "A &amp; &lt;B&gt;");
+    }
+}
@@ -2,6 +2,7 @@ use crate::error::{PopoError, Result};
 use crate::models::{FeedIndex, VolcanoReport, SCHEMA_VERSION};
 use chrono::NaiveDate;
 use serde::de::DeserializeOwned;
+use serde::Deserialize;
 
 /// Where the published JSON lives by default.
 ///
@@ -63,6 +64,18 @@ impl Feed {
         Ok(report)
     }
 
+    /// The report for a specific day along with the JSON it was read from,
+    /// which keeps list order and any field this version does not model.
+    pub fn get_with_json(&self, date: NaiveDate) -> Result<(VolcanoReport, serde_json::Value)> {
+        let path = format!("reports/{}/{}.json", date.format("%Y"), date);
+        let json: serde_json::Value = self.fetch(&path, Some(date))?;
+        let report = VolcanoReport::deserialize(&json).map_err(|e| {
+            PopoError::Parse(format!("feed returned malformed JSON for {}: {}", path, e))
+        })?;
+        check_schema(report.schema_version)?;
+        Ok((report, json))
+    }
+
     /// Everything the feed currently carries.
     pub fn index(&self) -> Result<FeedIndex> {
         let index: FeedIndex = self.fetch("index.json", None)?;
@@ -70,6 +83,22 @@ impl Feed {
         Ok(index)
     }
 
+    /// Every report the index lists between `from` and `to`, inclusive, in
+    /// date order. Partial records are included; callers decide whether
+    /// counters alone are enough for what they are computing.
+    pub fn range(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<VolcanoReport>> {
+        if from > to {
+            return Err(PopoError::InvalidRange { from, to });
+        }
+        let index = self.index()?;
+        index
+            .dates
+            .iter()
+            .filter(|date| (from..=to).contains(*date))
+            .map(|date| self.get(*date))
+            .collect()
+    }
+
     fn fetch<T: DeserializeOwned>(&self, path: &str, date: Option<NaiveDate>) -> Result<T> {
         let body = if self.is_remote() {
             self.fetch_http(path, date)?
@@ -101,6 +130,13 @@ impl Feed {
             });
         }
 
+        // The feed is up but failing, which is as much an outage as a timeout.
+        if status.is_server_error() {
+            if let Err(err) = response.error_for_status_ref() {
+                return Err(PopoError::Network(err));
+            }
+        }
+
         if !status.is_success() {
             return Err(PopoError::Feed(format!(
                 "feed request to {} failed with HTTP {}",
@@ -256,6 +292,24 @@ mod tests {
         assert!(matches!(feed.latest(), Err(PopoError::Parse(_))));
     }
 
+    /// `popo watch` keeps polling through these, and stops on the rest.
+    #[test]
+    fn half_written_and_missing_files_are_transient() {
+        let dir = temp_dir("transient");
+        let feed = Feed::with_base(dir.to_str().unwrap());
+        assert!(feed.latest().unwrap_err().is_transient());
+
+        fs::write(dir.join("latest.json"), r#"{"date": "2026-08-"#).unwrap();
+        assert!(feed.latest().unwrap_err().is_transient());
+
+        fs::write(
+            dir.join("latest.json"),
+            r#"{"schema_version": 99, "date": "2026-08-04"}"#,
+        )
+        .unwrap();
+        assert!(!feed.latest().unwrap_err().is_transient());
+    }
+
     #[test]
     fn base_url_trailing_slash_is_normalized() {
         assert_eq!(
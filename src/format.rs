@@ -0,0 +1,542 @@
+//! Machine-readable output shared by every command.
+//!
+//! A command builds one serialisable value and hands it here. JSON and YAML
+//! show it whole. The row-based formats (CSV, Markdown, a plain-text table, and
+//! NDJSON with one object per line) show the rows the value's [`Tabular`]
+//! implementation lists, under the columns it names, so the columns stay the
+//! same whatever the data holds, even when there are no rows. In a row,
+//! nested fields are named by dotted paths and lists of plain values are
+//! joined with "; ".
+
+use crate::error::{PopoError, Result};
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    /// Each command's own layout for people.
+    #[default]
+    Human,
+    Json,
+    JsonCompact,
+    Ndjson,
+    Yaml,
+    Csv,
+    Markdown,
+    Table,
+    /// Map layers, which only `popo export` writes.
+    Geojson,
+    Kml,
+}
+
+impl Format {
+    pub const ALL: [Format; 10] = [
+        Format::Human,
+        Format::Json,
+        Format::JsonCompact,
+        Format::Ndjson,
+        Format::Yaml,
+        Format::Csv,
+        Format::Markdown,
+        Format::Table,
+        Format::Geojson,
+        Format::Kml,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::Human => "human",
+            Format::Json => "json",
+            Format::JsonCompact => "json-compact",
+            Format::Ndjson => "ndjson",
+            Format::Yaml => "yaml",
+            Format::Csv => "csv",
+            Format::Markdown => "markdown",
+            Format::Table => "table",
+            Format::Geojson => "geojson",
+            Format::Kml => "kml",
+        }
+    }
+
+    /// Whether this is one of the map formats of `popo export`.
+    pub fn is_map(self) -> bool {
+        matches!(self, Format::Geojson | Format::Kml)
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(text: &str) -> std::result::Result<Self, String> {
+        let text = text.trim().to_lowercase();
+        Format::ALL
+            .into_iter()
+            .find(|f| f.name() == text || (text == "md" && *f == Format::Markdown))
+            .ok_or_else(|| {
+                let names: Vec<&str> = Format::ALL.iter().map(|f| f.name()).collect();
+                format!("expected one of {}", names.join(", "))
+            })
+    }
+}
+
+/// How the row-based formats lay out a value.
+pub trait Tabular: Serialize {
+    type Row<'a>: Serialize
+    where
+        Self: 'a;
+
+    /// The fields of a row that become columns, as dotted paths. A row
+    /// without one of them leaves its cell blank.
+    const COLUMNS: &'static [&'static str];
+
+    /// Whether the value is one record rather than a list, which Markdown and
+    /// the table show one field per line.
+    const RECORD: bool = false;
+
+    fn rows(&self) -> Vec<Self::Row<'_>>;
+}
+
+impl<T: Tabular> Tabular for [T] {
+    type Row<'a>
+        = T::Row<'a>
+    where
+        T: 'a;
+
+    const COLUMNS: &'static [&'static str] = T::COLUMNS;
+
+    fn rows(&self) -> Vec<Self::Row<'_>> {
+        self.iter().flat_map(T::rows).collect()
+    }
+}
+
+impl<T: Tabular> Tabular for Vec<T> {
+    type Row<'a>
+        = T::Row<'a>
+    where
+        T: 'a;
+
+    const COLUMNS: &'static [&'static str] = T::COLUMNS;
+
+    fn rows(&self) -> Vec<Self::Row<'_>> {
+        self.as_slice().rows()
+    }
+}
+
+impl<T: Tabular + ?Sized> Tabular for &T {
+    type Row<'a>
+        = T::Row<'a>
+    where
+        Self: 'a;
+
+    const COLUMNS: &'static [&'static str] = T::COLUMNS;
+    const RECORD: bool = T::RECORD;
+
+    fn rows(&self) -> Vec<Self::Row<'_>> {
+        (**self).rows()
+    }
+}
+
+/// `value` in `format`, ending with a newline. `Human` has no generic
+/// rendering and falls back to JSON, as do the map formats, which the CLI only
+/// accepts for `popo export`.
+pub fn render<T: Tabular + ?Sized>(value: &T, format: Format) -> Result<String> {
+    let json = |v: &Value, pretty: bool| {
+        let text = if pretty {
+            serde_json::to_string_pretty(v)
+        } else {
+            serde_json::to_string(v)
+        };
+        text.map_err(|e| PopoError::Serialize(e.to_string()))
+    };
+    let rows = || -> Result<Vec<Value>> {
+        value
+            .rows()
+            .iter()
+            .map(|row| serde_json::to_value(row).map_err(|e| PopoError::Serialize(e.to_string())))
+            .collect()
+    };
+    let grid = || -> Result<Grid> { Ok(Grid::new(T::COLUMNS, &rows()?, T::RECORD)) };
+
+    Ok(match format {
+        Format::Ndjson => {
+            let mut out = String::new();
+            for row in rows()? {
+                out.push_str(&json(&row, false)?);
+                out.push('\n');
+            }
+            out
+        }
+        Format::Csv => csv(&grid()?),
+        Format::Markdown => markdown(&grid()?),
+        Format::Table => table(&grid()?),
+        whole => {
+            let value =
+                serde_json::to_value(value).map_err(|e| PopoError::Serialize(e.to_string()))?;
+            match whole {
+                Format::JsonCompact => json(&value, false)? + "\n",
+                Format::Yaml => {
+                    let mut out = String::new();
+                    write_yaml(&value, 0, &mut out);
+                    out
+                }
+                _ => json(&value, true)? + "\n",
+            }
+        }
+    })
+}
+
+/// Rows of cells under a fixed set of columns.
+struct Grid {
+    columns: Vec<String>,
+    rows: Vec<Vec<String>>,
+    /// The value was a single record, and reads better as one field per line.
+    single: bool,
+}
+
+impl Grid {
+    fn new(columns: &[&str], rows: &[Value], single: bool) -> Self {
+        let rows = rows
+            .iter()
+            .map(|row| {
+                columns
+                    .iter()
+                    .map(|path| field(row, path).map(cell).unwrap_or_default())
+                    .collect()
+            })
+            .collect();
+        Grid {
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            rows,
+            single,
+        }
+    }
+}
+
+/// The field at a dotted `path` within `row`.
+fn field<'a>(row: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(row, |value, key| value.get(key))
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) if !items.iter().any(|v| v.is_object() || v.is_array()) => {
+            items.iter().map(cell).collect::<Vec<_>>().join("; ")
+        }
+        other => other.to_string(),
+    }
+}
+
+fn csv(grid: &Grid) -> String {
+    let quote = |s: &str| {
+        if s.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", s.replace('"', "\"\""))
+        } else {
+            s.to_string()
+        }
+    };
+    let mut out = String::new();
+    for line in std::iter::once(&grid.columns).chain(&grid.rows) {
+        let line: Vec<String> = line.iter().map(|c| quote(c)).collect();
+        out.push_str(&line.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// The grid as rows of text, one field per line for a single object.
+fn lines(grid: &Grid) -> (Vec<String>, Vec<Vec<String>>) {
+    if grid.single {
+        let values = grid.rows.first().cloned().unwrap_or_default();
+        let rows = grid
+            .columns
+            .iter()
+            .cloned()
+            .zip(values)
+            .map(|(k, v)| vec![k, v])
+            .collect();
+        (vec!["field".to_string(), "value".to_string()], rows)
+    } else {
+        (grid.columns.clone(), grid.rows.clone())
+    }
+}
+
+fn markdown(grid: &Grid) -> String {
+    let (header, rows) = lines(grid);
+    if header.is_empty() {
+        return String::new();
+    }
+    let escape = |s: &str| s.replace('|', "\\|").replace('\n', "<br>");
+    let row = |cells: &[String]| {
+        let cells: Vec<String> = cells.iter().map(|c| escape(c)).collect();
+        format!("| {} |\n", cells.join(" | "))
+    };
+    let mut out = row(&header);
+    out.push_str(&format!("|{}\n", " --- |".repeat(header.len())));
+    for cells in &rows {
+        out.push_str(&row(cells));
+    }
+    out
+}
+
+fn table(grid: &Grid) -> String {
+    let (header, rows) = lines(grid);
+    if header.is_empty() {
+        return String::new();
+    }
+    let rows: Vec<Vec<String>> = rows
+        .into_iter()
+        .map(|r| {
+            r.into_iter()
+                .map(|c| c.replace(['\n', '\r'], " "))
+                .collect()
+        })
+        .collect();
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            std::iter::once(&header)
+                .chain(&rows)
+                .map(|r| r[i].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let line = |cells: &[String]| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(c, w)| format!("{}{}", c, " ".repeat(w - c.chars().count())))
+            .collect();
+        padded.join("  ").trim_end().to_string() + "\n"
+    };
+    let mut out = line(&header);
+    let rules: Vec<String> = widths.iter().map(|w| "─".repeat(*w)).collect();
+    out.push_str(&line(&rules));
+    for cells in &rows {
+        out.push_str(&line(cells));
+    }
+    out
+}
+
+fn write_yaml(value: &Value, indent: usize, out: &mut String) {
+    let pad = " ".repeat(indent);
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, inner) in map {
+                out.push_str(&pad);
+                out.push_str(&yaml_key(key));
+                out.push(':');
+                write_yaml_child(inner, indent, out);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            for item in items {
+                if is_collection(item) {
+                    // "- " takes the place of the nested block's indent.
+                    let mut nested = String::new();
+                    write_yaml(item, indent + 2, &mut nested);
+                    out.push_str(&pad);
+                    out.push_str("- ");
+                    out.push_str(&nested[indent + 2..]);
+                } else {
+                    out.push_str(&pad);
+                    out.push_str("- ");
+                    out.push_str(&yaml_scalar(item));
+                    out.push('\n');
+                }
+            }
+        }
+        scalar => {
+            out.push_str(&pad);
+            out.push_str(&yaml_scalar(scalar));
+            out.push('\n');
+        }
+    }
+}
+
+fn write_yaml_child(value: &Value, indent: usize, out: &mut String) {
+    if is_collection(value) {
+        out.push('\n');
+        write_yaml(value, indent + 2, out);
+    } else {
+        out.push(' ');
+        out.push_str(&yaml_scalar(value));
+        out.push('\n');
+    }
+}
+
+fn is_collection(value: &Value) -> bool {
+    match value {
+        Value::Object(map) => !map.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        _ => false,
+    }
+}
+
+/// Strings are written as JSON strings, which YAML reads as double-quoted
+/// scalars, so no text can be mistaken for a number, date or boolean.
+fn yaml_scalar(value: &Value) -> String {
+    match value {
+        Value::Object(_) => "{}".to_string(),
+        Value::Array(_) => "[]".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn yaml_key(key: &str) -> String {
+    if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        key.to_string()
+    } else {
+        Value::String(key.to_string()).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Scan {
+        scanned: usize,
+        hits: Vec<Hit>,
+    }
+
+    #[derive(Serialize)]
+    struct Hit {
+        date: &'static str,
+        towns: Vec<&'static str>,
+        note: Option<&'static str>,
+    }
+
+    impl Tabular for Scan {
+        type Row<'a> = &'a Hit;
+
+        const COLUMNS: &'static [&'static str] = &["date", "towns", "note"];
+
+        fn rows(&self) -> Vec<&Hit> {
+            self.hits.iter().collect()
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Rose {
+        date: &'static str,
+        rose: Observed,
+    }
+
+    #[derive(Serialize)]
+    struct Observed {
+        observed: u32,
+    }
+
+    impl Tabular for Rose {
+        type Row<'a> = &'a Rose;
+
+        const COLUMNS: &'static [&'static str] = &["date", "rose.observed"];
+        const RECORD: bool = true;
+
+        fn rows(&self) -> Vec<&Rose> {
+            vec![self]
+        }
+    }
+
+    fn sample() -> Scan {
+        Scan {
+            scanned: 2,
+            hits: vec![
+                Hit {
+                    date: "2023-05-20",
+                    towns: vec!["Atlixco", "Amecameca"],
+                    note: Some("a, \"b\""),
+                },
+                Hit {
+                    date: "2023-05-21",
+                    towns: vec![],
+                    note: None,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for format in Format::ALL {
+            assert_eq!(format.name().parse::<Format>(), Ok(format));
+        }
+        assert_eq!("MD".parse::<Format>(), Ok(Format::Markdown));
+        assert!("xml".parse::<Format>().is_err());
+    }
+
+    #[test]
+    fn row_formats_use_the_listed_rows() {
+        let csv = render(&sample(), Format::Csv).unwrap();
+        assert_eq!(
+            csv,
+            "date,towns,note\n2023-05-20,Atlixco; Amecameca,\"a, \"\"b\"\"\"\n2023-05-21,,\n"
+        );
+        let ndjson = render(&sample(), Format::Ndjson).unwrap();
+        assert_eq!(ndjson.lines().count(), 2);
+        assert!(ndjson.starts_with("{\"date\":\"2023-05-20\""));
+    }
+
+    #[test]
+    fn columns_do_not_depend_on_the_rows() {
+        let empty = Scan {
+            scanned: 0,
+            hits: Vec::new(),
+        };
+        assert_eq!(render(&empty, Format::Csv).unwrap(), "date,towns,note\n");
+        assert_eq!(render(&empty, Format::Ndjson).unwrap(), "");
+        assert_eq!(
+            render(&empty, Format::Markdown).unwrap(),
+            "| date | towns | note |\n| --- | --- | --- |\n"
+        );
+    }
+
+    #[test]
+    fn a_single_record_reads_as_fields() {
+        let value = Rose {
+            date: "2023-05-20",
+            rose: Observed { observed: 3 },
+        };
+        assert_eq!(
+            render(&value, Format::Markdown).unwrap(),
+            "| field | value |\n| --- | --- |\n| date | 2023-05-20 |\n| rose.observed | 3 |\n"
+        );
+        assert_eq!(
+            render(&value, Format::Table).unwrap(),
+            "field          value\n─────────────  ──────────\ndate           2023-05-20\nrose.observed  3\n"
+        );
+        assert_eq!(
+            render(&value, Format::Csv).unwrap(),
+            "date,rose.observed\n2023-05-20,3\n"
+        );
+    }
+
+    #[test]
+    fn yaml_quotes_strings_and_nests_lists() {
+        let yaml = render(&sample(), Format::Yaml).unwrap();
+        assert_eq!(
+            yaml,
+            "scanned: 2\n\
+             hits:\n  \
+               - date: \"2023-05-20\"\n    \
+                 towns:\n      \
+                   - \"Atlixco\"\n      \
+                   - \"Amecameca\"\n    \
+                 note: \"a, \\\"b\\\"\"\n  \
+               - date: \"2023-05-21\"\n    \
+                 towns: []\n    \
+                 note: null\n"
+        );
+    }
+}
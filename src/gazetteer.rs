@@ -0,0 +1,285 @@
+//! Municipalities around the volcano, bundled so place names can be resolved
+//! offline.
+//!
+//! Coverage is the municipalities on the volcano's slopes, the towns and
+//! cities around it where ash usually falls, and the handful further out that
+//! CENAPRED has named in an ashfall report. It is not every municipality within
+//! some distance of the crater, so a name may not resolve; callers keep it as
+//! written. Positions are those of the municipal seat, to about a kilometre.
+
+use crate::geo::Coordinates;
+use crate::text::fold;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The states (and Mexico City) that ash from Popocatépetl reaches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum State {
+    #[serde(rename = "Estado de México")]
+    Mexico,
+    Puebla,
+    Morelos,
+    Tlaxcala,
+    #[serde(rename = "Ciudad de México")]
+    MexicoCity,
+}
+
+impl State {
+    pub const ALL: [State; 5] = [
+        State::Mexico,
+        State::Puebla,
+        State::Morelos,
+        State::Tlaxcala,
+        State::MexicoCity,
+    ];
+
+    /// The name as CENAPRED writes it.
+    pub fn name(self) -> &'static str {
+        match self {
+            State::Mexico => "Estado de México",
+            State::Puebla => "Puebla",
+            State::Morelos => "Morelos",
+            State::Tlaxcala => "Tlaxcala",
+            State::MexicoCity => "Ciudad de México",
+        }
+    }
+
+    /// Recognise a state name, with or without accents and the "Estado de"
+    /// that sometimes precedes it.
+    pub fn from_spanish(text: &str) -> Option<Self> {
+        let folded = fold(text.trim());
+        let name = folded
+            .strip_prefix("estado de ")
+            .unwrap_or(&folded)
+            .trim_end_matches('.');
+        match name {
+            "mexico" | "edomex" | "edo. mex" | "edo. de mexico" => Some(State::Mexico),
+            "puebla" => Some(State::Puebla),
+            "morelos" => Some(State::Morelos),
+            "tlaxcala" => Some(State::Tlaxcala),
+            "ciudad de mexico" | "cdmx" => Some(State::MexicoCity),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for State {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// One gazetteer entry.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Municipality {
+    pub name: &'static str,
+    pub state: State,
+    pub coordinates: Coordinates,
+}
+
+impl Municipality {
+    /// "Tetela del Volcán, Morelos".
+    pub fn canonical_name(&self) -> String {
+        format!("{}, {}", self.name, self.state)
+    }
+}
+
+const fn m(name: &'static str, state: State, lat: f64, lon: f64) -> Municipality {
+    Municipality {
+        name,
+        state,
+        coordinates: Coordinates::new(lat, lon),
+    }
+}
+
+/// Every bundled municipality, grouped by state.
+#[rustfmt::skip]
+pub const MUNICIPALITIES: &[Municipality] = &[
+    // Estado de México
+    m("Amecameca", State::Mexico, 19.1239, -98.7664),
+    m("Atlautla", State::Mexico, 19.0297, -98.7803),
+    m("Ayapango", State::Mexico, 19.1264, -98.8028),
+    m("Chalco", State::Mexico, 19.2628, -98.8972),
+    m("Chimalhuacán", State::Mexico, 19.4217, -98.9539),
+    m("Cocotitlán", State::Mexico, 19.2333, -98.8617),
+    m("Ecatzingo", State::Mexico, 18.9556, -98.7517),
+    m("Ixtapaluca", State::Mexico, 19.3186, -98.8822),
+    m("Juchitepec", State::Mexico, 19.1000, -98.8792),
+    m("La Paz", State::Mexico, 19.3600, -98.9800),
+    m("Nezahualcóyotl", State::Mexico, 19.4006, -99.0147),
+    m("Ozumba", State::Mexico, 19.0394, -98.7931),
+    m("Temamatla", State::Mexico, 19.2028, -98.8700),
+    m("Tenango del Aire", State::Mexico, 19.1575, -98.8581),
+    m("Tepetlixpa", State::Mexico, 19.0006, -98.8167),
+    m("Texcoco", State::Mexico, 19.5139, -98.8828),
+    m("Tlalmanalco", State::Mexico, 19.2047, -98.8025),
+    m("Valle de Chalco Solidaridad", State::Mexico, 19.2933, -98.9375),
+    // Puebla
+    m("Acteopan", State::Puebla, 18.7250, -98.5956),
+    m("Atlixco", State::Puebla, 18.9083, -98.4369),
+    m("Atzitzihuacán", State::Puebla, 18.8333, -98.6000),
+    m("Calpan", State::Puebla, 19.1017, -98.4558),
+    m("Chiautzingo", State::Puebla, 19.1944, -98.4772),
+    m("Chichiquila", State::Puebla, 19.2000, -97.1167),
+    m("Chilchotla", State::Puebla, 19.2622, -97.1806),
+    m("Cohuecan", State::Puebla, 18.7767, -98.7158),
+    m("Coronango", State::Puebla, 19.1306, -98.2881),
+    m("Cuautlancingo", State::Puebla, 19.0900, -98.2717),
+    m("Domingo Arenas", State::Puebla, 19.1433, -98.4628),
+    m("Huaquechula", State::Puebla, 18.7681, -98.5428),
+    m("Huejotzingo", State::Puebla, 19.1592, -98.4083),
+    m("Izúcar de Matamoros", State::Puebla, 18.6000, -98.4653),
+    m("Juan C. Bonilla", State::Puebla, 19.1125, -98.3461),
+    m("Nealtican", State::Puebla, 19.0533, -98.4275),
+    m("Ocoyucan", State::Puebla, 18.9522, -98.3094),
+    m("Puebla", State::Puebla, 19.0414, -98.2063),
+    m("Quimixtlán", State::Puebla, 19.2386, -97.1458),
+    m("San Andrés Cholula", State::Puebla, 19.0500, -98.2956),
+    m("San Felipe Teotlalcingo", State::Puebla, 19.2286, -98.5028),
+    m("San Jerónimo Tecuanipan", State::Puebla, 19.0636, -98.3853),
+    m("San Martín Texmelucan", State::Puebla, 19.2847, -98.4344),
+    m("San Nicolás de los Ranchos", State::Puebla, 19.0694, -98.4942),
+    m("San Pedro Cholula", State::Puebla, 19.0633, -98.3064),
+    m("San Salvador el Verde", State::Puebla, 19.2667, -98.5167),
+    m("Santa Isabel Cholula", State::Puebla, 19.0028, -98.3833),
+    m("Tianguismanalco", State::Puebla, 18.9639, -98.4764),
+    m("Tlahuapan", State::Puebla, 19.3425, -98.5567),
+    m("Tochimilco", State::Puebla, 18.8917, -98.5714),
+    // Morelos
+    m("Atlatlahucan", State::Morelos, 18.9347, -98.9000),
+    m("Axochiapan", State::Morelos, 18.5000, -98.7500),
+    m("Ayala", State::Morelos, 18.7700, -98.9800),
+    m("Cuautla", State::Morelos, 18.8122, -98.9547),
+    m("Cuernavaca", State::Morelos, 18.9186, -99.2342),
+    m("Hueyapan", State::Morelos, 18.8869, -98.6903),
+    m("Jantetelco", State::Morelos, 18.7158, -98.7747),
+    m("Jiutepec", State::Morelos, 18.8814, -99.1772),
+    m("Jonacatepec", State::Morelos, 18.6811, -98.8022),
+    m("Ocuituco", State::Morelos, 18.8764, -98.7744),
+    m("Temoac", State::Morelos, 18.7725, -98.7842),
+    m("Tepoztlán", State::Morelos, 18.9850, -99.0997),
+    m("Tetela del Volcán", State::Morelos, 18.8931, -98.7297),
+    m("Tlalnepantla", State::Morelos, 19.0083, -98.9989),
+    m("Tlayacapan", State::Morelos, 18.9553, -98.9811),
+    m("Totolapan", State::Morelos, 18.9867, -98.9200),
+    m("Yautepec", State::Morelos, 18.8867, -99.0617),
+    m("Yecapixtla", State::Morelos, 18.8836, -98.8650),
+    m("Zacualpan de Amilpas", State::Morelos, 18.7836, -98.7561),
+    // Tlaxcala
+    m("Calpulalpan", State::Tlaxcala, 19.5869, -98.5694),
+    m("Nativitas", State::Tlaxcala, 19.2336, -98.3108),
+    m("Tlaxcala", State::Tlaxcala, 19.3139, -98.2403),
+    // Ciudad de México
+    m("Iztapalapa", State::MexicoCity, 19.3553, -99.0622),
+    m("Milpa Alta", State::MexicoCity, 19.1922, -99.0231),
+    m("Tláhuac", State::MexicoCity, 19.2864, -99.0044),
+    m("Tlalpan", State::MexicoCity, 19.2889, -99.1667),
+    m("Xochimilco", State::MexicoCity, 19.2572, -99.1036),
+];
+
+/// Find a municipality by name, ignoring case and accents and tolerating a
+/// slip in spelling ("Chilchota" for Chilchotla). When a state is given,
+/// matches in that state are preferred, and misspellings must be in it.
+pub fn lookup(name: &str, state: Option<State>) -> Option<&'static Municipality> {
+    let wanted = fold(name.trim());
+    if wanted.is_empty() {
+        return None;
+    }
+    // One slip in names of eight letters or more, two from sixteen. A short
+    // name, another first letter or another state is more likely a town the
+    // gazetteer lacks (Acatzingo, not Ecatzingo) than a misspelling.
+    let tolerance = wanted.chars().count() / 8;
+    let first = wanted.chars().next();
+    let close = |m: &Municipality, folded: &str, distance: usize| {
+        distance == 0
+            || (distance <= tolerance
+                && folded.chars().next() == first
+                && state.map_or(true, |s| s == m.state))
+    };
+
+    MUNICIPALITIES
+        .iter()
+        .map(|m| {
+            let folded = fold(m.name);
+            (m, edit_distance(&wanted, &folded), folded)
+        })
+        .filter(|(m, distance, folded)| close(m, folded, *distance))
+        .min_by_key(|(m, distance, _)| (*distance, state.is_some_and(|s| s != m.state)))
+        .map(|(m, _, _)| m)
+}
+
+/// Levenshtein distance over characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geo::CRATER;
+
+    #[test]
+    fn names_are_unique_within_a_state_and_fold_apart() {
+        for (i, a) in MUNICIPALITIES.iter().enumerate() {
+            for b in &MUNICIPALITIES[i + 1..] {
+                assert_ne!(fold(a.name), fold(b.name), "{} twice", a.name);
+            }
+        }
+    }
+
+    #[test]
+    fn every_entry_is_near_the_volcano() {
+        // Chichiquila and friends, on the Puebla-Veracruz border, are the
+        // furthest anyone has reported ash.
+        for m in MUNICIPALITIES {
+            assert!(
+                (m.coordinates.lat - CRATER.lat).abs() < 1.0
+                    && (m.coordinates.lon - CRATER.lon).abs() < 1.6,
+                "{} is out of range",
+                m.name
+            );
+        }
+    }
+
+    #[test]
+    fn lookup_ignores_accents_and_small_misspellings() {
+        let tetela = lookup("Tétela del volcan", None).unwrap();
+        assert_eq!(tetela.canonical_name(), "Tetela del Volcán, Morelos");
+        assert_eq!(lookup("Chilchota", None).unwrap().name, "Chilchotla");
+        assert!(lookup("Guadalajara", None).is_none());
+    }
+
+    #[test]
+    fn unknown_towns_are_not_snapped_onto_similar_names() {
+        assert!(lookup("Acatzingo", None).is_none());
+        assert!(lookup("Acatzingo", Some(State::Puebla)).is_none());
+        assert!(lookup("Ayapa", None).is_none());
+        assert_eq!(lookup("Totolapa", None).unwrap().name, "Totolapan");
+        assert!(lookup("Totolapa", Some(State::Puebla)).is_none());
+        // Exact names still resolve whatever state is given.
+        assert_eq!(lookup("Ocoyucan", None).unwrap().state, State::Puebla);
+        assert_eq!(
+            lookup("juan c. bonilla", Some(State::Mexico)).unwrap().name,
+            "Juan C. Bonilla"
+        );
+    }
+
+    #[test]
+    fn states_are_read_with_or_without_estado_de() {
+        assert_eq!(State::from_spanish("Estado de México"), Some(State::Mexico));
+        assert_eq!(State::from_spanish("estado de Puebla"), Some(State::Puebla));
+        assert_eq!(State::from_spanish("Morelos"), Some(State::Morelos));
+        assert_eq!(State::from_spanish("Amecameca"), None);
+    }
+}
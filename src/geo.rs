@@ -0,0 +1,105 @@
+//! Points on the map around the volcano.
+
+use serde::{Deserialize, Serialize};
+
+/// Mean Earth radius, in kilometres.
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// A WGS84 position in decimal degrees.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Coordinates {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+impl Coordinates {
+    pub const fn new(lat: f64, lon: f64) -> Self {
+        Coordinates { lat, lon }
+    }
+
+    /// Read "19.05,-98.30" (latitude first). `None` unless both numbers are
+    /// present and on the globe.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (lat, lon) = text.split_once(',')?;
+        let (lat, lon) = (lat.trim().parse().ok()?, lon.trim().parse().ok()?);
+        Coordinates::checked(lat, lon)
+    }
+
+    /// `None` when either value is off the globe.
+    pub fn checked(lat: f64, lon: f64) -> Option<Self> {
+        ((-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon))
+            .then_some(Coordinates { lat, lon })
+    }
+
+    /// Great-circle distance to `other`, in kilometres.
+    pub fn distance_km(self, other: Coordinates) -> f64 {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let dlat = lat2 - lat1;
+        let dlon = (other.lon - self.lon).to_radians();
+        let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+    }
+
+    /// Initial compass bearing towards `other`, in degrees clockwise from
+    /// north (0 to 360).
+    pub fn bearing_to(self, other: Coordinates) -> f64 {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let dlon = (other.lon - self.lon).to_radians();
+        let y = dlon.sin() * lat2.cos();
+        let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * dlon.cos();
+        y.atan2(x).to_degrees().rem_euclid(360.0)
+    }
+
+    /// The point `km` away along `bearing_deg`, following a great circle.
+    pub fn destination(self, bearing_deg: f64, km: f64) -> Coordinates {
+        let (lat1, lon1) = (self.lat.to_radians(), self.lon.to_radians());
+        let bearing = bearing_deg.to_radians();
+        let d = km / EARTH_RADIUS_KM;
+        let lat2 = (lat1.sin() * d.cos() + lat1.cos() * d.sin() * bearing.cos()).asin();
+        let lon2 =
+            lon1 + (bearing.sin() * d.sin() * lat1.cos()).atan2(d.cos() - lat1.sin() * lat2.sin());
+        Coordinates::new(lat2.to_degrees(), lon2.to_degrees())
+    }
+}
+
+/// Popocatépetl's summit crater.
+pub const CRATER: Coordinates = Coordinates::new(19.0225, -98.6278);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn puebla_city_is_east_of_the_crater() {
+        let puebla = Coordinates::new(19.0414, -98.2063);
+        let distance = CRATER.distance_km(puebla);
+        assert!((44.0..45.0).contains(&distance), "{distance}");
+        let bearing = CRATER.bearing_to(puebla);
+        assert!((85.0..88.0).contains(&bearing), "{bearing}");
+    }
+
+    #[test]
+    fn destination_inverts_distance_and_bearing() {
+        let point = CRATER.destination(135.0, 40.0);
+        assert!((CRATER.distance_km(point) - 40.0).abs() < 1e-6);
+        assert!((CRATER.bearing_to(point) - 135.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn parse_reads_lat_then_lon() {
+        assert_eq!(
+            Coordinates::parse(" 19.05, -98.3 "),
+            Some(Coordinates::new(19.05, -98.3))
+        );
+        assert_eq!(Coordinates::parse("-98.3"), None);
+        assert_eq!(Coordinates::parse("98.3,19.05,1"), None);
+        assert_eq!(Coordinates::parse("119.0,-98.3"), None);
+    }
+
+    #[test]
+    fn bearings_wrap_into_the_compass() {
+        let west = Coordinates::new(CRATER.lat, CRATER.lon - 0.5);
+        assert!((CRATER.bearing_to(west) - 270.0).abs() < 0.5);
+        assert_eq!(CRATER.distance_km(CRATER), 0.0);
+    }
+}
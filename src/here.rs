@@ -0,0 +1,217 @@
+//! What the volcano means for one place on the map.
+//!
+//! Given a position, this puts together what a resident would ask first: how
+//! far away is the crater and in which direction, am I inside the exclusion
+//! radius the narrative quotes, is today's plume heading my way, and how often
+//! has ash fallen near here lately. The position is given on the command line
+//! or kept in the `location` setting (or `POPO_LOCATION`).
+
+use crate::ashfall::AshfallDay;
+use crate::downwind::Sector;
+use crate::events::EventKind;
+use crate::format::Tabular;
+use crate::gazetteer::MUNICIPALITIES;
+use crate::geo::{Coordinates, CRATER};
+use crate::models::{VolcanoReport, WindDirection};
+use chrono::NaiveDate;
+use serde::Serialize;
+
+/// Environment variable holding the default location, as "LAT,LON".
+pub const LOCATION_ENV: &str = "POPO_LOCATION";
+
+/// Ash reported within this distance counts as falling on your area.
+pub const AREA_KM: f64 = 15.0;
+
+/// A personal summary for one location and one day's report.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LocalRisk {
+    pub location: Coordinates,
+    /// The closest bundled municipality, to give the position a name.
+    pub nearest_town: Option<String>,
+    pub distance_km: f64,
+    /// Which way to look to see the crater, degrees clockwise from north.
+    pub bearing_to_crater_deg: f64,
+    pub date: NaiveDate,
+    /// The radius the day's narrative asks people to keep out of.
+    pub exclusion_radius_km: Option<f64>,
+    /// `None` when the narrative gives no radius.
+    pub inside_exclusion: Option<bool>,
+    pub wind_direction: Option<WindDirection>,
+    /// Whether you sit inside the default downwind sector. `None` when the
+    /// report states no direction.
+    pub in_plume: Option<bool>,
+    pub ashfall: AreaAshfall,
+}
+
+/// How often ash was reported within [`AREA_KM`] of a location.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AreaAshfall {
+    /// Full reports looked at; partial records cannot say whether ash fell.
+    pub scanned: usize,
+    /// Date of the oldest of them.
+    pub since: Option<NaiveDate>,
+    /// Of those, days that named a place within [`AREA_KM`].
+    pub days: usize,
+    pub last: Option<NaiveDate>,
+    /// Canonical names of the nearby places named, nearest first.
+    pub towns: Vec<String>,
+}
+
+impl LocalRisk {
+    /// Summarise `report` for `location`, counting ashfall across `history`.
+    pub fn assess(location: Coordinates, report: &VolcanoReport, history: &[AshfallDay]) -> Self {
+        let distance_km = CRATER.distance_km(location);
+        let exclusion_radius_km = report
+            .events()
+            .into_iter()
+            .filter_map(|e| match e.kind {
+                EventKind::ExclusionRadius { km } => Some(km),
+                _ => None,
+            })
+            .reduce(f64::max);
+
+        LocalRisk {
+            location,
+            nearest_town: MUNICIPALITIES
+                .iter()
+                .min_by(|a, b| {
+                    let (a, b) = (a.coordinates, b.coordinates);
+                    location.distance_km(a).total_cmp(&location.distance_km(b))
+                })
+                .map(|m| m.canonical_name()),
+            distance_km,
+            bearing_to_crater_deg: location.bearing_to(CRATER),
+            date: report.date,
+            exclusion_radius_km,
+            inside_exclusion: exclusion_radius_km.map(|km| distance_km <= km),
+            wind_direction: report.wind_direction,
+            in_plume: report
+                .wind_direction
+                .map(|dir| Sector::default().contains(dir, location)),
+            ashfall: AreaAshfall::count(location, history),
+        }
+    }
+}
+
+impl Tabular for LocalRisk {
+    type Row<'a> = &'a LocalRisk;
+
+    const COLUMNS: &'static [&'static str] = &[
+        "location.lat",
+        "location.lon",
+        "nearest_town",
+        "distance_km",
+        "bearing_to_crater_deg",
+        "date",
+        "exclusion_radius_km",
+        "inside_exclusion",
+        "wind_direction",
+        "in_plume",
+        "ashfall.scanned",
+        "ashfall.since",
+        "ashfall.days",
+        "ashfall.last",
+        "ashfall.towns",
+    ];
+    const RECORD: bool = true;
+
+    fn rows(&self) -> Vec<&LocalRisk> {
+        vec![self]
+    }
+}
+
+impl AreaAshfall {
+    fn count(location: Coordinates, history: &[AshfallDay]) -> Self {
+        let mut days = 0;
+        let mut last = None;
+        let mut towns: Vec<(f64, String)> = Vec::new();
+
+        for day in history {
+            let nearby: Vec<_> = day
+                .places()
+                .resolved
+                .into_iter()
+                .map(|p| (location.distance_km(p.coordinates), p.canonical))
+                .filter(|(km, _)| *km <= AREA_KM)
+                .collect();
+            if nearby.is_empty() {
+                continue;
+            }
+            days += 1;
+            last = last.max(Some(day.date));
+            for place in nearby {
+                if !towns.iter().any(|(_, name)| *name == place.1) {
+                    towns.push(place);
+                }
+            }
+        }
+
+        towns.sort_by(|a, b| a.0.total_cmp(&b.0));
+        AreaAshfall {
+            scanned: history.len(),
+            since: history.iter().map(|d| d.date).min(),
+            days,
+            last,
+            towns: towns.into_iter().map(|(_, name)| name).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Central Amecameca, about 18 km north-west of the crater.
+    const AMECAMECA: Coordinates = Coordinates::new(19.1239, -98.7664);
+
+    fn report(date: &str, ashfall: &[&str]) -> VolcanoReport {
+        let mut report = VolcanoReport::blank(date);
+        report.ashfall_reports = ashfall.iter().map(|s| s.to_string()).collect();
+        report
+    }
+
+    #[test]
+    fn reads_radius_and_plume_from_the_day() {
+        let mut today = report("2023-05-21", &[]);
+        today.summary_spanish = Some("Continuar con el radio de seguridad de 12 km.".into());
+        today.wind_direction = Some(WindDirection::NW);
+
+        let risk = LocalRisk::assess(AMECAMECA, &today, &[]);
+        assert_eq!(
+            risk.nearest_town.as_deref(),
+            Some("Amecameca, Estado de México")
+        );
+        assert!((17.0..19.0).contains(&risk.distance_km));
+        assert!((120.0..150.0).contains(&risk.bearing_to_crater_deg));
+        assert_eq!(risk.exclusion_radius_km, Some(12.0));
+        assert_eq!(risk.inside_exclusion, Some(false));
+        assert_eq!(risk.in_plume, Some(true));
+    }
+
+    #[test]
+    fn unstated_radius_and_wind_stay_unknown() {
+        let risk = LocalRisk::assess(AMECAMECA, &report("2023-05-21", &[]), &[]);
+        assert_eq!(risk.inside_exclusion, None);
+        assert_eq!(risk.in_plume, None);
+    }
+
+    #[test]
+    fn ashfall_counts_days_with_a_nearby_place() {
+        let mut partial = report("2023-05-18", &[]);
+        partial.partial = true;
+        let reports = [
+            partial,
+            report("2023-05-19", &["Amecameca y Ozumba, Estado de México"]),
+            report("2023-05-20", &["Puebla, Puebla"]),
+            report("2023-05-21", &["Ayapango, Estado de México"]),
+        ];
+        let history: Vec<_> = reports.iter().filter_map(AshfallDay::of).collect();
+        let ashfall = LocalRisk::assess(AMECAMECA, &reports[3], &history).ashfall;
+        assert_eq!(ashfall.scanned, 3);
+        assert_eq!(ashfall.since.unwrap().to_string(), "2023-05-19");
+        assert_eq!(ashfall.days, 2);
+        assert_eq!(ashfall.last.unwrap().to_string(), "2023-05-21");
+        assert_eq!(ashfall.towns[0], "Amecameca, Estado de México");
+        assert!(!ashfall.towns.iter().any(|t| t.starts_with("Puebla")));
+    }
+}
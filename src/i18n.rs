@@ -0,0 +1,471 @@
+//! English and Spanish wording for the human-readable report and alert views.
+//!
+//! The language comes from `--lang`, then `POPO_LANG` (or `lang` in the
+//! config), then the usual locale variables `LC_ALL`, `LC_MESSAGES` and
+//! `LANG`, so `es_MX.UTF-8` gets Spanish without any setup. Anything else is
+//! English. Machine formats are not localised: their keys and values stay as
+//! they are in the feed.
+
+use crate::models::{AlertLevel, WindDirection};
+use chrono::{Datelike, NaiveDate};
+use std::fmt;
+use std::str::FromStr;
+
+/// Environment variable choosing the output language.
+pub const LANG_ENV: &str = "POPO_LANG";
+
+/// Locale variables consulted after `POPO_LANG`, in POSIX priority order.
+const LOCALE_ENVS: [&str; 3] = ["LC_ALL", "LC_MESSAGES", "LANG"];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Lang {
+    #[default]
+    En,
+    Es,
+}
+
+/// Every phrase the human-readable report and alert views print. A `{}` is
+/// filled in order by [`fill`].
+#[derive(Debug)]
+pub struct Messages {
+    pub report_title: &'static str,
+    pub report_date: &'static str,
+    pub partial_note: &'static str,
+    pub seismic_activity: &'static str,
+    pub exhalations: &'static str,
+    pub explosions: &'static str,
+    pub volcanotectonic_events: &'static str,
+    pub tremor_total: &'static str,
+    pub tremor_high_frequency: &'static str,
+    pub tremor_harmonic: &'static str,
+    pub minutes: &'static str,
+    pub not_reported: &'static str,
+    pub alert_status: &'static str,
+    pub alert_level: &'static str,
+    pub phase: &'static str,
+    pub from_narrative: &'static str,
+    pub ashfall_reported: &'static str,
+    pub not_in_gazetteer: &'static str,
+    pub environmental_conditions: &'static str,
+    pub wind_direction: &'static str,
+    pub emissions: &'static str,
+    pub so2_emissions: &'static str,
+    pub tons_per_day: &'static str,
+    pub measured: &'static str,
+    pub media: &'static str,
+    pub images_available: &'static str,
+    pub videos_available: &'static str,
+    pub source: &'static str,
+    pub ingested: &'static str,
+
+    pub explosion: (&'static str, &'static str),
+    pub minor: &'static str,
+    pub up_to_moderate: &'static str,
+    pub up_to_major: &'static str,
+    pub at_local_time: &'static str,
+    pub ash_column: &'static str,
+    pub incandescent_fragments: &'static str,
+    pub volcanotectonic_earthquake: (&'static str, &'static str),
+    pub magnitude: &'static str,
+    pub exclusion_radius: &'static str,
+    pub image: (&'static str, &'static str),
+    pub video: (&'static str, &'static str),
+    pub refers_to: &'static str,
+
+    pub alert_details: &'static str,
+    pub current_alert: &'static str,
+    pub no_alert_status: &'static str,
+    pub as_of: &'static str,
+    pub summary: &'static str,
+    pub expected_scenarios: &'static str,
+    pub recommendations: &'static str,
+    pub narrative_spanish: &'static str,
+    pub narrative_english: &'static str,
+
+    pub counters: &'static str,
+    pub last_days: &'static str,
+    pub wind_rose: &'static str,
+    pub ashfall: &'static str,
+    pub narrative: &'static str,
+    pub no_ashfall: &'static str,
+    pub loading: &'static str,
+    pub refreshed: &'static str,
+    pub dashboard_keys: &'static str,
+}
+
+const EN: Messages = Messages {
+    report_title: "POPOCATÉPETL VOLCANO MONITORING REPORT",
+    report_date: "Report Date",
+    partial_note: "Counters only: this day was recorded from a neighbouring report's \
+                   15-day chart window, so it carries no narrative, alert status, wind or media.",
+    seismic_activity: "SEISMIC ACTIVITY (Last 24 Hours)",
+    exhalations: "Exhalations",
+    explosions: "Explosions",
+    volcanotectonic_events: "Volcanotectonic events",
+    tremor_total: "Total tremor",
+    tremor_high_frequency: "High frequency",
+    tremor_harmonic: "Harmonic",
+    minutes: "minutes",
+    not_reported: "not reported",
+    alert_status: "ALERT STATUS",
+    alert_level: "Alert Level",
+    phase: "Phase",
+    from_narrative: "FROM THE NARRATIVE",
+    ashfall_reported: "ASHFALL REPORTED",
+    not_in_gazetteer: "not in the gazetteer",
+    environmental_conditions: "ENVIRONMENTAL CONDITIONS",
+    wind_direction: "Wind Direction",
+    emissions: "EMISSIONS",
+    so2_emissions: "SO₂ Emissions",
+    tons_per_day: "tons/day",
+    measured: "Measured",
+    media: "MEDIA",
+    images_available: "Images: {} available",
+    videos_available: "Videos: {} available",
+    source: "SOURCE",
+    ingested: "Ingested",
+
+    explosion: ("explosion", "explosions"),
+    minor: "minor",
+    up_to_moderate: "up to moderate",
+    up_to_major: "up to major",
+    at_local_time: "at {} local time",
+    ash_column: "Eruptive column of {} km",
+    incandescent_fragments: "Incandescent fragments ejected",
+    volcanotectonic_earthquake: ("volcanotectonic earthquake", "volcanotectonic earthquakes"),
+    magnitude: "magnitude",
+    exclusion_radius: "Exclusion radius: {} km",
+    image: ("image", "images"),
+    video: ("video", "videos"),
+    refers_to: "Refers to {} and {}",
+
+    alert_details: "ALERT STATUS DETAILS",
+    current_alert: "Current Alert",
+    no_alert_status: "No alert status published for {}. This day was recorded from a \
+                      neighbouring report's counter window. Try a nearby date.",
+    as_of: "As of",
+    summary: "SUMMARY",
+    expected_scenarios: "EXPECTED SCENARIOS FOR THIS PHASE",
+    recommendations: "RECOMMENDATIONS",
+    narrative_spanish: "Spanish",
+    narrative_english: "English, machine-rendered",
+
+    counters: "Counters",
+    last_days: "Last {} days",
+    wind_rose: "Wind rose",
+    ashfall: "Ashfall",
+    narrative: "Narrative",
+    no_ashfall: "No ashfall reported",
+    loading: "Loading…",
+    refreshed: "Refreshed at {}",
+    dashboard_keys: "←/→ date  ↑/↓ scroll  Home/End first/last  r refresh  q quit",
+};
+
+const ES: Messages = Messages {
+    report_title: "REPORTE DE MONITOREO DEL VOLCÁN POPOCATÉPETL",
+    report_date: "Fecha del reporte",
+    partial_note: "Solo contadores: este día se tomó de la gráfica de 15 días de un reporte \
+                   vecino, así que no trae texto, semáforo, viento ni multimedia.",
+    seismic_activity: "ACTIVIDAD SÍSMICA (últimas 24 horas)",
+    exhalations: "Exhalaciones",
+    explosions: "Explosiones",
+    volcanotectonic_events: "Sismos volcanotectónicos",
+    tremor_total: "Tremor total",
+    tremor_high_frequency: "Alta frecuencia",
+    tremor_harmonic: "Armónico",
+    minutes: "minutos",
+    not_reported: "sin dato",
+    alert_status: "SEMÁFORO DE ALERTA VOLCÁNICA",
+    alert_level: "Semáforo",
+    phase: "Fase",
+    from_narrative: "DEL REPORTE",
+    ashfall_reported: "CAÍDA DE CENIZA REPORTADA",
+    not_in_gazetteer: "no está en el nomenclátor",
+    environmental_conditions: "CONDICIONES AMBIENTALES",
+    wind_direction: "Dirección del viento",
+    emissions: "EMISIONES",
+    so2_emissions: "Emisión de SO₂",
+    tons_per_day: "toneladas/día",
+    measured: "Medida el",
+    media: "MULTIMEDIA",
+    images_available: "Imágenes: {} disponibles",
+    videos_available: "Videos: {} disponibles",
+    source: "FUENTE",
+    ingested: "Obtenido",
+
+    explosion: ("explosión", "explosiones"),
+    minor: "menor",
+    up_to_moderate: "hasta moderada",
+    up_to_major: "hasta mayor",
+    at_local_time: "a las {} hora local",
+    ash_column: "Columna eruptiva de {} km",
+    incandescent_fragments: "Emisión de fragmentos incandescentes",
+    volcanotectonic_earthquake: ("sismo volcanotectónico", "sismos volcanotectónicos"),
+    magnitude: "magnitud",
+    exclusion_radius: "Radio de exclusión: {} km",
+    image: ("imagen", "imágenes"),
+    video: ("video", "videos"),
+    refers_to: "Menciona {} y {}",
+
+    alert_details: "DETALLE DEL SEMÁFORO DE ALERTA",
+    current_alert: "Semáforo actual",
+    no_alert_status: "No se publicó semáforo para el {}. Este día se tomó de los contadores \
+                      de un reporte vecino. Pruebe una fecha cercana.",
+    as_of: "Vigente al",
+    summary: "RESUMEN",
+    expected_scenarios: "ESCENARIOS PREVISTOS PARA ESTA FASE",
+    recommendations: "RECOMENDACIONES",
+    narrative_spanish: "texto original",
+    narrative_english: "en inglés, traducción automática",
+
+    counters: "Contadores",
+    last_days: "Últimos {} días",
+    wind_rose: "Rosa de vientos",
+    ashfall: "Caída de ceniza",
+    narrative: "Reporte",
+    no_ashfall: "Sin caída de ceniza reportada",
+    loading: "Cargando…",
+    refreshed: "Actualizado a las {}",
+    dashboard_keys: "←/→ fecha  ↑/↓ desplazar  Inicio/Fin primero/último  r actualizar  q salir",
+};
+
+const MONTHS_EN: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const MONTHS_ES: [&str; 12] = [
+    "enero",
+    "febrero",
+    "marzo",
+    "abril",
+    "mayo",
+    "junio",
+    "julio",
+    "agosto",
+    "septiembre",
+    "octubre",
+    "noviembre",
+    "diciembre",
+];
+
+const WEEKDAYS_EN: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+const WEEKDAYS_ES: [&str; 7] = [
+    "lunes",
+    "martes",
+    "miércoles",
+    "jueves",
+    "viernes",
+    "sábado",
+    "domingo",
+];
+
+impl Lang {
+    pub const ALL: [Lang; 2] = [Lang::En, Lang::Es];
+
+    /// The `--lang` flag if given, then the configured `lang` (a config file
+    /// or `POPO_LANG`), then the locale, then English.
+    pub fn detect(flag: Option<Lang>, configured: Option<&str>) -> Lang {
+        flag.or_else(|| configured.and_then(Self::from_locale))
+            .or_else(Self::from_system_locale)
+            .unwrap_or_default()
+    }
+
+    /// The first locale variable that is set. A locale in some other
+    /// language gives `None`, as does no locale at all.
+    pub fn from_system_locale() -> Option<Lang> {
+        let var = |name| std::env::var(name).ok().filter(|v: &String| !v.is_empty());
+        LOCALE_ENVS
+            .into_iter()
+            .find_map(var)
+            .and_then(|v| Self::from_locale(&v))
+    }
+
+    /// The language of a POSIX locale such as `es_MX.UTF-8`, or of a bare
+    /// code or name as `--lang` takes.
+    pub fn from_locale(locale: &str) -> Option<Lang> {
+        let code = locale
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default();
+        match code.to_lowercase().as_str() {
+            "en" | "english" | "inglés" | "ingles" => Some(Lang::En),
+            "es" | "spanish" | "español" | "espanol" => Some(Lang::Es),
+            _ => None,
+        }
+    }
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Lang::En => "en",
+            Lang::Es => "es",
+        }
+    }
+
+    pub fn messages(self) -> &'static Messages {
+        match self {
+            Lang::En => &EN,
+            Lang::Es => &ES,
+        }
+    }
+
+    /// "Yellow", "Amarillo".
+    pub fn alert_level(self, level: AlertLevel) -> &'static str {
+        match (self, level) {
+            (Lang::En, AlertLevel::Green) => "Green",
+            (Lang::En, AlertLevel::Yellow) => "Yellow",
+            (Lang::En, AlertLevel::Orange) => "Orange",
+            (Lang::En, AlertLevel::Red) => "Red",
+            (Lang::Es, AlertLevel::Green) => "Verde",
+            (Lang::Es, AlertLevel::Yellow) => "Amarillo",
+            (Lang::Es, AlertLevel::Orange) => "Naranja",
+            (Lang::Es, AlertLevel::Red) => "Rojo",
+        }
+    }
+
+    /// "West", "Oeste".
+    pub fn wind_direction(self, dir: WindDirection) -> &'static str {
+        if self == Lang::En {
+            return dir.name();
+        }
+        match dir {
+            WindDirection::N => "Norte",
+            WindDirection::NNE => "Nornoreste",
+            WindDirection::NE => "Noreste",
+            WindDirection::ENE => "Estenoreste",
+            WindDirection::E => "Este",
+            WindDirection::ESE => "Estesureste",
+            WindDirection::SE => "Sureste",
+            WindDirection::SSE => "Sursureste",
+            WindDirection::S => "Sur",
+            WindDirection::SSW => "Sursuroeste",
+            WindDirection::SW => "Suroeste",
+            WindDirection::WSW => "Oestesuroeste",
+            WindDirection::W => "Oeste",
+            WindDirection::WNW => "Oestenoroeste",
+            WindDirection::NW => "Noroeste",
+            WindDirection::NNW => "Nornoroeste",
+        }
+    }
+
+    /// "Tuesday, 22 March 2022", "martes 22 de marzo de 2022".
+    pub fn date(self, date: NaiveDate) -> String {
+        let weekday = date.weekday().num_days_from_monday() as usize;
+        let month = date.month0() as usize;
+        match self {
+            Lang::En => format!(
+                "{}, {} {} {}",
+                WEEKDAYS_EN[weekday],
+                date.day(),
+                MONTHS_EN[month],
+                date.year()
+            ),
+            Lang::Es => format!(
+                "{} {} de {} de {}",
+                WEEKDAYS_ES[weekday],
+                date.day(),
+                MONTHS_ES[month],
+                date.year()
+            ),
+        }
+    }
+
+    /// `count` with the singular or plural of a pair from [`Messages`].
+    pub fn count(self, count: u32, (one, many): (&str, &str)) -> String {
+        format!("{} {}", count, if count == 1 { one } else { many })
+    }
+}
+
+impl fmt::Display for Lang {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for Lang {
+    type Err = String;
+
+    fn from_str(text: &str) -> std::result::Result<Self, String> {
+        Self::from_locale(text.trim())
+            .ok_or_else(|| format!("unknown language '{}'; use en or es", text))
+    }
+}
+
+/// `template` with each `{}` replaced by the next of `args`.
+pub fn fill(template: &str, args: &[&dyn fmt::Display]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut parts = template.split("{}");
+    if let Some(first) = parts.next() {
+        out.push_str(first);
+    }
+    for part in parts {
+        if let Some(arg) = args.next() {
+            out.push_str(&arg.to_string());
+        }
+        out.push_str(part);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_posix_locales() {
+        assert_eq!(Lang::from_locale("es_MX.UTF-8"), Some(Lang::Es));
+        assert_eq!(Lang::from_locale("es-MX"), Some(Lang::Es));
+        assert_eq!(Lang::from_locale("en_GB.UTF-8@euro"), Some(Lang::En));
+        assert_eq!(Lang::from_locale("Español"), Some(Lang::Es));
+        assert_eq!(Lang::from_locale("fr_FR.UTF-8"), None);
+        assert_eq!(Lang::from_locale("C"), None);
+        assert!("de".parse::<Lang>().is_err());
+    }
+
+    #[test]
+    fn dates_read_naturally() {
+        let date = NaiveDate::from_ymd_opt(2022, 3, 22).unwrap();
+        assert_eq!(Lang::En.date(date), "Tuesday, 22 March 2022");
+        assert_eq!(Lang::Es.date(date), "martes 22 de marzo de 2022");
+    }
+
+    #[test]
+    fn names_levels_and_directions() {
+        assert_eq!(Lang::Es.alert_level(AlertLevel::Yellow), "Amarillo");
+        assert_eq!(Lang::Es.wind_direction(WindDirection::W), "Oeste");
+        assert_eq!(Lang::En.wind_direction(WindDirection::W), "West");
+        assert_eq!(
+            Lang::Es.count(3, Lang::Es.messages().explosion),
+            "3 explosiones"
+        );
+    }
+
+    #[test]
+    fn fills_placeholders_in_order() {
+        assert_eq!(
+            fill("Menciona {} y {}", &[&"1 imagen", &2]),
+            "Menciona 1 imagen y 2"
+        );
+        assert_eq!(fill("no placeholders", &[&1]), "no placeholders");
+    }
+}
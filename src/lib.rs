@@ -3,10 +3,61 @@
 //! Data comes from a published JSON feed rather than by scraping CENAPRED
 //! directly. See [`feed`] and `docs/feed-schema.md` for why.
 
+pub mod ashfall;
+pub mod compare;
+pub mod config;
+pub mod diff;
+pub mod downwind;
+pub mod email;
 pub mod error;
+pub mod events;
+pub mod export;
 pub mod feed;
+pub mod format;
+pub mod gazetteer;
+pub mod geo;
+pub mod here;
+pub mod i18n;
 pub mod models;
+pub mod mqtt;
+pub mod narrative;
+pub mod notify;
+pub mod paths;
+pub mod rules;
+pub mod search;
+pub mod style;
+pub mod template;
+pub mod text;
+pub mod translate;
+pub mod tui;
+pub mod watch;
+pub mod watchlist;
+pub mod windrose;
 
+pub use ashfall::{AshfallDay, AshfallLocation, AshfallPlaces, TownCount};
+pub use compare::{Discrepancy, FeedComparison, Mismatch, Unreadable};
+pub use config::{Config, Layers};
+pub use diff::{FieldChange, ReportDiff};
+pub use downwind::{DownwindTown, Sector};
+pub use email::{Email, SmtpConfig, SmtpTls};
 pub use error::{PopoError, Result};
+pub use events::{EventKind, Intensity, MediaKind, NarrativeEvent, Span};
 pub use feed::{Feed, DEFAULT_FEED_BASE, FEED_BASE_ENV};
+pub use format::{Format, Tabular};
+pub use gazetteer::{Municipality, State};
+pub use geo::{Coordinates, CRATER};
+pub use here::{AreaAshfall, LocalRisk, LOCATION_ENV};
+pub use i18n::{Lang, LANG_ENV};
 pub use models::{AlertLevel, FeedIndex, VolcanoReport, WindDirection, SCHEMA_VERSION};
+pub use mqtt::MqttConfig;
+pub use narrative::Narrative;
+pub use notify::{Notification, Webhook, WebhookKind};
+pub use rules::{Condition, Outcome, Rule, RuleResult, RuleSet};
+pub use search::{Query, SearchHit, SearchIndex, Snippet};
+pub use style::{Renderer, Style, STYLE_ENV};
+pub use template::Template;
+pub use translate::{EnglishNarrative, Rendered};
+pub use tui::Dashboard;
+pub use watch::{Change, WatchState};
+pub use watchlist::{WatchHit, Watchlist, WATCHLIST_ENV};
+pub use windrose::{Petal, WindRose};
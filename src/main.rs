@@ -1,6 +1,21 @@
 use chrono::NaiveDate;
-use clap::{Parser, Subcommand};
-use popo_cli::{AlertLevel, Feed, FeedIndex, PopoError, Result, VolcanoReport};
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand};
+use popo_cli::export;
+use popo_cli::i18n::fill;
+use popo_cli::style::{Renderer, Style};
+use popo_cli::{
+    AlertLevel, Change, Config, Coordinates, DownwindTown, Email, EnglishNarrative, EventKind,
+    Feed, FeedComparison, FeedIndex, Format, Intensity, Lang, Layers, LocalRisk, MediaKind,
+    MqttConfig, Narrative, NarrativeEvent, Notification, Outcome, Petal, PopoError, Query,
+    ReportDiff, Result, RuleResult, RuleSet, SearchHit, SearchIndex, Sector, SmtpConfig, Snippet,
+    Tabular, Template, TownCount, VolcanoReport, WatchHit, WatchState, Watchlist, Webhook,
+    WindDirection, WindRose,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::ExitCode;
 
 #[derive(Parser)]
 #[command(name = "popo")]
@@ -11,317 +26,2442 @@ struct Cli {
     command: Option<Commands>,
 
     /// Read from a different feed (URL or local directory).
-    /// Also settable with POPO_FEED_BASE.
+    /// Also settable with POPO_FEED_BASE or `feed` in the config.
     #[arg(long, global = true, value_name = "URL_OR_PATH")]
     feed: Option<String>,
+
+    /// Output format: human, json, json-compact, ndjson, yaml, csv, markdown
+    /// or table, and geojson or kml for `popo export`
+    #[arg(long, global = true, value_name = "FORMAT", default_value = "human", value_parser = |s: &str| s.parse::<Format>())]
+    format: Format,
+
+    /// Same as --format json
+    #[arg(long, global = true, hide = true)]
+    json: bool,
+
+    /// Language for reports and alerts: en or es. Defaults to POPO_LANG,
+    /// then the locale (LC_ALL, LC_MESSAGES, LANG), then English. For
+    /// `popo alert` it also picks the narrative, which is otherwise the
+    /// Spanish original.
+    #[arg(long, global = true, value_name = "LANG", value_parser = |s: &str| s.parse::<Lang>())]
+    lang: Option<Lang>,
+
+    /// How human-readable output is drawn: rich (box drawing, emoji and
+    /// colour), plain (ASCII) or accessible (linear text for screen readers).
+    /// Defaults to POPO_STYLE, then rich on a terminal and plain elsewhere.
+    /// Colour is off when NO_COLOR is set.
+    #[arg(long, global = true, value_name = "STYLE", value_parser = |s: &str| s.parse::<Style>())]
+    style: Option<Style>,
+
+    /// Render latest, get, alert and digest through a Tera template instead:
+    /// a file, or a name from `[templates]` in the config.
+    #[arg(long, global = true, value_name = "PATH_OR_NAME")]
+    template: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Show the latest report
+    Latest,
+
+    /// Show the latest report as JSON
+    Json,
+
+    /// Show the current alert status in detail. Exits with status 4 when the
+    /// report carries no alert status, and with --fail-at, 3 when the level is
+    /// at or above the threshold.
+    Alert {
+        /// Exit with status 3 when the level is this or higher: green,
+        /// yellow, orange or red
+        #[arg(long, value_name = "LEVEL", value_parser = parse_alert_level)]
+        fail_at: Option<AlertLevel>,
+
+        /// Print only the level, e.g. YELLOW
+        #[arg(long)]
+        quiet: bool,
+    },
+
+    /// Show the report for a specific date (YYYY-MM-DD)
+    Get {
+        /// Date in YYYY-MM-DD format (e.g. 2022-03-22)
+        date: String,
+    },
+
+    /// Show what changed between two reports: counters, alert level and
+    /// phase, wind, ashfall towns and media. Give two dates, or a date and
+    /// --against to compare the same day in another feed.
+    Diff {
+        /// The earlier report (YYYY-MM-DD)
+        from: String,
+
+        /// The later report (YYYY-MM-DD); with --against, defaults to FROM
+        #[arg(required_unless_present = "against")]
+        to: Option<String>,
+
+        /// Read the later report from this feed (URL or local directory)
+        #[arg(long, value_name = "URL_OR_PATH")]
+        against: Option<String>,
+    },
+
+    /// Check that two feeds carry the same reports, such as a mirror and the
+    /// feed it copies. Exits with status 3 when they diverge.
+    CompareFeeds {
+        /// The first feed (URL or local directory)
+        a: String,
+
+        /// The second feed (URL or local directory)
+        b: String,
+
+        /// First day to compare (YYYY-MM-DD); defaults to the earliest
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Last day to compare (YYYY-MM-DD); defaults to the latest
+        #[arg(long)]
+        to: Option<String>,
+    },
+
+    /// Show what the feed covers
+    Index,
+
+    /// Show how often the plume headed each way over a date range
+    Windrose {
+        /// First day to include (YYYY-MM-DD)
+        #[arg(long)]
+        from: String,
+
+        /// Last day to include (YYYY-MM-DD)
+        #[arg(long)]
+        to: String,
+
+        /// Also break the rose down by calendar month
+        #[arg(long)]
+        by_month: bool,
+    },
+
+    /// Search the Spanish narratives across the archive
+    Search {
+        /// Words to find, accent- and case-insensitive. Supports "phrases",
+        /// OR, NOT (or -word) and parentheses; words are ANDed by default.
+        query: String,
+
+        /// Show at most this many reports, newest first
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+
+        /// Discard the local index and rebuild it from the feed
+        #[arg(long)]
+        rebuild: bool,
+    },
+
+    /// Check ashfall against a watchlist of towns, or rank towns by how often
+    /// ash fell on them. Exits with status 3 when a watched town is named.
+    Ashfall {
+        /// Towns to watch, comma separated (e.g. "Amecameca,Atlixco").
+        /// Defaults to POPO_WATCHLIST.
+        #[arg(long, value_name = "TOWNS")]
+        watch: Option<String>,
+
+        /// Rank towns by the number of days ash was reported there
+        #[arg(long, conflicts_with = "watch")]
+        top: bool,
+
+        /// First day to scan (YYYY-MM-DD). Without a range the latest report
+        /// is checked, or with --top, the whole archive.
+        #[arg(long, requires = "to")]
+        from: Option<String>,
+
+        /// Last day to scan (YYYY-MM-DD)
+        #[arg(long, requires = "from")]
+        to: Option<String>,
+
+        /// With --top, show at most this many towns
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+    },
+
+    /// Check a report against your own alert rules. Exits with status 3 when
+    /// a rule matches, or 4 when a rule with missing = "error" could not be
+    /// decided for want of data.
+    Check {
+        /// TOML file of [[rule]] tables, each with a `name` and a `when`
+        /// condition such as "explosions >= 5 or alert_level >= ORANGE".
+        /// Defaults to `rules` in the config.
+        #[arg(long, value_name = "FILE")]
+        rules: Option<std::path::PathBuf>,
+
+        /// Date in YYYY-MM-DD format; defaults to the latest report
+        date: Option<String>,
+
+        /// Post to a webhook when a rule matches, as KIND=URL where KIND is
+        /// json, slack, discord or matrix. Repeat for several.
+        #[arg(long = "webhook", value_name = "KIND=URL")]
+        webhooks: Vec<String>,
+
+        /// Email these addresses, comma separated, when a rule matches
+        #[arg(long, value_name = "ADDRESSES")]
+        email: Option<String>,
+    },
+
+    /// List the towns in the path of the day's plume
+    Downwind {
+        /// Date in YYYY-MM-DD format; defaults to the latest report
+        date: Option<String>,
+
+        /// Full width of the downwind sector, in degrees
+        #[arg(long, default_value_t = Sector::default().width_deg)]
+        width: f64,
+
+        /// Leave out towns further than this from the crater, in km
+        #[arg(long, default_value_t = Sector::default().max_km)]
+        max_km: f64,
+    },
+
+    /// Poll the feed and announce new reports and changes as they appear
+    Watch {
+        /// How often to poll, e.g. 30s, 10m, 1h [default: 10m]
+        #[arg(long)]
+        interval: Option<String>,
+
+        /// Run this shell command on every change, with the new report as
+        /// JSON on its stdin
+        #[arg(long, value_name = "COMMAND")]
+        exec: Option<String>,
+
+        /// Post every change to a webhook, as KIND=URL where KIND is json,
+        /// slack, discord or matrix. Repeat for several. POPO_WEBHOOK_SECRET
+        /// signs json bodies; POPO_MATRIX_TOKEN authorises matrix.
+        #[arg(long = "webhook", value_name = "KIND=URL")]
+        webhooks: Vec<String>,
+
+        /// Email every change to these addresses, comma separated. The relay
+        /// is configured with POPO_SMTP_HOST and friends, or under [email] in
+        /// the config.
+        #[arg(long, value_name = "ADDRESSES")]
+        email: Option<String>,
+
+        /// Poll once and exit, for use from cron
+        #[arg(long)]
+        once: bool,
+    },
+
+    /// Full-screen dashboard: alert level, counters, 60 days of history, wind
+    /// rose, ashfall and the narrative. Left and right step through dates.
+    Tui {
+        /// How often to check the feed for a new report, e.g. 30s, 10m, 1h
+        /// [default: 10m]
+        #[arg(long)]
+        interval: Option<String>,
+    },
+
+    /// Email a digest of recent reports, for a daily cron job
+    Digest {
+        /// Recipients, comma separated. Defaults to `email.to` in the config.
+        #[arg(long, value_name = "ADDRESSES")]
+        email: Option<String>,
+
+        /// How many days to cover, ending with the latest report (at most 3650)
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..=3650))]
+        days: u64,
+
+        /// Print the plain-text digest, or the --template output, instead of
+        /// sending it
+        #[arg(long)]
+        print: bool,
+    },
+
+    /// Publish the latest report to a message broker
+    Publish {
+        #[command(subcommand)]
+        target: PublishTarget,
+    },
+
+    /// Export the crater, plume and ashfall as map layers, with
+    /// --format geojson or --format kml
+    Export {
+        /// Date in YYYY-MM-DD format; defaults to the latest report
+        #[arg(conflicts_with_all = ["from", "to"])]
+        date: Option<String>,
+
+        /// First day of a range (YYYY-MM-DD)
+        #[arg(long, requires = "to")]
+        from: Option<String>,
+
+        /// Last day of a range (YYYY-MM-DD)
+        #[arg(long, requires = "from")]
+        to: Option<String>,
+
+        /// Full width of the plume wedge, in degrees
+        #[arg(long, default_value_t = Sector::default().width_deg)]
+        width: f64,
+
+        /// Length of the plume wedge, in km
+        #[arg(long, default_value_t = Sector::default().max_km)]
+        max_km: f64,
+    },
+
+    /// What today's report means where you are. Set POPO_LOCATION=LAT,LON
+    /// once instead of passing --lat and --lon every time.
+    Here {
+        /// Your latitude in decimal degrees (e.g. 19.05)
+        #[arg(long, requires = "lon", allow_hyphen_values = true)]
+        lat: Option<f64>,
+
+        /// Your longitude in decimal degrees (e.g. -98.30)
+        #[arg(long, requires = "lat", allow_hyphen_values = true)]
+        lon: Option<f64>,
+    },
+
+    /// Show or change settings. The user file is read first, then popo.toml
+    /// in this directory or a parent, then POPO_* variables, then flags.
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Print the settings in effect, from every file and variable
+    Show,
+
+    /// Set KEY (e.g. watch.interval) to VALUE in the user config file. Lists
+    /// are comma separated; an empty VALUE removes the key.
+    Set {
+        key: String,
+        value: String,
+
+        /// Write to the project's popo.toml instead, creating it here if
+        /// there is none
+        #[arg(long)]
+        project: bool,
+    },
+
+    /// Print where config files are read from
+    Path,
+}
+
+#[derive(Subcommand)]
+enum PublishTarget {
+    /// Publish to MQTT: the report as JSON on TOPIC/report and each field on
+    /// its own topic, all retained. POPO_MQTT_USER and POPO_MQTT_PASSWORD
+    /// authenticate.
+    Mqtt {
+        /// Broker address: mqtt://host:1883, or mqtts://host:8883 for TLS
+        #[arg(long)]
+        broker: Option<String>,
+
+        /// Topic prefix [default: popo]
+        #[arg(long)]
+        topic: Option<String>,
+
+        /// Delivery guarantee: 0 at most once, 1 at least once, 2 exactly
+        /// once [default: 0]
+        #[arg(long, value_parser = clap::value_parser!(u8).range(0..=2))]
+        qos: Option<u8>,
+
+        /// Use TLS even with an mqtt:// address
+        #[arg(long)]
+        tls: bool,
+
+        /// PEM certificate authority to trust for TLS
+        #[arg(long, value_name = "FILE")]
+        ca: Option<std::path::PathBuf>,
+
+        /// Client identifier to connect with
+        #[arg(long)]
+        client_id: Option<String>,
+    },
+}
+
+/// Exit status of `popo ashfall` when a watched town was named.
+const EXIT_WATCHED_TOWN: u8 = 3;
+
+/// Exit statuses of `popo alert`.
+const EXIT_ALERT_AT_THRESHOLD: u8 = 3;
+const EXIT_NO_ALERT_STATUS: u8 = 4;
+
+/// Exit statuses for failures, so a script can tell an outage from a problem
+/// with the feed itself. Other errors exit 1, and usage errors 2.
+const EXIT_NETWORK: u8 = 5;
+const EXIT_FEED: u8 = 6;
+
+/// Exit status of `popo compare-feeds` when the feeds differ.
+const EXIT_FEEDS_DIVERGE: u8 = 3;
+
+/// Exit statuses of `popo check`.
+const EXIT_RULE_MATCHED: u8 = 3;
+const EXIT_RULE_UNDECIDED: u8 = 4;
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            if let PopoError::Network(_) = err {
+                eprintln!("\nThe feed could not be reached. Check your connection, or point");
+                eprintln!("popo at another mirror with --feed / POPO_FEED_BASE.");
+            }
+            exit_code(&err)
+        }
+    }
+}
+
+fn exit_code(err: &PopoError) -> ExitCode {
+    match err {
+        PopoError::Network(_) => ExitCode::from(EXIT_NETWORK),
+        PopoError::NotFound(_)
+        | PopoError::Parse(_)
+        | PopoError::Feed(_)
+        | PopoError::UnsupportedSchema { .. }
+        | PopoError::LocalFeed { .. } => ExitCode::from(EXIT_FEED),
+        _ => ExitCode::FAILURE,
+    }
+}
+
+fn run() -> Result<ExitCode> {
+    let cli = Cli::parse();
+    let format = if cli.json { Format::Json } else { cli.format };
+    let machine = format != Format::Human;
+    let export = matches!(cli.command, Some(Commands::Export { .. }));
+    if format.is_map() != export {
+        let message = match export {
+            true => "popo export writes map layers: use --format geojson or --format kml",
+            false => "--format geojson and --format kml are only for popo export",
+        };
+        Cli::command()
+            .error(ErrorKind::InvalidValue, message)
+            .exit();
+    }
+    let templated = matches!(
+        cli.command,
+        Some(
+            Commands::Latest
+                | Commands::Get { .. }
+                | Commands::Alert { .. }
+                | Commands::Digest { .. }
+        )
+    );
+    if cli.template.is_some() && !templated {
+        Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--template is only for popo latest, get, alert and digest",
+            )
+            .exit();
+    }
+    // Handled before loading the config, so a broken file can still be fixed.
+    if let Some(Commands::Config { action }) = cli.command {
+        run_config(action, format)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // Files and `POPO_*` variables, merged; flags override what they say.
+    let config = Config::load()?;
+    let lang = Lang::detect(cli.lang, config.lang.as_deref());
+    let render = Renderer::detect(cli.style, config.style.as_deref());
+    let feed = Feed::with_base(
+        cli.feed
+            .or(config.feed.clone())
+            .unwrap_or_else(|| popo_cli::DEFAULT_FEED_BASE.to_string()),
+    );
+    let cache_dir = config.cache_dir.as_deref();
+    let template = match &cli.template {
+        Some(spec) => Some(Template::load(
+            &popo_cli::template::locate(spec, &config.templates)?,
+            lang,
+        )?),
+        None => None,
+    };
+
+    match cli.command {
+        Some(Commands::Json) | None => {
+            let format = if machine { format } else { Format::Json };
+            emit(&feed.latest()?, format)?;
+        }
+        Some(Commands::Latest) => {
+            let report = feed.latest()?;
+            if let Some(template) = &template {
+                print!("{}", template.render_report(&report)?);
+            } else if machine {
+                emit(&report, format)?;
+            } else {
+                print_human_readable(&report, lang, &render);
+            }
+        }
+        Some(Commands::Alert { fail_at, quiet }) => {
+            // English is rendered offline from the Spanish by a fixed
+            // glossary, so only an explicit --lang en asks for it.
+            let narrative_lang = cli.lang.unwrap_or(Lang::Es);
+            let report = feed.latest()?;
+            match (quiet, report.alert_level) {
+                (true, Some(level)) => println!("{}", format!("{:?}", level).to_uppercase()),
+                (true, None) => eprintln!("No alert status published for {}", report.date),
+                (false, _) => match &template {
+                    Some(template) => print!("{}", template.render_report(&report)?),
+                    None if machine => emit(&AlertStatus::new(&report, narrative_lang), format)?,
+                    None => print_alert_info(&report, narrative_lang, lang, &render),
+                },
+            }
+            let Some(level) = report.alert_level else {
+                return Ok(ExitCode::from(EXIT_NO_ALERT_STATUS));
+            };
+            if fail_at.is_some_and(|threshold| level >= threshold) {
+                return Ok(ExitCode::from(EXIT_ALERT_AT_THRESHOLD));
+            }
+        }
+        Some(Commands::Get { date }) => {
+            let report = feed.get(parse_date(&date)?)?;
+            if let Some(template) = &template {
+                print!("{}", template.render_report(&report)?);
+            } else if machine {
+                emit(&report, format)?;
+            } else {
+                print_human_readable(&report, lang, &render);
+            }
+        }
+        Some(Commands::Diff { from, to, against }) => {
+            let from = parse_date(&from)?;
+            let to = match to {
+                Some(to) => parse_date(&to)?,
+                None => from,
+            };
+            let other = against.map(Feed::with_base);
+            let later = other.as_ref().unwrap_or(&feed).get(to)?;
+            let diff = feed.get(from)?.diff(&later);
+            if machine {
+                emit(&diff, format)?;
+            } else {
+                print_diff(&diff, other.as_ref().map(|o| (&feed, o)), &render);
+            }
+        }
+        Some(Commands::CompareFeeds { a, b, from, to }) => {
+            let from = from.as_deref().map(parse_date).transpose()?;
+            let to = to.as_deref().map(parse_date).transpose()?;
+            let comparison =
+                FeedComparison::run(&Feed::with_base(a), &Feed::with_base(b), from, to)?;
+            if machine {
+                emit(&comparison, format)?;
+            } else {
+                print_comparison(&comparison, &render);
+            }
+            if comparison.is_divergent() {
+                return Ok(ExitCode::from(EXIT_FEEDS_DIVERGE));
+            }
+        }
+        Some(Commands::Index) => {
+            let index = feed.index()?;
+            if machine {
+                emit(&index, format)?;
+            } else {
+                print_index(&index, &render);
+            }
+        }
+        Some(Commands::Windrose { from, to, by_month }) => {
+            let (from, to) = (parse_date(&from)?, parse_date(&to)?);
+            let reports = feed.range(from, to)?;
+            let table = WindRoseTable {
+                from,
+                to,
+                rose: WindRose::from_reports(&reports),
+                by_month: by_month.then(|| WindRose::by_month(&reports)),
+            };
+            if machine {
+                emit(&table, format)?;
+            } else {
+                print_windrose(&table, &render);
+            }
+        }
+        Some(Commands::Search {
+            query,
+            limit,
+            rebuild,
+        }) => {
+            let query = Query::parse(&query)?;
+            let index = open_search_index(&feed, cache_dir, rebuild)?;
+            let hits = index.search(&query);
+            if machine {
+                emit(&hits.iter().take(limit).collect::<Vec<_>>(), format)?;
+            } else {
+                print_search_hits(&hits, limit, index.len(), &render);
+            }
+        }
+        Some(Commands::Ashfall {
+            watch,
+            top,
+            from,
+            to,
+            limit,
+        }) => {
+            let range = match (from, to) {
+                (Some(from), Some(to)) => Some((parse_date(&from)?, parse_date(&to)?)),
+                _ => None,
+            };
+            if top {
+                let (from, to) = range.unwrap_or((NaiveDate::MIN, NaiveDate::MAX));
+                if from > to {
+                    return Err(PopoError::InvalidRange { from, to });
+                }
+                let days = open_search_index(&feed, cache_dir, false)?.ashfall(from, to);
+                let ranked = popo_cli::ashfall::rank(&days);
+                if machine {
+                    emit(&ranked.iter().take(limit).collect::<Vec<_>>(), format)?;
+                } else {
+                    let dates: Vec<NaiveDate> = days.iter().map(|d| d.date).collect();
+                    print_ashfall_ranking(&ranked, limit, &dates, &render);
+                }
+                return Ok(ExitCode::SUCCESS);
+            }
+
+            let reports = match range {
+                Some((from, to)) => feed.range(from, to)?,
+                None => vec![feed.latest()?],
+            };
+            let watchlist = match watch {
+                Some(list) => Watchlist::parse(&list),
+                None => Watchlist {
+                    towns: config.watchlist.clone().unwrap_or_default(),
+                },
+            };
+            let watch = AshfallWatch {
+                watching: watchlist.towns.clone(),
+                scanned: reports.len(),
+                hits: watchlist.scan(&reports),
+            };
+            if machine {
+                emit(&watch, format)?;
+            } else {
+                print_ashfall_watch(&watch, &reports, &render);
+            }
+            if !watch.hits.is_empty() {
+                return Ok(ExitCode::from(EXIT_WATCHED_TOWN));
+            }
+        }
+        Some(Commands::Check {
+            rules,
+            date,
+            webhooks,
+            email,
+        }) => {
+            let path = rules.or(config.rules).ok_or_else(|| {
+                PopoError::InvalidRules(
+                    "no rules file. Pass --rules or set `rules` in the config".to_string(),
+                )
+            })?;
+            let rules = RuleSet::load(&path)?;
+            let webhooks = webhooks
+                .iter()
+                .map(|spec| Webhook::parse(spec))
+                .collect::<Result<Vec<_>>>()?;
+            let email = match email {
+                Some(list) => Some((SmtpConfig::from_settings(&config.email)?, split_list(&list))),
+                None => None,
+            };
+            let report = match date {
+                Some(date) => feed.get(parse_date(&date)?)?,
+                None => feed.latest()?,
+            };
+            let history = match rules.history_days() {
+                0 => Vec::new(),
+                days => {
+                    let earliest = |n: u64| {
+                        report
+                            .date
+                            .checked_sub_days(chrono::Days::new(n))
+                            .unwrap_or(chrono::NaiveDate::MIN)
+                    };
+                    feed.range(earliest(u64::from(days)), earliest(1))?
+                }
+            };
+            let check = RuleCheck {
+                date: report.date,
+                rules: rules.check(&report, &history),
+            };
+            if machine {
+                emit(&check, format)?;
+            } else {
+                print_rule_check(&check, &render);
+            }
+            let matched: Vec<String> = check
+                .rules
+                .iter()
+                .filter(|r| r.outcome == Outcome::Matched)
+                .map(|r| format!("Rule {} matched: {}", r.name, r.when))
+                .collect();
+            if !matched.is_empty() {
+                let notifiers = Notifiers {
+                    exec: None,
+                    webhooks: &webhooks,
+                    email: email.as_ref(),
+                };
+                notifiers.send(
+                    &popo_cli::notify::client(),
+                    &Notification {
+                        report: &report,
+                        reasons: matched,
+                    },
+                );
+            }
+            let any = |outcome| check.rules.iter().any(|r| r.outcome == outcome);
+            if any(Outcome::Matched) {
+                return Ok(ExitCode::from(EXIT_RULE_MATCHED));
+            }
+            if any(Outcome::Undecided) {
+                return Ok(ExitCode::from(EXIT_RULE_UNDECIDED));
+            }
+        }
+        Some(Commands::Downwind {
+            date,
+            width,
+            max_km,
+        }) => {
+            let report = match date {
+                Some(date) => feed.get(parse_date(&date)?)?,
+                None => feed.latest()?,
+            };
+            let sector = Sector::new(width, max_km);
+            let downwind = Downwind {
+                date: report.date,
+                wind_direction: report.wind_direction,
+                sector,
+                towns: report.downwind_towns(&sector),
+            };
+            if machine {
+                emit(&downwind, format)?;
+            } else {
+                print_downwind(&downwind, &render);
+            }
+        }
+        Some(Commands::Watch {
+            interval,
+            exec,
+            webhooks,
+            email,
+            once,
+        }) => {
+            let interval = interval
+                .or(config.watch.interval)
+                .unwrap_or_else(|| "10m".to_string());
+            let interval = popo_cli::watch::parse_interval(&interval)?;
+            let webhooks = match webhooks.is_empty() {
+                true => config.watch.webhooks.unwrap_or_default(),
+                false => webhooks,
+            };
+            let webhooks = webhooks
+                .iter()
+                .map(|spec| Webhook::parse(spec))
+                .collect::<Result<Vec<_>>>()?;
+            let email = match email
+                .map(|list| split_list(&list))
+                .or(config.email.to.clone())
+            {
+                Some(to) => Some((SmtpConfig::from_settings(&config.email)?, to)),
+                None => None,
+            };
+            let exec = exec.or(config.watch.exec);
+            let notifiers = Notifiers {
+                exec: exec.as_deref(),
+                webhooks: &webhooks,
+                email: email.as_ref(),
+            };
+            watch(
+                &feed, interval, &notifiers, once, cache_dir, format, &render,
+            )?;
+        }
+        Some(Commands::Tui { interval }) => {
+            let interval = interval
+                .or(config.watch.interval)
+                .unwrap_or_else(|| "10m".to_string());
+            let interval = popo_cli::watch::parse_interval(&interval)?;
+            // As with `popo alert`, only an explicit --lang en asks for the
+            // machine-rendered narrative.
+            let narrative_lang = cli.lang.unwrap_or(Lang::Es);
+            popo_cli::tui::run(&feed, lang, narrative_lang, render.colour, interval)?;
+        }
+        Some(Commands::Digest { email, days, print }) => {
+            let latest = feed.latest()?;
+            let from = latest
+                .date
+                .checked_sub_days(chrono::Days::new(days - 1))
+                .ok_or(PopoError::InvalidRange {
+                    from: NaiveDate::MIN,
+                    to: latest.date,
+                })?;
+            let reports: Vec<VolcanoReport> = feed
+                .range(from, latest.date)?
+                .into_iter()
+                .filter(|r| r.is_full())
+                .collect();
+            let mut digest = Email::digest(&reports);
+            let custom = template.is_some();
+            if let Some(template) = &template {
+                digest = digest.with_text(template.render_range(&reports)?);
+            }
+            if print && custom {
+                print!("{}", digest.text);
+            } else if print && machine {
+                emit(&digest, format)?;
+            } else if print {
+                print!("Subject: {}\n\n{}", digest.subject, digest.text);
+            } else {
+                let to = email
+                    .map(|list| split_list(&list))
+                    .or(config.email.to.clone())
+                    .ok_or_else(|| {
+                        PopoError::Email(
+                            "no recipients. Pass --email or set `email.to` in the config"
+                                .to_string(),
+                        )
+                    })?;
+                SmtpConfig::from_settings(&config.email)?.send(&digest, &to)?;
+            }
+        }
+        Some(Commands::Publish {
+            target:
+                PublishTarget::Mqtt {
+                    broker,
+                    topic,
+                    qos,
+                    tls,
+                    ca,
+                    client_id,
+                },
+        }) => {
+            let settings = config.mqtt;
+            let broker = broker.or(settings.broker).ok_or_else(|| {
+                PopoError::Mqtt(
+                    "no broker. Pass --broker or set `mqtt.broker` in the config".into(),
+                )
+            })?;
+            let topic = topic
+                .or(settings.topic)
+                .unwrap_or_else(|| "popo".to_string());
+            let mut mqtt = MqttConfig::parse_broker(&broker)?;
+            mqtt.qos = qos.or(settings.qos).unwrap_or(0);
+            mqtt.tls |= tls || ca.is_some();
+            if let Some(path) = ca {
+                mqtt.ca = Some(std::fs::read(&path).map_err(|e| {
+                    PopoError::Mqtt(format!("cannot read {}: {}", path.display(), e))
+                })?);
+            }
+            if let Some(id) = client_id.or(settings.client_id) {
+                mqtt.client_id = id;
+            }
+            if let (Ok(user), Ok(password)) = (
+                std::env::var(popo_cli::mqtt::MQTT_USER_ENV),
+                std::env::var(popo_cli::mqtt::MQTT_PASSWORD_ENV),
+            ) {
+                mqtt.credentials = Some((user, password));
+            }
+            let report = feed.latest()?;
+            let messages = popo_cli::mqtt::messages(&report, &topic)?;
+            mqtt.publish(&messages)?;
+            eprintln!(
+                "Published the report of {} to {} topics under {}/",
+                report.date,
+                messages.len(),
+                topic.trim_end_matches('/')
+            );
+        }
+        Some(Commands::Export {
+            date,
+            from,
+            to,
+            width,
+            max_km,
+        }) => {
+            let reports = match (date, from, to) {
+                (_, Some(from), Some(to)) => feed.range(parse_date(&from)?, parse_date(&to)?)?,
+                (Some(date), _, _) => vec![feed.get(parse_date(&date)?)?],
+                _ => vec![feed.latest()?],
+            };
+            let features = export::features(&reports, &Sector::new(width, max_km));
+            match format {
+                Format::Kml => print!("{}", export::to_kml(&features)),
+                _ => println!("{:#}", export::to_geojson(&features)),
+            }
+        }
+        Some(Commands::Config { .. }) => unreachable!("handled before the config is loaded"),
+        Some(Commands::Here { lat, lon }) => {
+            let location = match (lat, lon) {
+                (Some(lat), Some(lon)) => Coordinates::checked(lat, lon)
+                    .ok_or_else(|| PopoError::InvalidLocation(format!("{},{}", lat, lon)))?,
+                _ => config.location()?.ok_or(PopoError::NoLocation)?,
+            };
+            let report = feed.latest()?;
+            let since = report
+                .date
+                .checked_sub_days(chrono::Days::new(364))
+                .unwrap_or(NaiveDate::MIN);
+            let history = open_search_index(&feed, cache_dir, false)?.ashfall(since, report.date);
+            let risk = LocalRisk::assess(location, &report, &history);
+            if machine {
+                emit(&risk, format)?;
+            } else {
+                print_here(&risk, &render);
+            }
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn run_config(action: ConfigAction, format: Format) -> Result<()> {
+    let layers = Layers::discover();
+    match action {
+        ConfigAction::Show => {
+            let config = Config::load_from(&layers)?;
+            if format != Format::Human {
+                return emit(&config, format);
+            }
+            for path in [&layers.user, &layers.project].into_iter().flatten() {
+                if path.is_file() {
+                    println!("# from {}", path.display());
+                }
+            }
+            print!("{}", config.to_toml()?);
+        }
+        ConfigAction::Set {
+            key,
+            value,
+            project,
+        } => {
+            if project && popo_cli::config::USER_ONLY_KEYS.contains(&key.as_str()) {
+                return Err(PopoError::Config {
+                    path: popo_cli::config::PROJECT_FILE.to_string(),
+                    reason: popo_cli::config::user_only(&key),
+                });
+            }
+            let path = if project {
+                match layers.project {
+                    Some(path) => path,
+                    None => std::path::PathBuf::from(popo_cli::config::PROJECT_FILE),
+                }
+            } else {
+                layers.user.ok_or_else(|| PopoError::Config {
+                    path: "the user config".to_string(),
+                    reason: format!(
+                        "no home directory. Set {} to a file path",
+                        popo_cli::paths::CONFIG_ENV
+                    ),
+                })?
+            };
+            popo_cli::config::set(&path, &key, &value)?;
+            eprintln!("Updated {}", path.display());
+        }
+        ConfigAction::Path if format != Format::Human => {
+            emit(&layers, format)?;
+        }
+        ConfigAction::Path => {
+            let describe = |path: &Option<std::path::PathBuf>| match path {
+                Some(path) if path.is_file() => path.display().to_string(),
+                Some(path) => format!("{} (not created)", path.display()),
+                None => "none".to_string(),
+            };
+            println!("user     {}", describe(&layers.user));
+            println!("project  {}", describe(&layers.project));
+        }
+    }
+    Ok(())
+}
+
+fn print_here(risk: &LocalRisk, render: &Renderer) {
+    println!("{}", render.banner("YOUR LOCATION"));
+    println!();
+    println!(
+        "{}",
+        render.lead(
+            "📍 ",
+            &format!(
+                "{:.4}, {:.4}{}",
+                risk.location.lat,
+                risk.location.lon,
+                risk.nearest_town
+                    .as_ref()
+                    .map(|t| format!(" (near {})", t))
+                    .unwrap_or_default()
+            )
+        )
+    );
+    println!(
+        "{}",
+        render.lead(
+            "🌋 ",
+            &format!(
+                "Crater {:.1} km away, bearing {:.0}°",
+                risk.distance_km, risk.bearing_to_crater_deg
+            )
+        )
+    );
+    println!(
+        "{}",
+        render.lead("📅 ", &format!("Report of {}", risk.date))
+    );
+    println!();
+
+    let line = match (risk.exclusion_radius_km, risk.inside_exclusion) {
+        (Some(km), Some(true)) => render.line(
+            "🚫 ",
+            &format!("INSIDE the {} km exclusion radius. Leave the area.", km),
+        ),
+        (Some(km), _) => render.line("✅ ", &format!("Outside the {} km exclusion radius", km)),
+        _ => render.line("ℹ️  ", "The report states no exclusion radius"),
+    };
+    println!("{}", line);
+    let line = match (risk.wind_direction, risk.in_plume) {
+        (Some(dir), Some(true)) => render.line(
+            "🌫️  ",
+            &format!("The plume is heading {:?}, towards you", dir),
+        ),
+        (Some(dir), _) => render.line(
+            "✅ ",
+            &format!("The plume is heading {:?}, away from you", dir),
+        ),
+        _ => render.line("ℹ️  ", "The report states no wind direction"),
+    };
+    println!("{}", line);
+
+    let ashfall = &risk.ashfall;
+    let area = popo_cli::here::AREA_KM;
+    let line = match (ashfall.last, ashfall.since) {
+        (Some(last), Some(since)) => render.line(
+            "🌫️  ",
+            &format!(
+                "Ash reported within {} km on {} of {} reports since {}, most recently {}",
+                area, ashfall.days, ashfall.scanned, since, last
+            ),
+        ),
+        (None, Some(since)) => render.line(
+            "✅ ",
+            &format!(
+                "No ash reported within {} km in {} reports since {}",
+                area, ashfall.scanned, since
+            ),
+        ),
+        (_, None) => render.line("ℹ️  ", "No earlier reports to check for ash"),
+    };
+    println!("{}", line);
+    if ashfall.last.is_some() {
+        for line in render.indented(&ashfall.towns.join("; "), 6) {
+            println!("{}", line);
+        }
+    }
+    println!();
+}
+
+fn print_rule_check(check: &RuleCheck, render: &Renderer) {
+    println!("{}", render.banner("RULE CHECK"));
+    println!();
+    println!("{}", render.lead("📅 ", &check.date.to_string()));
+    println!();
+
+    let width = check
+        .rules
+        .iter()
+        .map(|r| r.name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let matched = check
+        .rules
+        .iter()
+        .filter(|r| r.outcome == Outcome::Matched)
+        .count();
+    for rule in &check.rules {
+        let (icon, word) = match rule.outcome {
+            Outcome::Matched => ("⚠️  ", "MATCHED"),
+            Outcome::Undecided => ("❔ ", "UNDECIDED"),
+            Outcome::NotMatched => continue,
+        };
+        let note = if rule.missing_data {
+            "  (data missing)"
+        } else {
+            ""
+        };
+        let line = match render.style {
+            Style::Rich | Style::Plain => {
+                let pad = width - rule.name.chars().count();
+                let mark = if render.style == Style::Rich {
+                    icon
+                } else {
+                    ""
+                };
+                format!(
+                    "  {}{}{}  {}{}",
+                    mark,
+                    rule.name,
+                    " ".repeat(pad),
+                    rule.when,
+                    note
+                )
+            }
+            Style::Accessible => format!("{}: {}, {}{}", word, rule.name, rule.when, note),
+        };
+        println!("{}", line);
+    }
+    if matched == 0 {
+        println!(
+            "{}",
+            render.line(
+                "✅ ",
+                &format!("No rule matched ({} checked).", check.rules.len())
+            )
+        );
+    } else {
+        println!();
+        println!(
+            "{}",
+            render.line(
+                "",
+                &format!(
+                    "{} of {} {} matched.",
+                    matched,
+                    check.rules.len(),
+                    plural(check.rules.len() as u32, "rule", "rules")
+                )
+            )
+        );
+    }
+    println!();
+}
+
+fn print_downwind(downwind: &Downwind, render: &Renderer) {
+    println!("{}", render.banner("DOWNWIND TOWNS"));
+    println!();
+    println!("{}", render.lead("📅 ", &downwind.date.to_string()));
+    println!();
+
+    let Some(dir) = &downwind.wind_direction else {
+        println!(
+            "{}",
+            render.line("ℹ️  ", "This report states no wind direction.")
+        );
+        println!();
+        return;
+    };
+    println!(
+        "{}",
+        render.lead(
+            "🧭 ",
+            &format!(
+                "Plume heading {:?}, towns within {}° either side and {} km",
+                dir,
+                downwind.sector.width_deg / 2.0,
+                downwind.sector.max_km
+            )
+        )
+    );
+    println!();
+
+    if downwind.towns.is_empty() {
+        println!(
+            "{}",
+            render.line("✅ ", "No bundled town lies in that sector.")
+        );
+        println!();
+        return;
+    }
+    let width = downwind
+        .towns
+        .iter()
+        .map(|t| t.canonical.chars().count())
+        .max()
+        .unwrap_or(0);
+    for town in &downwind.towns {
+        let line = match render.style {
+            Style::Rich | Style::Plain => {
+                let pad = width - town.canonical.chars().count();
+                render.line(
+                    "🏘️  ",
+                    &format!(
+                        "{}{}  {:>5.1} km  {:>3.0}°",
+                        town.canonical,
+                        " ".repeat(pad),
+                        town.distance_km,
+                        town.bearing_deg
+                    ),
+                )
+            }
+            Style::Accessible => format!(
+                "{}, {:.1} km, bearing {:.0}°",
+                town.canonical, town.distance_km, town.bearing_deg
+            ),
+        };
+        println!("{}", line);
+    }
+    println!();
+}
+
+fn print_diff(diff: &ReportDiff, feeds: Option<(&Feed, &Feed)>, render: &Renderer) {
+    println!("{}", render.banner("REPORT DIFF"));
+    println!();
+    let heading = match feeds {
+        Some((a, b)) if diff.from == diff.to => {
+            format!("{} in {} → {}", diff.from, a.base(), b.base())
+        }
+        Some((a, b)) => format!(
+            "{} in {} → {} in {}",
+            diff.from,
+            a.base(),
+            diff.to,
+            b.base()
+        ),
+        None => format!("{} → {}", diff.from, diff.to),
+    };
+    println!("{}", render.lead("📅 ", &heading));
+    println!();
+    if diff.is_empty() {
+        println!("{}", render.line("✅ ", "No differences."));
+    }
+    for change in &diff.changes {
+        for line in render.bullet(&change.to_string()) {
+            println!("{}", line);
+        }
+    }
+    println!();
+}
+
+fn print_comparison(comparison: &FeedComparison, render: &Renderer) {
+    println!("{}", render.banner("FEED COMPARISON"));
+    println!();
+    let count = |n: usize| n.to_string();
+    for (icon, label, value) in [
+        ("📡 ", "Feed A", comparison.a.clone()),
+        ("📡 ", "Feed B", comparison.b.clone()),
+        ("📅 ", "Dates in both", count(comparison.compared)),
+        ("➖ ", "Only in A", count(comparison.only_in_a.len())),
+        ("➖ ", "Only in B", count(comparison.only_in_b.len())),
+        (
+            "✏️  ",
+            "Reports differing",
+            count(comparison.differing.len()),
+        ),
+        (
+            "❌ ",
+            "Reports unreadable",
+            count(comparison.unreadable.len()),
+        ),
+    ] {
+        println!("{}", render.field(icon, label, &value));
+    }
+    println!();
+
+    for (title, dates) in [
+        ("ONLY IN A", &comparison.only_in_a),
+        ("ONLY IN B", &comparison.only_in_b),
+    ] {
+        if dates.is_empty() {
+            continue;
+        }
+        println!("{}", render.section(title));
+        for line in render.paragraph(&date_runs(dates).join(", ")) {
+            println!("{}", line);
+        }
+        println!();
+    }
+    if !comparison.differing.is_empty() {
+        println!("{}", render.section("DIFFERING"));
+        for mismatch in &comparison.differing {
+            println!("{}", render.line("📅 ", &mismatch.date.to_string()));
+            for line in mismatch.explain() {
+                println!("{}", render.item(&line));
+            }
+        }
+        println!();
+    }
+    if !comparison.unreadable.is_empty() {
+        println!("{}", render.section("UNREADABLE"));
+        for unreadable in &comparison.unreadable {
+            println!(
+                "{}",
+                render.line(
+                    "📅 ",
+                    &format!("{} in {}", unreadable.date, unreadable.feed)
+                )
+            );
+            println!("{}", render.item(&unreadable.reason));
+        }
+        println!();
+    }
+
+    let verdict = match comparison.is_divergent() {
+        true => render.line("⚠️  ", "The feeds diverge."),
+        false => render.line("✅ ", "The feeds match."),
+    };
+    println!("{}", verdict);
+    println!();
+}
+
+/// Consecutive dates folded into `first..last`, so a missing year reads as
+/// one entry.
+fn date_runs(dates: &[NaiveDate]) -> Vec<String> {
+    let mut runs: Vec<(NaiveDate, NaiveDate)> = Vec::new();
+    for &date in dates {
+        match runs.last_mut() {
+            Some((_, last)) if last.succ_opt() == Some(date) => *last = date,
+            _ => runs.push((date, date)),
+        }
+    }
+    runs.into_iter()
+        .map(|(first, last)| match first == last {
+            true => first.to_string(),
+            false => format!("{}..{}", first, last),
+        })
+        .collect()
+}
+
+fn print_ashfall_watch(watch: &AshfallWatch, reports: &[VolcanoReport], render: &Renderer) {
+    println!("{}", render.banner("ASHFALL WATCH"));
+    println!();
+    let dates: Vec<NaiveDate> = reports.iter().map(|r| r.date).collect();
+    print_scanned(&dates, render);
+    if watch.watching.is_empty() {
+        println!(
+            "{}",
+            render.line(
+                "ℹ️  ",
+                "No watchlist. Pass --watch \"Town,Town\" or set POPO_WATCHLIST."
+            )
+        );
+        println!();
+        return;
+    }
+    println!(
+        "{}",
+        render.lead("👀 ", &format!("Watching: {}", watch.watching.join(", ")))
+    );
+    println!();
+
+    if watch.hits.is_empty() {
+        println!(
+            "{}",
+            render.line(
+                "✅ ",
+                &format!(
+                    "No watched town named in {} {}.",
+                    watch.scanned,
+                    plural(watch.scanned as u32, "report", "reports")
+                )
+            )
+        );
+        println!();
+        return;
+    }
+    for hit in &watch.hits {
+        println!(
+            "{}",
+            render.line("⚠️  ", &format!("{}  {}", hit.date, hit.towns.join(", ")))
+        );
+        for raw in &hit.ashfall_reports {
+            for line in render.indented(raw, 19) {
+                println!("{}", line);
+            }
+        }
+    }
+    println!();
+}
+
+fn print_ashfall_ranking(
+    ranked: &[TownCount],
+    limit: usize,
+    dates: &[NaiveDate],
+    render: &Renderer,
+) {
+    println!("{}", render.banner("ASHFALL BY TOWN"));
+    println!();
+    print_scanned(dates, render);
+
+    if ranked.is_empty() {
+        println!(
+            "{}",
+            render.line("ℹ️  ", "No ashfall reported in this range.")
+        );
+        println!();
+        return;
+    }
+    let width = ranked
+        .iter()
+        .take(limit)
+        .map(|t| t.name.chars().count())
+        .max()
+        .unwrap_or(0);
+    for (i, town) in ranked.iter().take(limit).enumerate() {
+        let line = match render.style {
+            Style::Rich | Style::Plain => {
+                let pad = width - town.name.chars().count();
+                format!(
+                    "  {:>3}. {}{}  {:>4} {}  last {}",
+                    i + 1,
+                    town.name,
+                    " ".repeat(pad),
+                    town.days,
+                    plural(town.days as u32, "day ", "days"),
+                    town.last
+                )
+            }
+            Style::Accessible => format!(
+                "{}. {}, {} {}, last {}",
+                i + 1,
+                town.name,
+                town.days,
+                plural(town.days as u32, "day", "days"),
+                town.last
+            ),
+        };
+        println!("{}", line);
+    }
+    if ranked.len() > limit {
+        println!();
+        let more = format!(
+            "{} {} more. Use --limit to see them.",
+            if render.style == Style::Rich {
+                "… and"
+            } else {
+                "... and"
+            },
+            ranked.len() - limit
+        );
+        println!("{}", render.line("", &more));
+    }
+    println!();
+}
+
+fn print_scanned(dates: &[NaiveDate], render: &Renderer) {
+    let text = match (dates.first(), dates.last()) {
+        (Some(first), Some(last)) if first != last => {
+            format!("{} to {} ({} reports)", first, last, dates.len())
+        }
+        (Some(only), _) => only.to_string(),
+        _ => "No reports in range".to_string(),
+    };
+    println!("{}", render.lead("📅 ", &text));
+    println!();
 }
 
-#[derive(Subcommand)]
-enum Commands {
-    /// Show the latest report (human-readable)
-    Latest,
+/// Where `popo watch` sends each change, and `popo check` each match,
+/// besides stdout.
+struct Notifiers<'a> {
+    exec: Option<&'a str>,
+    webhooks: &'a [Webhook],
+    email: Option<&'a (SmtpConfig, Vec<String>)>,
+}
 
-    /// Show the latest report as JSON
-    Json,
+impl Notifiers<'_> {
+    /// Post and mail `notification`. A failure is logged rather than
+    /// returned, so one dead endpoint does not keep it from the others.
+    fn send(&self, client: &reqwest::blocking::Client, notification: &Notification) {
+        for webhook in self.webhooks {
+            if let Err(err) = webhook.send(client, notification) {
+                eprintln!("{} {}", now(), err);
+            }
+        }
+        if let Some((smtp, to)) = self.email {
+            if let Err(err) = smtp.send(&Email::alert(notification), to) {
+                eprintln!("{} {}", now(), err);
+            }
+        }
+    }
+}
 
-    /// Show the current alert status in detail
-    Alert,
+/// Poll until interrupted, or once with `once`. Network trouble is reported
+/// and retried on the next poll rather than ending the watch.
+fn watch(
+    feed: &Feed,
+    interval: std::time::Duration,
+    notifiers: &Notifiers,
+    once: bool,
+    cache_dir: Option<&Path>,
+    format: Format,
+    render: &Renderer,
+) -> Result<()> {
+    let client = popo_cli::notify::client();
+    let path = WatchState::default_path(feed.base(), cache_dir);
+    let mut state = match &path {
+        Some(path) => WatchState::open(path, feed.base())?,
+        None => WatchState::new(feed.base()),
+    };
+    if let Some(last) = &state.last {
+        eprintln!("Watching {} from the report of {}", feed.base(), last.date);
+    }
 
-    /// Show the report for a specific date (YYYY-MM-DD)
-    Get {
-        /// Date in YYYY-MM-DD format (e.g. 2022-03-22)
-        date: String,
+    loop {
+        match feed.latest() {
+            Ok(report) => {
+                let first = state.last.is_none();
+                let changes = state.update(report.clone());
+                if first {
+                    eprintln!(
+                        "Watching {} from the report of {}",
+                        feed.base(),
+                        report.date
+                    );
+                }
+                if !changes.is_empty() {
+                    if format == Format::Human {
+                        print_changes(&report, &changes, render);
+                    } else {
+                        let rows: Vec<_> = changes
+                            .iter()
+                            .map(|change| WatchEvent {
+                                date: report.date,
+                                change,
+                            })
+                            .collect();
+                        emit(&rows, format)?;
+                    }
+                    if let Some(command) = notifiers.exec {
+                        run_watch_command(command, &report)?;
+                    }
+                    notifiers.send(
+                        &client,
+                        &Notification {
+                            report: &report,
+                            reasons: changes.iter().map(|c| c.to_string()).collect(),
+                        },
+                    );
+                }
+                if let Some(path) = &path {
+                    state.save(path)?;
+                }
+            }
+            Err(err) if !once && err.is_transient() => {
+                eprintln!("{} {}. Trying again at the next poll.", now(), err);
+            }
+            Err(err) => return Err(err),
+        }
 
-        /// Output as JSON instead of human-readable
-        #[arg(long)]
-        json: bool,
-    },
+        if once {
+            return Ok(());
+        }
+        std::thread::sleep(interval);
+    }
+}
 
-    /// Show what the feed covers
-    Index {
-        /// Output as JSON instead of human-readable
-        #[arg(long)]
-        json: bool,
-    },
+fn print_changes(report: &VolcanoReport, changes: &[Change], render: &Renderer) {
+    let level = match (render.style, report.alert_level) {
+        (Style::Rich, Some(level)) => level.emoji().to_string(),
+        (Style::Rich, None) => "⚪".to_string(),
+        (_, Some(level)) => format!("{:?}", level).to_uppercase(),
+        (_, None) => "-".to_string(),
+    };
+    println!("{} {} {}", now(), level, report.date);
+    for change in changes {
+        let bullet = if render.style == Style::Rich {
+            "•"
+        } else {
+            "-"
+        };
+        println!("    {} {}", bullet, change);
+    }
 }
 
-fn main() {
-    if let Err(err) = run() {
-        eprintln!("Error: {}", err);
-        if let PopoError::Network(_) = err {
-            eprintln!("\nThe feed could not be reached. Check your connection, or point");
-            eprintln!("popo at another mirror with --feed / POPO_FEED_BASE.");
+fn run_watch_command(command: &str, report: &VolcanoReport) -> Result<()> {
+    let json = serde_json::to_string(report).map_err(|e| PopoError::Serialize(e.to_string()))?;
+    match popo_cli::watch::run_command(command, &json) {
+        Ok(status) if !status.success() => {
+            eprintln!("{} `{}` exited with {}", now(), command, status)
         }
-        std::process::exit(1);
+        Ok(_) => {}
+        Err(err) => eprintln!("{} Could not run `{}`: {}", now(), command, err),
     }
+    Ok(())
 }
 
-fn run() -> Result<()> {
-    let cli = Cli::parse();
-    let feed = match &cli.feed {
-        Some(base) => Feed::with_base(base.clone()),
-        None => Feed::new(),
+/// "a@x.org, b@y.org" → two addresses.
+fn split_list(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn now() -> String {
+    chrono::Local::now()
+        .format("[%Y-%m-%d %H:%M:%S]")
+        .to_string()
+}
+
+/// Load the persistent search index for this feed and bring it up to date.
+/// Without a cache directory the index is built in memory for this run only.
+fn open_search_index(feed: &Feed, cache_dir: Option<&Path>, rebuild: bool) -> Result<SearchIndex> {
+    let path = SearchIndex::default_path(feed.base(), cache_dir);
+    let mut index = match &path {
+        Some(path) if !rebuild => SearchIndex::open(path, feed.base())?,
+        _ => SearchIndex::new(feed.base()),
     };
 
-    match cli.command {
-        Some(Commands::Json) | None => {
-            print_json(&feed.latest()?)?;
-        }
-        Some(Commands::Latest) => {
-            print_human_readable(&feed.latest()?);
-        }
-        Some(Commands::Alert) => {
-            print_alert_info(&feed.latest()?);
+    if index.is_empty() {
+        eprintln!("Indexing the archive. This reads every report once.");
+    }
+    let fetched = index.refresh(feed)?;
+    if fetched > 0 {
+        if let Some(path) = &path {
+            index.save(path)?;
         }
-        Some(Commands::Get { date, json }) => {
-            let parsed_date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
-                .map_err(|_| PopoError::InvalidDate(date.clone()))?;
+    }
+    Ok(index)
+}
 
-            let report = feed.get(parsed_date)?;
-            if json {
-                print_json(&report)?;
-            } else {
-                print_human_readable(&report);
-            }
-        }
-        Some(Commands::Index { json }) => {
-            let index = feed.index()?;
-            if json {
-                print_json(&index)?;
-            } else {
-                print_index(&index);
-            }
+/// "orange" or "naranja", in any case.
+fn parse_alert_level(text: &str) -> std::result::Result<AlertLevel, String> {
+    match text.to_lowercase().as_str() {
+        "green" => Ok(AlertLevel::Green),
+        "yellow" => Ok(AlertLevel::Yellow),
+        "orange" => Ok(AlertLevel::Orange),
+        "red" => Ok(AlertLevel::Red),
+        _ => AlertLevel::from_spanish(text)
+            .ok_or_else(|| "expected green, yellow, orange or red".to_string()),
+    }
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| PopoError::InvalidDate(date.to_string()))
+}
+
+/// One line of `popo watch` in a machine format.
+#[derive(Serialize)]
+struct WatchEvent<'a> {
+    date: NaiveDate,
+    change: &'a Change,
+}
+
+impl<'c> Tabular for WatchEvent<'c> {
+    type Row<'a>
+        = &'a WatchEvent<'c>
+    where
+        Self: 'a;
+
+    const COLUMNS: &'static [&'static str] = &[
+        "date",
+        "change.type",
+        "change.previous",
+        "change.from",
+        "change.to",
+        "change.places",
+    ];
+    const RECORD: bool = true;
+
+    fn rows(&self) -> Vec<&WatchEvent<'c>> {
+        vec![self]
+    }
+}
+
+/// What `popo alert` prints in a machine format.
+#[derive(Serialize)]
+struct AlertStatus {
+    date: NaiveDate,
+    alert_level: Option<AlertLevel>,
+    alert_phase: Option<String>,
+    narrative: Option<AlertNarrative>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum AlertNarrative {
+    Spanish(Narrative),
+    English(EnglishNarrative),
+}
+
+impl AlertStatus {
+    fn new(report: &VolcanoReport, lang: Lang) -> Self {
+        let narrative = report.narrative().map(|n| match lang {
+            Lang::Es => AlertNarrative::Spanish(n),
+            Lang::En => AlertNarrative::English(n.to_english()),
+        });
+        AlertStatus {
+            date: report.date,
+            alert_level: report.alert_level,
+            alert_phase: report.alert_phase.clone(),
+            narrative,
         }
     }
+}
 
-    Ok(())
+impl Tabular for AlertStatus {
+    type Row<'a> = &'a AlertStatus;
+
+    const COLUMNS: &'static [&'static str] = &[
+        "date",
+        "alert_level",
+        "alert_phase",
+        "narrative.headline",
+        "narrative.observations",
+        "narrative.scenarios",
+        "narrative.recommendations",
+        "narrative.disclaimer",
+    ];
+    const RECORD: bool = true;
+
+    fn rows(&self) -> Vec<&AlertStatus> {
+        vec![self]
+    }
+}
+
+/// What `popo windrose` prints in a machine format.
+#[derive(Serialize)]
+struct WindRoseTable {
+    from: NaiveDate,
+    to: NaiveDate,
+    rose: WindRose,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    by_month: Option<BTreeMap<u32, WindRose>>,
+}
+
+/// One petal of `popo windrose`, with its month when split by month.
+#[derive(Serialize)]
+struct PetalRow<'a> {
+    month: Option<u32>,
+    #[serde(flatten)]
+    petal: &'a Petal,
+}
+
+/// The petals of the whole range, then those of each month.
+impl Tabular for WindRoseTable {
+    type Row<'a> = PetalRow<'a>;
+
+    const COLUMNS: &'static [&'static str] = &["month", "direction", "count", "frequency"];
+
+    fn rows(&self) -> Vec<PetalRow<'_>> {
+        let months = self.by_month.iter().flatten();
+        std::iter::once((None, &self.rose))
+            .chain(months.map(|(month, rose)| (Some(*month), rose)))
+            .flat_map(|(month, rose)| {
+                rose.petals
+                    .iter()
+                    .map(move |petal| PetalRow { month, petal })
+            })
+            .collect()
+    }
+}
+
+/// What `popo check` prints in a machine format.
+#[derive(Serialize)]
+struct RuleCheck {
+    date: NaiveDate,
+    rules: Vec<RuleResult>,
+}
+
+impl Tabular for RuleCheck {
+    type Row<'a> = &'a RuleResult;
+
+    const COLUMNS: &'static [&'static str] = &["name", "when", "outcome", "missing_data"];
+
+    fn rows(&self) -> Vec<&RuleResult> {
+        self.rules.iter().collect()
+    }
+}
+
+/// What `popo downwind` prints in a machine format.
+#[derive(Serialize)]
+struct Downwind {
+    date: NaiveDate,
+    wind_direction: Option<WindDirection>,
+    sector: Sector,
+    towns: Vec<DownwindTown>,
+}
+
+impl Tabular for Downwind {
+    type Row<'a> = &'a DownwindTown;
+
+    const COLUMNS: &'static [&'static str] = &[
+        "municipality",
+        "state",
+        "canonical",
+        "coordinates.lat",
+        "coordinates.lon",
+        "distance_km",
+        "bearing_deg",
+        "offset_deg",
+    ];
+
+    fn rows(&self) -> Vec<&DownwindTown> {
+        self.towns.iter().collect()
+    }
+}
+
+/// What `popo ashfall` prints in a machine format.
+#[derive(Serialize)]
+struct AshfallWatch {
+    watching: Vec<String>,
+    scanned: usize,
+    hits: Vec<WatchHit>,
+}
+
+impl Tabular for AshfallWatch {
+    type Row<'a> = &'a WatchHit;
+
+    const COLUMNS: &'static [&'static str] = &["date", "towns", "ashfall_reports"];
+
+    fn rows(&self) -> Vec<&WatchHit> {
+        self.hits.iter().collect()
+    }
 }
 
-fn print_json<T: serde::Serialize>(value: &T) -> Result<()> {
-    let json = serde_json::to_string_pretty(value).map_err(|e| PopoError::Parse(e.to_string()))?;
-    println!("{}", json);
+fn emit<T: Tabular + ?Sized>(value: &T, format: Format) -> Result<()> {
+    print!("{}", popo_cli::format::render(value, format)?);
     Ok(())
 }
 
 /// Render a counter that may legitimately be absent from the archive.
-fn counter(value: Option<u32>) -> String {
+fn counter(value: Option<u32>, lang: Lang) -> String {
     match value {
         Some(v) => v.to_string(),
-        None => "not reported".to_string(),
+        None => lang.messages().not_reported.to_string(),
     }
 }
 
-fn print_human_readable(report: &VolcanoReport) {
-    println!("╔═══════════════════════════════════════════════════════════════╗");
-    println!("║          POPOCATÉPETL VOLCANO MONITORING REPORT               ║");
-    println!("╚═══════════════════════════════════════════════════════════════╝");
+fn print_human_readable(report: &VolcanoReport, lang: Lang, render: &Renderer) {
+    let m = lang.messages();
+    println!("{}", render.banner(m.report_title));
     println!();
-    println!("📅 Report Date: {}", report.date);
+    println!(
+        "{}",
+        render.lead(
+            "📅 ",
+            &format!("{}: {}", m.report_date, lang.date(report.date))
+        )
+    );
     if report.partial {
-        println!("ℹ️  Counters only: this day was recorded from a neighbouring");
-        println!("   report's 15-day chart window, so it carries no narrative,");
-        println!("   alert status, wind or media.");
+        for line in render.note("ℹ️  ", m.partial_note) {
+            println!("{}", line);
+        }
     }
     println!();
 
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!("  SEISMIC ACTIVITY (Last 24 Hours)");
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("{}", render.section(m.seismic_activity));
     println!();
     println!(
-        "  💨 Exhalations:               {}",
-        counter(report.exhalations)
+        "{}",
+        render.field("💨 ", m.exhalations, &counter(report.exhalations, lang))
     );
     println!(
-        "  💥 Explosions:                {}",
-        counter(report.explosions)
+        "{}",
+        render.field("💥 ", m.explosions, &counter(report.explosions, lang))
     );
     println!(
-        "  🌍 Volcanotectonic events:    {}",
-        counter(report.volcanotectonic_events)
+        "{}",
+        render.field(
+            "🌍 ",
+            m.volcanotectonic_events,
+            &counter(report.volcanotectonic_events, lang)
+        )
     );
     println!();
+    let minutes = |value: String| format!("{} {}", value, m.minutes);
     println!(
-        "  ⏱️  Total tremor:              {} minutes",
-        counter(report.tremor_minutes_total)
+        "{}",
+        render.field(
+            "⏱️  ",
+            m.tremor_total,
+            &minutes(counter(report.tremor_minutes_total, lang))
+        )
     );
     if let Some(hf) = report.tremor_high_frequency_minutes {
-        println!("     └─ High frequency:        {} minutes", hf);
+        println!(
+            "{}",
+            render.subfield(m.tremor_high_frequency, &minutes(hf.to_string()))
+        );
     }
     if let Some(h) = report.tremor_harmonic_minutes {
-        println!("     └─ Harmonic:              {} minutes", h);
+        println!(
+            "{}",
+            render.subfield(m.tremor_harmonic, &minutes(h.to_string()))
+        );
     }
     println!();
 
     if let Some(level) = report.alert_level {
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-        println!("  ALERT STATUS");
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!("{}", render.section(m.alert_status));
         println!();
-        println!("  {} Alert Level: {:?}", level.emoji(), level);
+        println!(
+            "{}",
+            render.line(
+                &render.level_icon(level),
+                &format!(
+                    "{}: {}",
+                    m.alert_level,
+                    render.level(level, lang.alert_level(level))
+                )
+            )
+        );
         if let Some(phase) = &report.alert_phase {
-            println!("  📋 Phase: {}", phase);
+            println!("{}", render.line("📋 ", &format!("{}: {}", m.phase, phase)));
+        }
+        println!();
+    }
+
+    let facts = describe_events(&report.events(), lang);
+    if !facts.is_empty() {
+        println!("{}", render.section(m.from_narrative));
+        println!();
+        for (icon, fact) in facts {
+            println!("{}", render.line(icon, &fact));
         }
         println!();
     }
 
-    if !report.ashfall_reports.is_empty() {
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-        println!("  ASHFALL REPORTED");
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    let ashfall = report.ashfall_places();
+    if !ashfall.is_empty() {
+        println!("{}", render.section(m.ashfall_reported));
         println!();
-        for place in &report.ashfall_reports {
-            println!("  🌫️  {}", place);
+        for place in &ashfall.resolved {
+            println!("{}", render.line("🌫️  ", &place.canonical));
+        }
+        for name in &ashfall.unresolved {
+            println!(
+                "{}",
+                render.line("🌫️  ", &format!("{} ({})", name, m.not_in_gazetteer))
+            );
         }
         println!();
     }
 
     if let Some(dir) = &report.wind_direction {
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-        println!("  ENVIRONMENTAL CONDITIONS");
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!("{}", render.section(m.environmental_conditions));
         println!();
-        println!("  🧭 Wind Direction: {:?}", dir);
+        println!(
+            "{}",
+            render.line(
+                "🧭 ",
+                &format!(
+                    "{}: {}{} ({}°)",
+                    m.wind_direction,
+                    render.arrow(*dir),
+                    lang.wind_direction(*dir),
+                    dir.degrees()
+                )
+            )
+        );
         println!();
     }
 
     if let Some(so2) = report.so2_emissions_tons_per_day {
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-        println!("  EMISSIONS");
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!("{}", render.section(m.emissions));
         println!();
-        println!("  ☁️  SO₂ Emissions: {} tons/day", so2);
+        println!(
+            "{}",
+            render.line(
+                "☁️  ",
+                &format!("{}: {} {}", m.so2_emissions, so2, m.tons_per_day)
+            )
+        );
         if let Some(date) = report.so2_measurement_date {
-            println!("     Measured: {}", date);
+            println!(
+                "{}",
+                render.item(&format!("{}: {}", m.measured, lang.date(date)))
+            );
         }
         println!();
     }
 
     if !report.image_urls.is_empty() || !report.video_urls.is_empty() {
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-        println!("  MEDIA");
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!("{}", render.section(m.media));
         println!();
-        if !report.image_urls.is_empty() {
-            println!("  📷 Images: {} available", report.image_urls.len());
-            for (i, url) in report.image_urls.iter().take(3).enumerate() {
-                println!("     {}. {}", i + 1, url);
+        for (icon, available, urls) in [
+            ("📷 ", m.images_available, &report.image_urls),
+            ("🎥 ", m.videos_available, &report.video_urls),
+        ] {
+            if urls.is_empty() {
+                continue;
             }
-        }
-        if !report.video_urls.is_empty() {
-            println!("  🎥 Videos: {} available", report.video_urls.len());
-            for (i, url) in report.video_urls.iter().take(3).enumerate() {
-                println!("     {}. {}", i + 1, url);
+            println!("{}", render.line(icon, &fill(available, &[&urls.len()])));
+            for (i, url) in urls.iter().take(3).enumerate() {
+                println!("{}", render.item(&format!("{}. {}", i + 1, url)));
             }
         }
         println!();
     }
 
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!("  SOURCE");
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("{}", render.section(m.source));
     println!();
     if let Some(url) = report.source_url.as_deref().filter(|u| !u.is_empty()) {
-        println!("  🔗 {}", url);
+        println!("{}", render.line("🔗 ", url));
     }
     if let Some(ingested) = report.ingested_at {
         println!(
-            "  ⏰ Ingested: {}",
-            ingested.format("%Y-%m-%d %H:%M:%S UTC")
+            "{}",
+            render.line(
+                "⏰ ",
+                &format!(
+                    "{}: {}",
+                    m.ingested,
+                    ingested.format("%Y-%m-%d %H:%M:%S UTC")
+                )
+            )
         );
     }
     println!();
 }
 
-fn print_alert_info(report: &VolcanoReport) {
+/// One icon and line per extracted event, with media references rolled into
+/// a single count since the URLs are already listed under MEDIA.
+fn describe_events(events: &[NarrativeEvent], lang: Lang) -> Vec<(&'static str, String)> {
+    let m = lang.messages();
+    let mut lines = Vec::new();
+    let (mut images, mut videos) = (0, 0);
+
+    for event in events {
+        match &event.kind {
+            EventKind::Explosion {
+                count,
+                times,
+                intensity,
+            } => {
+                let size = match intensity {
+                    Some(Intensity::Minor) => format!(" ({})", m.minor),
+                    Some(Intensity::Moderate) => format!(" ({})", m.up_to_moderate),
+                    Some(Intensity::Major) => format!(" ({})", m.up_to_major),
+                    None => String::new(),
+                };
+                lines.push((
+                    "💥 ",
+                    format!(
+                        "{}{}{}",
+                        lang.count(*count, m.explosion),
+                        size,
+                        at_times(times, lang)
+                    ),
+                ));
+            }
+            EventKind::AshColumn { height_km } => {
+                let height = format!("{:.1}", height_km);
+                lines.push(("🌋 ", fill(m.ash_column, &[&height])));
+            }
+            EventKind::IncandescentFragments => {
+                lines.push(("🔥 ", m.incandescent_fragments.to_string()));
+            }
+            EventKind::Volcanotectonic {
+                count,
+                times,
+                magnitudes,
+            } => {
+                let mut line = format!(
+                    "{}{}",
+                    lang.count(*count, m.volcanotectonic_earthquake),
+                    at_times(times, lang)
+                );
+                if !magnitudes.is_empty() {
+                    let list: Vec<_> = magnitudes.iter().map(|m| format!("{:.1}", m)).collect();
+                    line.push_str(&format!(", {} {}", m.magnitude, list.join(", ")));
+                }
+                lines.push(("🌍 ", line));
+            }
+            EventKind::ExclusionRadius { km } => {
+                lines.push(("⭕ ", fill(m.exclusion_radius, &[km])));
+            }
+            EventKind::Media { kind, .. } => match kind {
+                MediaKind::Image => images += 1,
+                MediaKind::Video => videos += 1,
+            },
+        }
+    }
+
+    if images + videos > 0 {
+        lines.push((
+            "📎 ",
+            fill(
+                m.refers_to,
+                &[&lang.count(images, m.image), &lang.count(videos, m.video)],
+            ),
+        ));
+    }
+    lines
+}
+
+fn plural<'a>(count: u32, one: &'a str, many: &'a str) -> &'a str {
+    if count == 1 {
+        one
+    } else {
+        many
+    }
+}
+
+fn at_times(times: &[chrono::NaiveTime], lang: Lang) -> String {
+    if times.is_empty() {
+        return String::new();
+    }
+    let list: Vec<_> = times
+        .iter()
+        .map(|t| t.format("%H:%M").to_string())
+        .collect();
+    format!(
+        " {}",
+        fill(lang.messages().at_local_time, &[&list.join(", ")])
+    )
+}
+
+/// The alert level and narrative. `narrative_lang` picks the narrative's
+/// language and `lang` everything around it.
+fn print_alert_info(report: &VolcanoReport, narrative_lang: Lang, lang: Lang, render: &Renderer) {
+    let m = lang.messages();
     println!();
-    println!("╔═══════════════════════════════════════════════════════════════╗");
-    println!("║                    ALERT STATUS DETAILS                       ║");
-    println!("╚═══════════════════════════════════════════════════════════════╝");
+    println!("{}", render.banner(m.alert_details));
     println!();
 
     match report.alert_level {
         Some(level) => {
-            let color_desc = match level {
-                AlertLevel::Green => "GREEN",
-                AlertLevel::Yellow => "YELLOW",
-                AlertLevel::Orange => "ORANGE",
-                AlertLevel::Red => "RED",
-            };
+            let name = lang.alert_level(level).to_uppercase();
             println!(
-                "  {} Current Alert: {} - {}",
-                level.emoji(),
-                color_desc,
-                report.alert_phase.as_deref().unwrap_or(color_desc)
+                "{}",
+                render.line(
+                    &render.level_icon(level),
+                    &format!(
+                        "{}: {} - {}",
+                        m.current_alert,
+                        render.level(level, &name),
+                        report.alert_phase.as_deref().unwrap_or(&name)
+                    )
+                )
             );
         }
         None => {
-            println!("  ℹ️  No alert status published for {}.", report.date);
-            println!("     This day was recorded from a neighbouring report's");
-            println!("     counter window. Try a nearby date.");
+            let text = fill(m.no_alert_status, &[&lang.date(report.date)]);
+            for line in render.note("ℹ️  ", &text) {
+                println!("{}", line);
+            }
         }
     }
-    println!("  📅 As of: {}", report.date);
+    println!(
+        "{}",
+        render.line("📅 ", &format!("{}: {}", m.as_of, lang.date(report.date)))
+    );
     println!();
 
-    let Some(summary) = report.summary_spanish.as_deref().filter(|s| !s.is_empty()) else {
+    let Some(narrative) = report.narrative() else {
         return;
     };
 
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!("  SUMMARY (Spanish)");
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!();
-    for paragraph in summary.split("\n\n") {
-        for line in wrap_text(paragraph, 63) {
-            println!("  {}", line);
+    let (headline, observations, scenarios, recommendations, disclaimer, untranslated, note) =
+        match narrative_lang {
+            Lang::Es => (
+                narrative.headline,
+                narrative.observations,
+                narrative.scenarios,
+                narrative.recommendations,
+                narrative.disclaimer,
+                0,
+                m.narrative_spanish,
+            ),
+            Lang::En => {
+                let english = narrative.to_english();
+                (
+                    english.headline,
+                    english.observations,
+                    english.scenarios,
+                    english.recommendations,
+                    english.disclaimer,
+                    english.untranslated,
+                    m.narrative_english,
+                )
+            }
+        };
+
+    if headline.is_some() || !observations.is_empty() {
+        println!("{}", render.section(&format!("{} ({})", m.summary, note)));
+        println!();
+        for paragraph in headline.iter().chain(&observations) {
+            for line in render.paragraph(paragraph) {
+                println!("{}", line);
+            }
+            println!();
+        }
+    }
+
+    if !scenarios.is_empty() {
+        println!(
+            "{}",
+            render.section(&format!("{} ({})", m.expected_scenarios, note))
+        );
+        println!();
+        print_bullets(&scenarios, render);
+    }
+
+    if !recommendations.is_empty() {
+        println!(
+            "{}",
+            render.section(&format!("{} ({})", m.recommendations, note))
+        );
+        println!();
+        print_bullets(&recommendations, render);
+    }
+
+    if let Some(disclaimer) = &disclaimer {
+        for line in render.paragraph(disclaimer) {
+            println!("{}", line);
+        }
+        println!();
+    }
+
+    if narrative_lang == Lang::En {
+        let notice = match untranslated {
+            0 => popo_cli::translate::NOTICE.to_string(),
+            n => format!(
+                "{} {} {} kept in Spanish here.",
+                popo_cli::translate::NOTICE,
+                n,
+                plural(n as u32, "passage was", "passages were")
+            ),
+        };
+        for line in render.note("⚠️  ", &notice) {
+            println!("{}", line);
         }
         println!();
     }
 }
 
-fn print_index(index: &FeedIndex) {
+fn print_bullets(items: &[String], render: &Renderer) {
+    for item in items {
+        for line in render.bullet(item) {
+            println!("{}", line);
+        }
+    }
+    println!();
+}
+
+fn print_index(index: &FeedIndex, render: &Renderer) {
+    println!();
+    println!("{}", render.line("📚 ", "Feed coverage"));
+    println!();
+    let updated = index.updated_at.format("%Y-%m-%d %H:%M:%S UTC").to_string();
+    for (label, value) in [
+        ("Reports:  ", index.count.to_string()),
+        ("Earliest: ", index.earliest.to_string()),
+        ("Latest:   ", index.latest.to_string()),
+        ("Updated:  ", updated),
+    ] {
+        let text = match render.style {
+            Style::Rich | Style::Plain => format!("{}{}", label, value),
+            Style::Accessible => format!("{} {}", label.trim_end(), value),
+        };
+        println!("{}", render.item(&text));
+    }
+    println!();
+}
+
+fn print_windrose(table: &WindRoseTable, render: &Renderer) {
+    println!("{}", render.banner("PLUME WIND ROSE"));
+    println!();
+    println!(
+        "{}",
+        render.lead("📅 ", &format!("{} to {}", table.from, table.to))
+    );
     println!();
-    println!("  📚 Feed coverage");
+
+    if table.rose.observed == 0 {
+        println!(
+            "{}",
+            render.line(
+                "ℹ️  ",
+                "No full report in this range states a wind direction."
+            )
+        );
+        println!();
+        return;
+    }
+
+    // A picture says nothing to a screen reader; the table below has it all.
+    if render.style != Style::Accessible {
+        for line in rose_diagram(&table.rose, 8, render.style) {
+            println!("{}", format!("  {}", line).trim_end());
+        }
+        println!();
+    }
+    print_rose_table(&table.rose, render);
+
+    if let Some(months) = &table.by_month {
+        println!("{}", render.section("BY MONTH"));
+        println!();
+        if render.style != Style::Accessible {
+            println!("  {:<38}N   E   S   W", "");
+        }
+        for (month, rose) in months {
+            let name = chrono::Month::try_from(*month as u8)
+                .map(|m| m.name())
+                .unwrap_or("?");
+            let prevailing = rose
+                .prevailing()
+                .map(|d| format!("{:?}", d))
+                .unwrap_or_else(|| "-".to_string());
+            match render.style {
+                Style::Rich | Style::Plain => println!(
+                    "  {:<10} {:>4} days  prevailing {:<3}  {}",
+                    name,
+                    rose.observed,
+                    prevailing,
+                    rose_strip(rose, render.style)
+                ),
+                Style::Accessible => println!(
+                    "{}: {} days, prevailing {}",
+                    name, rose.observed, prevailing
+                ),
+            }
+        }
+        println!();
+    }
+}
+
+fn print_rose_table(rose: &WindRose, render: &Renderer) {
+    let block = if render.style == Style::Rich {
+        "█"
+    } else {
+        "#"
+    };
+    for petal in &rose.petals {
+        match render.style {
+            Style::Rich | Style::Plain => println!(
+                "  {:<4} {:>4}  {:>5.1}%  {}",
+                format!("{:?}", petal.direction),
+                petal.count,
+                petal.frequency * 100.0,
+                block.repeat(scaled(rose, petal.count, 30))
+            ),
+            Style::Accessible => println!(
+                "{}: {} days, {:.1}%",
+                petal.direction.name(),
+                petal.count,
+                petal.frequency * 100.0
+            ),
+        }
+    }
     println!();
-    println!("     Reports:  {}", index.count);
-    println!("     Earliest: {}", index.earliest);
-    println!("     Latest:   {}", index.latest);
     println!(
-        "     Updated:  {}",
-        index.updated_at.format("%Y-%m-%d %H:%M:%S UTC")
+        "{}",
+        render.line(
+            "",
+            &format!(
+                "{} days with a direction, {} full reports without one.",
+                rose.observed, rose.unreported
+            )
+        )
     );
     println!();
 }
 
-/// Wrap on whitespace at `width` columns, counting characters rather than
-/// bytes so accented Spanish text does not wrap short.
-fn wrap_text(text: &str, width: usize) -> Vec<String> {
-    let mut result = Vec::new();
-    let mut current_line = String::new();
-    let mut current_len = 0;
+/// Draw the rose as rays from the crater, each as long as its share of days
+/// relative to the prevailing direction. Columns are doubled so the picture
+/// is roughly round in a terminal.
+fn rose_diagram(rose: &WindRose, radius: usize, style: Style) -> Vec<String> {
+    let (tip, ray, crater) = match style {
+        Style::Rich => ('●', '·', '▲'),
+        Style::Plain | Style::Accessible => ('o', '.', '^'),
+    };
+    // One row and two columns of margin on each side for the compass labels.
+    let height = radius * 2 + 3;
+    let width = radius * 4 + 5;
+    let mut grid = vec![vec![' '; width]; height];
+    let (cx, cy) = (radius * 2 + 2, radius + 1);
 
-    for word in text.split_whitespace() {
-        let word_len = word.chars().count();
-        if current_line.is_empty() {
-            current_line = word.to_string();
-            current_len = word_len;
-        } else if current_len + word_len < width {
-            current_line.push(' ');
-            current_line.push_str(word);
-            current_len += word_len + 1;
-        } else {
-            result.push(std::mem::take(&mut current_line));
-            current_line = word.to_string();
-            current_len = word_len;
+    for petal in &rose.petals {
+        let length = scaled(rose, petal.count, radius);
+        let angle = petal.direction.degrees().to_radians();
+        // Walk in half-cell steps so doubled columns leave no gaps.
+        for step in 1..=length * 2 {
+            let r = step as f64 / 2.0;
+            let x = (cx as f64 + angle.sin() * r * 2.0).round() as usize;
+            let y = (cy as f64 - angle.cos() * r).round() as usize;
+            grid[y][x] = if step == length * 2 { tip } else { ray };
         }
     }
 
-    if !current_line.is_empty() {
-        result.push(current_line);
+    grid[cy][cx] = crater;
+    grid[0][cx] = 'N';
+    grid[height - 1][cx] = 'S';
+    grid[cy][0] = 'W';
+    grid[cy][width - 1] = 'E';
+
+    grid.into_iter()
+        .map(|row| row.into_iter().collect())
+        .collect()
+}
+
+/// Sixteen block characters, one per compass point from north, scaled to the
+/// busiest point of this rose.
+fn rose_strip(rose: &WindRose, style: Style) -> String {
+    const BLOCKS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    const ASCII: [char; 9] = [' ', '.', ':', '-', '=', '+', '*', '#', '@'];
+    let levels = if style == Style::Rich { BLOCKS } else { ASCII };
+    rose.petals
+        .iter()
+        .map(|p| levels[scaled(rose, p.count, 8)])
+        .collect()
+}
+
+/// `count` as a length out of `max`, relative to the busiest point, rounding
+/// up so a single day still shows.
+fn scaled(rose: &WindRose, count: u32, max: usize) -> usize {
+    let widest = rose
+        .petals
+        .iter()
+        .map(|p| p.count)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    (f64::from(count) / f64::from(widest) * max as f64).ceil() as usize
+}
+
+fn print_search_hits(hits: &[SearchHit], limit: usize, indexed: usize, render: &Renderer) {
+    println!();
+    if hits.is_empty() {
+        println!(
+            "{}",
+            render.line(
+                "",
+                &format!("No narrative matches, out of {} indexed.", indexed)
+            )
+        );
+        println!();
+        return;
+    }
+
+    let summary = format!(
+        "{} of {} narratives match{}",
+        hits.len(),
+        indexed,
+        if hits.len() > limit {
+            format!(", showing the newest {}", limit)
+        } else {
+            String::new()
+        }
+    );
+    println!("{}", render.line("🔎 ", &summary));
+    println!();
+
+    for hit in hits.iter().take(limit) {
+        let noun = if hit.matches == 1 { "match" } else { "matches" };
+        println!(
+            "{}",
+            render.line("📅 ", &format!("{}  ({} {})", hit.date, hit.matches, noun))
+        );
+        for line in render.indented(&highlight(&hit.snippet, render.emphasis()), 5) {
+            println!("{}", line);
+        }
+        println!();
     }
+}
 
-    result
+/// Wrap each highlighted span in `open` and `close` markers.
+fn highlight(snippet: &Snippet, (open, close): (&str, &str)) -> String {
+    let mut out = String::new();
+    let len = snippet.text.chars().count();
+    for (i, c) in snippet
+        .text
+        .chars()
+        .chain(std::iter::once('\0'))
+        .enumerate()
+    {
+        if snippet.highlights.iter().any(|&(_, end)| end == i) {
+            out.push_str(close);
+        }
+        if i == len {
+            break;
+        }
+        if snippet.highlights.iter().any(|&(start, _)| start == i) {
+            out.push_str(open);
+        }
+        out.push(c);
+    }
+    out
 }
 
 #[cfg(test)]
@@ -329,21 +2469,85 @@ mod tests {
     use super::*;
 
     #[test]
-    fn wraps_on_word_boundaries() {
-        let lines = wrap_text("uno dos tres cuatro cinco", 9);
-        assert_eq!(lines, vec!["uno dos", "tres", "cuatro", "cinco"]);
+    fn rose_diagram_points_rays_the_right_way() {
+        let reports: Vec<VolcanoReport> = serde_json::from_str(
+            r#"[{"date": "2023-01-01", "wind_direction": "E"},
+                {"date": "2023-01-02", "wind_direction": "E"}]"#,
+        )
+        .unwrap();
+        let lines = rose_diagram(&WindRose::from_reports(&reports), 4, Style::Rich);
+
+        assert_eq!(lines.len(), 11);
+        // An easterly plume draws a ray to the right of the crater marker.
+        assert!(lines[5].contains("▲·······●"));
+        assert!(lines[5].ends_with('E'));
+    }
+
+    #[test]
+    fn highlight_marks_each_span() {
+        let snippet = Snippet {
+            text: "un láhar y otro lahar".to_string(),
+            highlights: vec![(3, 8), (16, 21)],
+        };
+        assert_eq!(highlight(&snippet, ("[", "]")), "un [láhar] y otro [lahar]");
     }
 
-    /// Accented text must wrap by characters, not bytes.
     #[test]
-    fn wraps_accented_text_by_chars() {
-        let lines = wrap_text("ceniza volcánica ácida", 17);
-        assert_eq!(lines, vec!["ceniza volcánica", "ácida"]);
+    fn events_are_described_with_media_rolled_up() {
+        let summary =
+            "Se registró una explosión menor a las 11:02 h (imagen 1) (imagen 2) (video 1).";
+        let describe = |lang| -> Vec<String> {
+            describe_events(&popo_cli::events::extract(summary), lang)
+                .into_iter()
+                .map(|(icon, text)| format!("{}{}", icon, text))
+                .collect()
+        };
+        let lines = describe(Lang::En);
+        assert_eq!(
+            lines,
+            vec![
+                "💥 1 explosion (minor) at 11:02 local time",
+                "📎 Refers to 2 images and 1 video",
+            ]
+        );
+        let lines = describe(Lang::Es);
+        assert_eq!(
+            lines,
+            vec![
+                "💥 1 explosión (menor) a las 11:02 hora local",
+                "📎 Menciona 2 imágenes y 1 video",
+            ]
+        );
     }
 
     #[test]
     fn counter_distinguishes_zero_from_absent() {
-        assert_eq!(counter(Some(0)), "0");
-        assert_eq!(counter(None), "not reported");
+        assert_eq!(counter(Some(0), Lang::En), "0");
+        assert_eq!(counter(None, Lang::En), "not reported");
+        assert_eq!(counter(None, Lang::Es), "sin dato");
+    }
+
+    #[test]
+    fn alert_levels_parse_in_english_or_spanish() {
+        assert_eq!(parse_alert_level("Orange"), Ok(AlertLevel::Orange));
+        assert_eq!(parse_alert_level("ROJO"), Ok(AlertLevel::Red));
+        assert!(parse_alert_level("purple").is_err());
+    }
+
+    #[test]
+    fn feed_failures_exit_apart_from_other_errors() {
+        let date = NaiveDate::from_ymd_opt(2023, 5, 20).unwrap();
+        assert_eq!(
+            exit_code(&PopoError::NotFound(date)),
+            ExitCode::from(EXIT_FEED)
+        );
+        assert_eq!(
+            exit_code(&PopoError::InvalidDate("x".into())),
+            ExitCode::FAILURE
+        );
+        assert_eq!(
+            exit_code(&PopoError::Serialize("a report".into())),
+            ExitCode::FAILURE
+        );
     }
 }
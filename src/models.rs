@@ -1,3 +1,9 @@
+use crate::ashfall::AshfallPlaces;
+use crate::diff::ReportDiff;
+use crate::downwind::{DownwindTown, Sector};
+use crate::events::NarrativeEvent;
+use crate::format::Tabular;
+use crate::narrative::Narrative;
 use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -80,6 +86,104 @@ impl VolcanoReport {
     pub fn is_full(&self) -> bool {
         !self.partial
     }
+
+    /// The Spanish summary split into sections, with the page's navigation
+    /// link text removed. `None` when the report has no narrative.
+    pub fn narrative(&self) -> Option<Narrative> {
+        self.summary_spanish
+            .as_deref()
+            .filter(|s| !s.trim().is_empty())
+            .map(Narrative::parse)
+    }
+
+    /// Explosions, ash columns, exclusion radius and media references read out
+    /// of the narrative. Spans index into `summary_spanish`.
+    pub fn events(&self) -> Vec<NarrativeEvent> {
+        self.summary_spanish
+            .as_deref()
+            .map(crate::events::extract)
+            .unwrap_or_default()
+    }
+
+    /// `ashfall_reports` resolved against the bundled gazetteer.
+    pub fn ashfall_places(&self) -> AshfallPlaces {
+        AshfallPlaces::resolve(&self.ashfall_reports)
+    }
+
+    /// Municipalities inside `sector` along the day's plume direction. Empty
+    /// when the report states no direction.
+    pub fn downwind_towns(&self, sector: &Sector) -> Vec<DownwindTown> {
+        self.wind_direction
+            .map(|dir| sector.towns(dir))
+            .unwrap_or_default()
+    }
+
+    /// What changed from this report to `other`, ignoring `ingested_at`.
+    pub fn diff(&self, other: &VolcanoReport) -> ReportDiff {
+        ReportDiff::between(self, other)
+    }
+}
+
+/// A report for `date` with every optional field empty, for unit tests to fill
+/// in just the fields they care about.
+#[cfg(test)]
+impl VolcanoReport {
+    pub(crate) fn blank(date: &str) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            date: NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
+            exhalations: None,
+            volcanotectonic_events: None,
+            tremor_minutes_total: None,
+            tremor_high_frequency_minutes: None,
+            tremor_harmonic_minutes: None,
+            explosions: None,
+            so2_emissions_tons_per_day: None,
+            so2_measurement_date: None,
+            alert_level: None,
+            alert_phase: None,
+            wind_direction: None,
+            summary_spanish: None,
+            ashfall_reports: Vec::new(),
+            image_urls: Vec::new(),
+            video_urls: Vec::new(),
+            source_url: None,
+            ingested_at: None,
+            partial: false,
+        }
+    }
+}
+
+impl Tabular for VolcanoReport {
+    type Row<'a> = &'a VolcanoReport;
+
+    const COLUMNS: &'static [&'static str] = &[
+        "schema_version",
+        "date",
+        "exhalations",
+        "volcanotectonic_events",
+        "tremor_minutes_total",
+        "tremor_high_frequency_minutes",
+        "tremor_harmonic_minutes",
+        "explosions",
+        "so2_emissions_tons_per_day",
+        "so2_measurement_date",
+        "alert_level",
+        "alert_phase",
+        "wind_direction",
+        "summary_spanish",
+        "ashfall_reports",
+        "image_urls",
+        "video_urls",
+        "source_url",
+        "ingested_at",
+        "partial",
+    ];
+    const RECORD: bool = true;
+
+    fn rows(&self) -> Vec<&VolcanoReport> {
+        vec![self]
+    }
 }
 
 fn default_schema_version() -> u32 {
@@ -99,7 +203,26 @@ pub struct FeedIndex {
     pub dates: Vec<NaiveDate>,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+impl Tabular for FeedIndex {
+    type Row<'a> = &'a FeedIndex;
+
+    const COLUMNS: &'static [&'static str] = &[
+        "schema_version",
+        "updated_at",
+        "earliest",
+        "latest",
+        "count",
+        "dates",
+    ];
+    const RECORD: bool = true;
+
+    fn rows(&self) -> Vec<&FeedIndex> {
+        vec![self]
+    }
+}
+
+/// CENAPRED's traffic-light alert, ordered from least to most severe.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum AlertLevel {
     Green,
@@ -135,7 +258,8 @@ impl AlertLevel {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+/// Direction of the plume on a 16-point compass, in clockwise order from north.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum WindDirection {
     N,
@@ -157,6 +281,26 @@ pub enum WindDirection {
 }
 
 impl WindDirection {
+    /// All sixteen points, clockwise from north.
+    pub const ALL: [WindDirection; 16] = [
+        WindDirection::N,
+        WindDirection::NNE,
+        WindDirection::NE,
+        WindDirection::ENE,
+        WindDirection::E,
+        WindDirection::ESE,
+        WindDirection::SE,
+        WindDirection::SSE,
+        WindDirection::S,
+        WindDirection::SSW,
+        WindDirection::SW,
+        WindDirection::WSW,
+        WindDirection::W,
+        WindDirection::WNW,
+        WindDirection::NW,
+        WindDirection::NNW,
+    ];
+
     /// Map CENAPRED's Spanish compass wording onto a 16-point code.
     ///
     /// Matching is ordered longest-first: `oestenoroeste` contains `noroeste`,
@@ -193,6 +337,66 @@ impl WindDirection {
             .find(|(pattern, _)| normalized.contains(pattern))
             .map(|(_, dir)| *dir)
     }
+
+    /// Compass bearing in degrees clockwise from north: N is 0, E is 90.
+    pub fn degrees(self) -> f64 {
+        self as usize as f64 * 22.5
+    }
+
+    /// The direction at exactly `degrees`, or `None` for a bearing between
+    /// two points. Use [`nearest`](Self::nearest) to snap instead.
+    pub fn from_degrees(degrees: f64) -> Option<Self> {
+        let dir = Self::nearest(degrees);
+        ((degrees.rem_euclid(360.0) - dir.degrees()).abs() % 360.0 < 1e-9).then_some(dir)
+    }
+
+    /// The point closest to any bearing, wrapping through north.
+    pub fn nearest(degrees: f64) -> Self {
+        let index = (degrees.rem_euclid(360.0) / 22.5).round() as usize % 16;
+        Self::ALL[index]
+    }
+
+    /// The point 180° away.
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 8) % 16]
+    }
+
+    /// The smaller angle between two directions, 0 to 180.
+    pub fn angle_to(self, other: WindDirection) -> f64 {
+        let steps = (self as usize + 16 - other as usize) % 16;
+        steps.min(16 - steps) as f64 * 22.5
+    }
+
+    /// "West-northwest".
+    pub fn name(self) -> &'static str {
+        match self {
+            WindDirection::N => "North",
+            WindDirection::NNE => "North-northeast",
+            WindDirection::NE => "Northeast",
+            WindDirection::ENE => "East-northeast",
+            WindDirection::E => "East",
+            WindDirection::ESE => "East-southeast",
+            WindDirection::SE => "Southeast",
+            WindDirection::SSE => "South-southeast",
+            WindDirection::S => "South",
+            WindDirection::SSW => "South-southwest",
+            WindDirection::SW => "Southwest",
+            WindDirection::WSW => "West-southwest",
+            WindDirection::W => "West",
+            WindDirection::WNW => "West-northwest",
+            WindDirection::NW => "Northwest",
+            WindDirection::NNW => "North-northwest",
+        }
+    }
+
+    /// An arrow pointing the way the plume heads. There are only eight arrows,
+    /// so the in-between points take the arrow of the cardinal beside them.
+    pub fn arrow(self) -> char {
+        const ARROWS: [char; 8] = ['↑', '↗', '→', '↘', '↓', '↙', '←', '↖'];
+        let i = self as usize;
+        let eighth = if i % 4 == 3 { (i + 1) / 2 } else { i / 2 };
+        ARROWS[eighth % 8]
+    }
 }
 
 #[cfg(test)]
@@ -237,6 +441,45 @@ mod tests {
         assert_eq!(WindDirection::from_spanish("OESTE"), Some(WindDirection::W));
     }
 
+    #[test]
+    fn wind_direction_degrees_round_trip() {
+        for dir in WindDirection::ALL {
+            assert_eq!(WindDirection::from_degrees(dir.degrees()), Some(dir));
+        }
+        assert_eq!(WindDirection::W.degrees(), 270.0);
+        assert_eq!(WindDirection::from_degrees(-90.0), Some(WindDirection::W));
+        assert_eq!(WindDirection::from_degrees(100.0), None);
+    }
+
+    #[test]
+    fn wind_direction_snaps_to_the_nearest_point() {
+        assert_eq!(WindDirection::nearest(100.0), WindDirection::E);
+        assert_eq!(WindDirection::nearest(355.0), WindDirection::N);
+        assert_eq!(WindDirection::nearest(-20.0), WindDirection::NNW);
+        assert_eq!(WindDirection::nearest(725.0), WindDirection::N);
+    }
+
+    #[test]
+    fn wind_direction_opposites_and_angles() {
+        assert_eq!(WindDirection::WNW.opposite(), WindDirection::ESE);
+        assert_eq!(WindDirection::S.opposite(), WindDirection::N);
+        assert_eq!(WindDirection::NNW.angle_to(WindDirection::NNE), 45.0);
+        assert_eq!(WindDirection::E.angle_to(WindDirection::W), 180.0);
+        assert_eq!(WindDirection::SW.angle_to(WindDirection::SW), 0.0);
+    }
+
+    #[test]
+    fn wind_direction_names_and_arrows() {
+        assert_eq!(WindDirection::WNW.name(), "West-northwest");
+        assert_eq!(WindDirection::W.arrow(), '←');
+        assert_eq!(WindDirection::NE.arrow(), '↗');
+        // In-between points lean towards the cardinal.
+        assert_eq!(WindDirection::NNE.arrow(), '↑');
+        assert_eq!(WindDirection::NNW.arrow(), '↑');
+        assert_eq!(WindDirection::ESE.arrow(), '→');
+        assert_eq!(WindDirection::SSW.arrow(), '↓');
+    }
+
     /// Compound directions must not be swallowed by their shorter substrings.
     #[test]
     fn test_wind_direction_prefers_most_specific() {
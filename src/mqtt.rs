@@ -0,0 +1,217 @@
+//! Publishing reports to an MQTT broker.
+//!
+//! The whole report goes to `{prefix}/report` as JSON, and each headline field
+//! gets a topic of its own (`popo/alert_level`, `popo/exhalations`, …) holding
+//! a bare value, which is what home-automation tools expect to read. Every
+//! message is retained, so a subscriber that connects later still gets the
+//! current state. A field the report does not state is published empty, which
+//! clears the retained value rather than leaving yesterday's in place.
+
+use crate::error::{PopoError, Result};
+use crate::models::VolcanoReport;
+use rumqttc::{
+    Client, ConnectReturnCode, Event, MqttOptions, Outgoing, Packet, QoS, TlsConfiguration,
+    Transport,
+};
+use std::time::Duration;
+
+/// Environment variables holding the broker username and password.
+pub const MQTT_USER_ENV: &str = "POPO_MQTT_USER";
+pub const MQTT_PASSWORD_ENV: &str = "POPO_MQTT_PASSWORD";
+
+/// Where to publish, and how.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MqttConfig {
+    pub host: String,
+    pub port: u16,
+    pub tls: bool,
+    /// PEM certificate to trust instead of the system roots.
+    pub ca: Option<Vec<u8>>,
+    pub credentials: Option<(String, String)>,
+    pub client_id: String,
+    /// 0, 1 or 2.
+    pub qos: u8,
+}
+
+impl MqttConfig {
+    /// Read a broker as `mqtt://host:1883`, `mqtts://host:8883` or a bare
+    /// host. The port defaults to 1883, or 8883 with TLS.
+    pub fn parse_broker(broker: &str) -> Result<Self> {
+        let invalid = || PopoError::Mqtt(format!("'{}' is not a broker address", broker));
+        let (tls, rest) = if let Some(rest) = broker.strip_prefix("mqtts://") {
+            (true, rest)
+        } else if let Some(rest) = broker.strip_prefix("mqtt://") {
+            (false, rest)
+        } else if broker.contains("://") {
+            return Err(invalid());
+        } else {
+            (false, broker)
+        };
+        let rest = rest.trim_end_matches('/');
+        let (host, port) = match rest.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().map_err(|_| invalid())?),
+            None => (rest, if tls { 8883 } else { 1883 }),
+        };
+        if host.is_empty() {
+            return Err(invalid());
+        }
+        Ok(MqttConfig {
+            host: host.to_string(),
+            port,
+            tls,
+            ca: None,
+            credentials: None,
+            client_id: format!("popo-{}", std::process::id()),
+            qos: 0,
+        })
+    }
+
+    /// Publish every message, retained, and wait until the broker has them
+    /// at the configured QoS before disconnecting.
+    pub fn publish(&self, messages: &[(String, String)]) -> Result<()> {
+        let qos = match self.qos {
+            0 => QoS::AtMostOnce,
+            1 => QoS::AtLeastOnce,
+            2 => QoS::ExactlyOnce,
+            other => return Err(PopoError::Mqtt(format!("QoS {} does not exist", other))),
+        };
+        let mut options = MqttOptions::new(&self.client_id, &self.host, self.port);
+        options.set_keep_alive(Duration::from_secs(30));
+        if let Some((user, password)) = &self.credentials {
+            options.set_credentials(user, password);
+        }
+        if self.tls {
+            options.set_transport(Transport::Tls(match &self.ca {
+                Some(ca) => TlsConfiguration::SimpleNative {
+                    ca: ca.clone(),
+                    client_auth: None,
+                },
+                None => TlsConfiguration::Native,
+            }));
+        }
+
+        let mqtt_err = |e: &dyn std::fmt::Display| PopoError::Mqtt(e.to_string());
+        let (client, mut connection) = Client::new(options, messages.len().max(1));
+        for (topic, payload) in messages {
+            client
+                .publish(topic.as_str(), qos, true, payload.as_bytes())
+                .map_err(|e| mqtt_err(&e))?;
+        }
+
+        let mut pending = messages.len();
+        if pending == 0 {
+            client.disconnect().map_err(|e| mqtt_err(&e))?;
+        }
+        for event in connection.iter() {
+            let done = match event.map_err(|e| mqtt_err(&e))? {
+                Event::Incoming(Packet::ConnAck(ack)) if ack.code != ConnectReturnCode::Success => {
+                    return Err(PopoError::Mqtt(format!("broker refused: {:?}", ack.code)));
+                }
+                Event::Outgoing(Outgoing::Publish(_)) if qos == QoS::AtMostOnce => true,
+                Event::Incoming(Packet::PubAck(_)) if qos == QoS::AtLeastOnce => true,
+                Event::Incoming(Packet::PubComp(_)) if qos == QoS::ExactlyOnce => true,
+                Event::Outgoing(Outgoing::Disconnect) => break,
+                _ => false,
+            };
+            if done {
+                pending -= 1;
+                if pending == 0 {
+                    client.disconnect().map_err(|e| mqtt_err(&e))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Topic and payload for every message describing `report`, under `prefix`.
+pub fn messages(report: &VolcanoReport, prefix: &str) -> Result<Vec<(String, String)>> {
+    let prefix = prefix.trim_end_matches('/');
+    let show = |v: Option<String>| v.unwrap_or_default();
+    let count = |v: Option<u32>| show(v.map(|n| n.to_string()));
+    let report_json =
+        serde_json::to_string(report).map_err(|e| PopoError::Serialize(e.to_string()))?;
+    let ashfall = serde_json::to_string(&report.ashfall_places().names())
+        .map_err(|e| PopoError::Serialize(e.to_string()))?;
+
+    let fields = [
+        ("report", report_json),
+        ("date", report.date.to_string()),
+        (
+            "alert_level",
+            show(
+                report
+                    .alert_level
+                    .map(|l| format!("{:?}", l).to_uppercase()),
+            ),
+        ),
+        ("alert_phase", show(report.alert_phase.clone())),
+        ("exhalations", count(report.exhalations)),
+        ("explosions", count(report.explosions)),
+        (
+            "volcanotectonic_events",
+            count(report.volcanotectonic_events),
+        ),
+        ("tremor_minutes_total", count(report.tremor_minutes_total)),
+        (
+            "so2_emissions_tons_per_day",
+            show(report.so2_emissions_tons_per_day.map(|t| t.to_string())),
+        ),
+        (
+            "wind_direction",
+            show(report.wind_direction.map(|d| format!("{:?}", d))),
+        ),
+        ("ashfall", ashfall),
+    ];
+    Ok(fields
+        .into_iter()
+        .map(|(field, payload)| (format!("{}/{}", prefix, field), payload))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AlertLevel, WindDirection};
+
+    #[test]
+    fn brokers_default_their_port_by_scheme() {
+        let plain = MqttConfig::parse_broker("mqtt://broker.local").unwrap();
+        assert_eq!(
+            (plain.host.as_str(), plain.port, plain.tls),
+            ("broker.local", 1883, false)
+        );
+        let tls = MqttConfig::parse_broker("mqtts://broker.local").unwrap();
+        assert_eq!((tls.port, tls.tls), (8883, true));
+        let bare = MqttConfig::parse_broker("10.0.0.5:1884").unwrap();
+        assert_eq!((bare.host.as_str(), bare.port), ("10.0.0.5", 1884));
+        assert!(MqttConfig::parse_broker("http://broker.local").is_err());
+        assert!(MqttConfig::parse_broker("mqtt://:1883").is_err());
+    }
+
+    #[test]
+    fn one_topic_per_field_plus_the_whole_report() {
+        let mut report = VolcanoReport::blank("2026-08-22");
+        report.alert_level = Some(AlertLevel::Yellow);
+        report.exhalations = Some(6);
+        report.wind_direction = Some(WindDirection::W);
+        report.ashfall_reports = vec!["Atlixco".into()];
+
+        let messages = messages(&report, "volcano/popo/").unwrap();
+        let get = |topic: &str| {
+            messages
+                .iter()
+                .find(|(t, _)| t == topic)
+                .map(|(_, p)| p.as_str())
+        };
+        assert_eq!(get("volcano/popo/alert_level"), Some("YELLOW"));
+        assert_eq!(get("volcano/popo/exhalations"), Some("6"));
+        assert_eq!(get("volcano/popo/wind_direction"), Some("W"));
+        assert_eq!(get("volcano/popo/ashfall"), Some(r#"["Atlixco, Puebla"]"#));
+        // Unreported fields are cleared, not left stale.
+        assert_eq!(get("volcano/popo/explosions"), Some(""));
+        assert!(get("volcano/popo/report")
+            .unwrap()
+            .contains("\"exhalations\":6"));
+    }
+}
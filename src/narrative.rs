@@ -0,0 +1,338 @@
+//! Structure recovered from `summary_spanish`.
+//!
+//! The narrative is scraped page text, not structured data. It carries the
+//! day's observations, then the standard scenario and recommendation lists for
+//! the current alert phase, a disclaimer, and finally the text of the page's
+//! navigation links ("Semáforo de alerta volcánica", "Mapa de Peligros…").
+//!
+//! Two layouts occur. Reports up to early 2023 are one long paragraph with
+//! numbered lists run inline; later reports put each observation and each
+//! scenario bullet in a paragraph of its own. [`Narrative::parse`] handles
+//! both and drops the link text, which is never part of the report itself.
+
+use serde::{Deserialize, Serialize};
+
+/// `summary_spanish`, split into its parts.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Narrative {
+    /// "Reporte del monitoreo del Cenapred al volcán Popocatépetl 22 de Agosto
+    /// de 2026", when present.
+    pub headline: Option<String>,
+    /// What was observed, one paragraph per entry. Includes the sentence
+    /// stating the alert phase.
+    pub observations: Vec<String>,
+    /// Scenarios CENAPRED expects for the current phase.
+    pub scenarios: Vec<String>,
+    /// Advice to the public and to authorities.
+    pub recommendations: Vec<String>,
+    /// The standing note on how and when the report is produced.
+    pub disclaimer: Option<String>,
+}
+
+/// Navigation link text rendered after every report. Matched as whole
+/// paragraphs, by prefix where the link carries a date.
+const LINK_BOILERPLATE: &[&str] = &[
+    "Semáforo de alerta volcánica",
+    "Emisión de bióxido de azufre",
+    "Imágenes del volcán Popocatépetl",
+    "Videos del volcán Popocatépetl",
+    "Monitoreo volcán",
+    "Mapa de Peligros del volcán Popocatépetl",
+];
+
+const HEADLINE_PREFIX: &str = "Reporte del monitoreo";
+
+const SCENARIO_MARKERS: &[&str] = &["Los escenarios previstos para esta fase son:"];
+
+const RECOMMENDATION_MARKERS: &[&str] = &[
+    "Las recomendaciones para la población ante esta actividad son:",
+    "RECOMENDACIONES:",
+];
+
+const DISCLAIMER_MARKERS: &[&str] = &[
+    "La información aquí publicada",
+    "El monitoreo del volcán Popocatépetl se realiza de forma continua",
+];
+
+/// In the paragraph layout, the scenario bullets follow straight on from the
+/// paragraph stating the phase, with no heading of their own.
+const PHASE_STATEMENT: &str = "Semáforo de Alerta Volcánica del Popocatépetl se encuentra en";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    Observations,
+    Scenarios,
+    Recommendations,
+    Disclaimer,
+}
+
+impl Narrative {
+    pub fn parse(summary: &str) -> Self {
+        let mut narrative = Narrative::default();
+        let mut section = Section::Observations;
+        let mut disclaimer = Vec::new();
+
+        let paragraphs = summary
+            .split("\n\n")
+            .map(str::trim)
+            .filter(|p| !p.is_empty() && !is_link_boilerplate(p));
+
+        for (i, paragraph) in paragraphs.enumerate() {
+            if i == 0 && paragraph.starts_with(HEADLINE_PREFIX) {
+                narrative.headline = Some(paragraph.to_string());
+                continue;
+            }
+
+            for piece in split_at_markers(paragraph) {
+                let mut body = piece;
+                if section != Section::Disclaimer {
+                    if let Some(rest) = strip_marker(piece, SCENARIO_MARKERS) {
+                        section = Section::Scenarios;
+                        body = rest;
+                    } else if let Some(rest) = strip_marker(piece, RECOMMENDATION_MARKERS) {
+                        section = Section::Recommendations;
+                        body = rest;
+                    }
+                }
+                if DISCLAIMER_MARKERS.iter().any(|m| piece.starts_with(m)) {
+                    section = Section::Disclaimer;
+                }
+                if body.is_empty() {
+                    continue;
+                }
+
+                match section {
+                    Section::Observations => {
+                        narrative.observations.push(body.to_string());
+                        if body.contains(PHASE_STATEMENT) {
+                            section = Section::Scenarios;
+                        }
+                    }
+                    Section::Scenarios => narrative.scenarios.extend(list_items(body)),
+                    Section::Recommendations => narrative.recommendations.extend(list_items(body)),
+                    Section::Disclaimer => disclaimer.push(body),
+                }
+            }
+        }
+
+        if !disclaimer.is_empty() {
+            narrative.disclaimer = Some(disclaimer.join(" "));
+        }
+
+        // Some pages were captured with only the scenario list under the
+        // headline. Short, unpunctuated lines with no phase statement are
+        // bullets, not observations.
+        if narrative.scenarios.is_empty()
+            && !narrative.observations.is_empty()
+            && narrative.observations.iter().all(|o| looks_like_bullet(o))
+        {
+            narrative.scenarios = std::mem::take(&mut narrative.observations);
+        }
+
+        narrative
+    }
+
+    /// The narrative without link boilerplate, as paragraphs in their original
+    /// order.
+    pub fn paragraphs(&self) -> Vec<&str> {
+        self.headline
+            .iter()
+            .chain(&self.observations)
+            .chain(&self.scenarios)
+            .chain(&self.recommendations)
+            .chain(&self.disclaimer)
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+fn is_link_boilerplate(paragraph: &str) -> bool {
+    // The links are short; a long paragraph that happens to start the same way
+    // is report text.
+    paragraph.chars().count() < 80 && LINK_BOILERPLATE.iter().any(|b| paragraph.starts_with(b))
+}
+
+fn looks_like_bullet(paragraph: &str) -> bool {
+    paragraph.chars().count() < 300
+        && !paragraph.ends_with('.')
+        && !paragraph.contains(PHASE_STATEMENT)
+        && !paragraph.contains("(imagen")
+        && !paragraph.contains("(video")
+}
+
+fn strip_marker<'a>(piece: &'a str, markers: &[&str]) -> Option<&'a str> {
+    markers
+        .iter()
+        .find_map(|m| piece.strip_prefix(m))
+        .map(str::trim)
+}
+
+/// Break a paragraph before each section marker it contains, so markers run
+/// inline in the single-paragraph layout start pieces of their own.
+fn split_at_markers(paragraph: &str) -> Vec<&str> {
+    let mut cuts: Vec<usize> = SCENARIO_MARKERS
+        .iter()
+        .chain(RECOMMENDATION_MARKERS)
+        .chain(DISCLAIMER_MARKERS)
+        .flat_map(|m| paragraph.match_indices(m).map(|(i, _)| i))
+        .filter(|&i| i > 0)
+        .collect();
+    cuts.sort_unstable();
+    cuts.dedup();
+
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    for cut in cuts {
+        pieces.push(paragraph[start..cut].trim());
+        start = cut;
+    }
+    pieces.push(paragraph[start..].trim());
+    pieces.retain(|p| !p.is_empty());
+    pieces
+}
+
+/// Split inline numbered lists ("1. Cubrir nariz… 2. Limpiar ojos…") into
+/// items. Text without numbering is a single item.
+fn list_items(text: &str) -> Vec<String> {
+    let mut starts = Vec::new();
+    let bytes = text.as_bytes();
+    for (i, _) in text.char_indices() {
+        let at_boundary = i == 0 || bytes[i - 1].is_ascii_whitespace();
+        if !at_boundary {
+            continue;
+        }
+        let digits = bytes[i..].iter().take_while(|b| b.is_ascii_digit()).count();
+        if (1..=2).contains(&digits)
+            && bytes.get(i + digits) == Some(&b'.')
+            && bytes.get(i + digits + 1) == Some(&b' ')
+        {
+            starts.push((i, i + digits + 2));
+        }
+    }
+
+    if starts.is_empty() {
+        return vec![text.to_string()];
+    }
+
+    let mut items = Vec::new();
+    let lead = text[..starts[0].0].trim();
+    if !lead.is_empty() {
+        items.push(lead.to_string());
+    }
+    for (n, &(_, body_start)) in starts.iter().enumerate() {
+        let end = starts.get(n + 1).map_or(text.len(), |&(next, _)| next);
+        let item = text[body_start..end].trim();
+        if !item.is_empty() {
+            items.push(item.to_string());
+        }
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARAGRAPH_LAYOUT: &str = "Reporte del monitoreo del Cenapred al volcán Popocatépetl 22 de Agosto de 2026\n\nSe detectaron 6 exhalaciones de baja intensidad (imagen 1).\n\nAl momento de este reporte se observa una emisión continua con dirección oeste (O).\n\nEl Semáforo de Alerta Volcánica del Popocatépetl se encuentra en Amarillo Fase 2.\n\nAlgunas explosiones de tamaño menor a moderado\n\nOcurrencia de tremor de amplitud variable\n\nLa información aquí publicada es el resultado del monitoreo permanente. No hacer caso a rumores:\n\nSemáforo de alerta volcánica\n\nEmisión de bióxido de azufre\n\nImágenes del volcán Popocatépetl - 22 de Agosto de 2026\n\nMapa de Peligros del volcán Popocatépetl";
+
+    const INLINE_LAYOUT: &str = "Reporte del monitoreo del Cenapred al volcán Popocatépetl 10 de Diciembre de 2021\n\nDiciembre 10, 11:00 h Se identificaron 8 exhalaciones. Un sismo con magnitud de 1.3. El Semáforo de Alerta Volcánica del Popocatépetl se encuentra en AMARILLO FASE 2. Las recomendaciones para la población ante esta actividad son: No hacer caso a rumores. Ante la probable caída de ceniza se recomienda: 1. Cubrir nariz y boca. 2. Limpiar ojos. Los escenarios previstos para esta fase son: 1. Que continúe la actividad explosiva. 2. Lluvias de ceniza leves. RECOMENDACIONES: Se hace especial énfasis en: 1. Continuar con el radio de seguridad de 12 km. El monitoreo del volcán Popocatépetl se realiza de forma continua las 24 horas. ARM\n\nSemáforo de alerta volcánica\n\nMonitoreo volcán Fuego de Colima";
+
+    #[test]
+    fn splits_paragraph_layout() {
+        let n = Narrative::parse(PARAGRAPH_LAYOUT);
+
+        assert_eq!(
+            n.headline.as_deref(),
+            Some("Reporte del monitoreo del Cenapred al volcán Popocatépetl 22 de Agosto de 2026")
+        );
+        assert_eq!(n.observations.len(), 3);
+        assert!(n.observations[2].contains("Amarillo Fase 2"));
+        assert_eq!(
+            n.scenarios,
+            vec![
+                "Algunas explosiones de tamaño menor a moderado",
+                "Ocurrencia de tremor de amplitud variable"
+            ]
+        );
+        assert!(n.recommendations.is_empty());
+        assert!(n
+            .disclaimer
+            .as_deref()
+            .unwrap()
+            .starts_with("La información aquí publicada"));
+    }
+
+    #[test]
+    fn splits_inline_layout() {
+        let n = Narrative::parse(INLINE_LAYOUT);
+
+        assert_eq!(n.observations.len(), 1);
+        assert!(n.observations[0].ends_with("AMARILLO FASE 2."));
+        assert!(n.observations[0].contains("magnitud de 1.3."));
+        assert_eq!(
+            n.scenarios,
+            vec![
+                "Que continúe la actividad explosiva.",
+                "Lluvias de ceniza leves."
+            ]
+        );
+        assert_eq!(
+            n.recommendations,
+            vec![
+                "No hacer caso a rumores. Ante la probable caída de ceniza se recomienda:",
+                "Cubrir nariz y boca.",
+                "Limpiar ojos.",
+                "Se hace especial énfasis en:",
+                "Continuar con el radio de seguridad de 12 km.",
+            ]
+        );
+        assert_eq!(
+            n.disclaimer.as_deref(),
+            Some("El monitoreo del volcán Popocatépetl se realiza de forma continua las 24 horas. ARM")
+        );
+    }
+
+    #[test]
+    fn drops_link_boilerplate() {
+        for summary in [PARAGRAPH_LAYOUT, INLINE_LAYOUT] {
+            let n = Narrative::parse(summary);
+            for p in n.paragraphs() {
+                assert!(!p.starts_with("Mapa de Peligros"), "{p}");
+                assert!(!p.starts_with("Monitoreo volcán"), "{p}");
+                assert_ne!(p, "Semáforo de alerta volcánica");
+            }
+        }
+    }
+
+    /// A scenario heading run on after the phase sentence still opens the list.
+    #[test]
+    fn scenario_heading_after_phase_sentence() {
+        let n = Narrative::parse(
+            "Se detectaron 27 exhalaciones.\n\nEl Semáforo de Alerta Volcánica del Popocatépetl se encuentra en Amarillo Fase 2. Los escenarios previstos para esta fase son:\n\nAlgunas explosiones de tamaño menor a moderado",
+        );
+        assert_eq!(n.headline, None);
+        assert_eq!(n.observations.len(), 2);
+        assert_eq!(
+            n.scenarios,
+            vec!["Algunas explosiones de tamaño menor a moderado"]
+        );
+    }
+
+    /// Some captured pages hold only the headline and the scenario list.
+    #[test]
+    fn bullet_only_page_is_scenarios() {
+        let n = Narrative::parse(
+            "Reporte del monitoreo del Cenapred al volcán Popocatépetl 03 de Junio de 2023\n\nLluvias de ceniza notorias sobre poblaciones y ciudades\n\nCrecimiento importante de domos y posibilidad de expulsión de magma\n\nSemáforo de alerta volcánica",
+        );
+        assert!(n.observations.is_empty());
+        assert_eq!(n.scenarios.len(), 2);
+    }
+
+    #[test]
+    fn plain_text_is_all_observation() {
+        let n = Narrative::parse("Se detectaron 160 exhalaciones.");
+        assert_eq!(n.observations, vec!["Se detectaron 160 exhalaciones."]);
+        assert!(n.scenarios.is_empty());
+        assert_eq!(n.disclaimer, None);
+    }
+}
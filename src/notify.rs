@@ -0,0 +1,372 @@
+//! Sending a report somewhere people will see it.
+//!
+//! A [`Webhook`] is a destination and the shape of message it expects. Slack,
+//! Discord and Matrix get a short formatted message: the alert emoji and
+//! phase, the counters and any ashfall towns, then the reasons it was sent.
+//! The generic `json` kind posts a machine-readable document instead, signed
+//! with an HMAC-SHA256 of the body when a secret is configured so the receiver
+//! can check where it came from.
+
+use crate::error::{PopoError, Result};
+use crate::models::VolcanoReport;
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+
+/// Header carrying the signature of a `json` webhook's body, as
+/// `sha256=<hex>`.
+pub const SIGNATURE_HEADER: &str = "X-Popo-Signature";
+
+/// Environment variable holding the secret used to sign `json` webhooks.
+pub const WEBHOOK_SECRET_ENV: &str = "POPO_WEBHOOK_SECRET";
+
+/// Environment variable holding the access token for `matrix` webhooks.
+pub const MATRIX_TOKEN_ENV: &str = "POPO_MATRIX_TOKEN";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookKind {
+    /// A plain JSON document, optionally signed.
+    Json,
+    /// A Slack incoming webhook.
+    Slack,
+    /// A Discord channel webhook.
+    Discord,
+    /// A Matrix room, through the client-server API. The URL is the room's
+    /// `.../rooms/{room}/send/m.room.message` endpoint.
+    Matrix,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Webhook {
+    pub kind: WebhookKind,
+    pub url: String,
+    /// Signs `json` bodies.
+    pub secret: Option<String>,
+    /// Bearer token for `matrix`.
+    pub token: Option<String>,
+}
+
+/// What is being announced, and why.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification<'a> {
+    pub report: &'a VolcanoReport,
+    /// One line each, e.g. "Alert phase AMARILLO FASE 2 → AMARILLO FASE 3".
+    pub reasons: Vec<String>,
+}
+
+impl Webhook {
+    pub fn new(kind: WebhookKind, url: impl Into<String>) -> Self {
+        Webhook {
+            kind,
+            url: url.into(),
+            secret: None,
+            token: None,
+        }
+    }
+
+    /// Read "slack=https://hooks.slack.com/…". The secret and Matrix token
+    /// come from `POPO_WEBHOOK_SECRET` and `POPO_MATRIX_TOKEN`.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let invalid = || PopoError::InvalidWebhook(spec.to_string());
+        let (kind, url) = spec.split_once('=').ok_or_else(invalid)?;
+        let kind = match kind.trim().to_lowercase().as_str() {
+            "json" => WebhookKind::Json,
+            "slack" => WebhookKind::Slack,
+            "discord" => WebhookKind::Discord,
+            "matrix" => WebhookKind::Matrix,
+            _ => return Err(invalid()),
+        };
+        let url = url.trim();
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            return Err(invalid());
+        }
+        let mut webhook = Webhook::new(kind, url);
+        webhook.secret = std::env::var(WEBHOOK_SECRET_ENV).ok();
+        webhook.token = std::env::var(MATRIX_TOKEN_ENV).ok();
+        Ok(webhook)
+    }
+
+    /// The body this webhook would send.
+    pub fn payload(&self, notification: &Notification) -> Value {
+        match self.kind {
+            WebhookKind::Json => json_payload(notification),
+            WebhookKind::Slack => slack_payload(notification),
+            WebhookKind::Discord => discord_payload(notification),
+            WebhookKind::Matrix => matrix_payload(notification),
+        }
+    }
+
+    /// Deliver `notification`. Any non-2xx answer is an error.
+    pub fn send(
+        &self,
+        client: &reqwest::blocking::Client,
+        notification: &Notification,
+    ) -> Result<()> {
+        let body = self.payload(notification).to_string();
+        let request = match self.kind {
+            // Matrix sends are PUTs keyed by a transaction id, and the server
+            // drops a repeat of one it has seen, so sending the same news
+            // twice posts it once.
+            WebhookKind::Matrix => {
+                let txn = transaction_id(notification);
+                let mut request = client.put(format!("{}/{}", self.url.trim_end_matches('/'), txn));
+                if let Some(token) = &self.token {
+                    request = request.bearer_auth(token);
+                }
+                request
+            }
+            _ => client.post(&self.url),
+        };
+        let mut request = request
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.clone());
+        if let (WebhookKind::Json, Some(secret)) = (self.kind, &self.secret) {
+            request = request.header(SIGNATURE_HEADER, sign(secret, &body));
+        }
+
+        let response = request.send()?;
+        if !response.status().is_success() {
+            return Err(PopoError::Notify {
+                url: self.url.clone(),
+                reason: format!("HTTP {}", response.status()),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// "popo-2026-08-22-…": the report's date and a digest of the reasons, the
+/// same for the same news however often it is sent.
+pub fn transaction_id(notification: &Notification) -> String {
+    let digest = Sha256::digest(notification.reasons.join("\n").as_bytes());
+    let mut txn = format!("popo-{}-", notification.report.date);
+    for byte in &digest[..8] {
+        let _ = write!(txn, "{:02x}", byte);
+    }
+    txn
+}
+
+/// An HTTP client for webhooks, identified the same way as the feed client.
+pub fn client() -> reqwest::blocking::Client {
+    reqwest::blocking::Client::builder()
+        .user_agent(concat!("popo-cli/", env!("CARGO_PKG_VERSION")))
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .expect("failed to build HTTP client")
+}
+
+/// `sha256=` and the hex HMAC-SHA256 of `body` under `secret`.
+pub fn sign(secret: &str, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body.as_bytes());
+    let mut hex = String::from("sha256=");
+    for byte in mac.finalize().into_bytes() {
+        let _ = write!(hex, "{:02x}", byte);
+    }
+    hex
+}
+
+fn json_payload(n: &Notification) -> Value {
+    let r = n.report;
+    json!({
+        "event": "popo.report",
+        "date": r.date,
+        "alert_level": r.alert_level,
+        "alert_phase": r.alert_phase,
+        "counters": counters(r),
+        "ashfall": r.ashfall_places().names(),
+        "reasons": n.reasons,
+        "report": r,
+    })
+}
+
+fn slack_payload(n: &Notification) -> Value {
+    let mut text = format!("*{}*\n{}", headline(n.report), counter_line(n.report));
+    push_details(&mut text, n, "_", "•");
+    json!({
+        "text": headline(n.report),
+        "blocks": [{"type": "section", "text": {"type": "mrkdwn", "text": text}}],
+    })
+}
+
+fn discord_payload(n: &Notification) -> Value {
+    let mut description = counter_line(n.report);
+    push_details(&mut description, n, "*", "•");
+    json!({
+        "embeds": [{
+            "title": headline(n.report),
+            "description": description,
+            "color": colour(n.report),
+        }],
+    })
+}
+
+fn matrix_payload(n: &Notification) -> Value {
+    let mut body = format!("{}\n{}", headline(n.report), counter_line(n.report));
+    push_details(&mut body, n, "", "•");
+    let mut html = format!(
+        "<strong>{}</strong><br>{}",
+        escape(&headline(n.report)),
+        escape(&counter_line(n.report))
+    );
+    let towns = n.report.ashfall_places().names();
+    if !towns.is_empty() {
+        let _ = write!(html, "<br><em>Ashfall:</em> {}", escape(&towns.join("; ")));
+    }
+    if !n.reasons.is_empty() {
+        html.push_str("<ul>");
+        for reason in &n.reasons {
+            let _ = write!(html, "<li>{}</li>", escape(reason));
+        }
+        html.push_str("</ul>");
+    }
+    json!({
+        "msgtype": "m.text",
+        "body": body,
+        "format": "org.matrix.custom.html",
+        "formatted_body": html,
+    })
+}
+
+/// "🟡 Popocatépetl AMARILLO FASE 2 · 2026-08-22".
+pub(crate) fn headline(report: &VolcanoReport) -> String {
+    let emoji = report.alert_level.map(|l| l.emoji()).unwrap_or("⚪");
+    let phase = report
+        .alert_phase
+        .clone()
+        .or_else(|| {
+            report
+                .alert_level
+                .map(|l| format!("{:?}", l).to_uppercase())
+        })
+        .unwrap_or_else(|| "alert not stated".to_string());
+    format!("{} Popocatépetl {} · {}", emoji, phase, report.date)
+}
+
+/// "Exhalations 6 · Explosions 0 · VT events 0 · Tremor 0 min".
+pub(crate) fn counter_line(report: &VolcanoReport) -> String {
+    let show = |v: Option<u32>| v.map(|n| n.to_string()).unwrap_or_else(|| "-".into());
+    format!(
+        "Exhalations {} · Explosions {} · VT events {} · Tremor {} min",
+        show(report.exhalations),
+        show(report.explosions),
+        show(report.volcanotectonic_events),
+        show(report.tremor_minutes_total)
+    )
+}
+
+/// Ashfall towns and reasons, as lines under the counters. `emphasis` wraps
+/// the "Ashfall:" label in the target's markup.
+fn push_details(text: &mut String, n: &Notification, emphasis: &str, bullet: &str) {
+    let towns = n.report.ashfall_places().names();
+    if !towns.is_empty() {
+        let _ = write!(text, "\n{0}Ashfall:{0} {1}", emphasis, towns.join("; "));
+    }
+    for reason in &n.reasons {
+        let _ = write!(text, "\n{} {}", bullet, reason);
+    }
+}
+
+fn counters(report: &VolcanoReport) -> Value {
+    json!({
+        "exhalations": report.exhalations,
+        "explosions": report.explosions,
+        "volcanotectonic_events": report.volcanotectonic_events,
+        "tremor_minutes_total": report.tremor_minutes_total,
+        "so2_emissions_tons_per_day": report.so2_emissions_tons_per_day,
+    })
+}
+
+/// Discord embed colour for the alert level.
+fn colour(report: &VolcanoReport) -> u32 {
+    use crate::models::AlertLevel;
+    match report.alert_level {
+        Some(AlertLevel::Green) => 0x2ecc71,
+        Some(AlertLevel::Yellow) => 0xf1c40f,
+        Some(AlertLevel::Orange) => 0xe67e22,
+        Some(AlertLevel::Red) => 0xe74c3c,
+        None => 0x95a5a6,
+    }
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::AlertLevel;
+
+    fn report() -> VolcanoReport {
+        let mut report = VolcanoReport::blank("2026-08-22");
+        report.alert_level = Some(AlertLevel::Yellow);
+        report.alert_phase = Some("AMARILLO FASE 2".into());
+        report.exhalations = Some(6);
+        report.explosions = Some(1);
+        report.ashfall_reports = vec!["Amecameca y Ozumba, Estado de México".into()];
+        report
+    }
+
+    fn notification(report: &VolcanoReport) -> Notification<'_> {
+        Notification {
+            report,
+            reasons: vec!["Explosions 0 → 1".into()],
+        }
+    }
+
+    #[test]
+    fn parse_reads_kind_and_url() {
+        let hook = Webhook::parse("Slack=https://hooks.slack.com/services/T/B/x").unwrap();
+        assert_eq!(hook.kind, WebhookKind::Slack);
+        assert_eq!(hook.url, "https://hooks.slack.com/services/T/B/x");
+        assert!(Webhook::parse("teams=https://example.com").is_err());
+        assert!(Webhook::parse("json=example.com").is_err());
+        assert!(Webhook::parse("https://example.com").is_err());
+    }
+
+    #[test]
+    fn signature_matches_a_known_hmac() {
+        // RFC 4231 test case 2.
+        assert_eq!(
+            sign("Jefe", "what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn chat_messages_carry_emoji_phase_counters_and_towns() {
+        let report = report();
+        let n = notification(&report);
+        let slack = Webhook::new(WebhookKind::Slack, "https://x").payload(&n);
+        let text = slack["blocks"][0]["text"]["text"].as_str().unwrap();
+        assert!(text.starts_with("*🟡 Popocatépetl AMARILLO FASE 2 · 2026-08-22*"));
+        assert!(text.contains("Exhalations 6 · Explosions 1"));
+        assert!(text.contains("_Ashfall:_ Amecameca, Estado de México; Ozumba, Estado de México"));
+        assert!(text.ends_with("• Explosions 0 → 1"));
+
+        let discord = Webhook::new(WebhookKind::Discord, "https://x").payload(&n);
+        assert_eq!(discord["embeds"][0]["color"], 0xf1c40f);
+
+        let matrix = Webhook::new(WebhookKind::Matrix, "https://x").payload(&n);
+        assert_eq!(matrix["msgtype"], "m.text");
+        assert!(matrix["formatted_body"]
+            .as_str()
+            .unwrap()
+            .contains("<li>Explosions 0 → 1</li>"));
+    }
+
+    #[test]
+    fn json_payload_is_structured() {
+        let report = report();
+        let json = Webhook::new(WebhookKind::Json, "https://x").payload(&notification(&report));
+        assert_eq!(json["alert_level"], "YELLOW");
+        assert_eq!(json["counters"]["exhalations"], 6);
+        assert_eq!(json["ashfall"][1], "Ozumba, Estado de México");
+        assert_eq!(json["report"]["date"], "2026-08-22");
+    }
+}
@@ -0,0 +1,69 @@
+//! Where popo keeps files of its own between runs.
+
+use std::path::{Path, PathBuf};
+
+/// Environment variable overriding the cache directory, read with the rest
+/// of the configuration.
+pub const CACHE_DIR_ENV: &str = "POPO_CACHE_DIR";
+
+/// Environment variable naming the user config file.
+pub const CONFIG_ENV: &str = "POPO_CONFIG";
+
+/// Directory for data popo can always rebuild from the feed, such as the
+/// search index. The configured `cache_dir` wins, then the platform
+/// convention. `None` only when no home directory can be found at all.
+pub fn cache_dir(configured: Option<&Path>) -> Option<PathBuf> {
+    if let Some(dir) = configured {
+        return Some(dir.to_path_buf());
+    }
+    if let Some(dir) = env_path("XDG_CACHE_HOME") {
+        return Some(dir.join("popo"));
+    }
+    if cfg!(windows) {
+        return env_path("LOCALAPPDATA").map(|d| d.join("popo").join("cache"));
+    }
+    env_path("HOME").map(|home| home.join(".cache").join("popo"))
+}
+
+/// The user's config file, whether or not it exists yet. `POPO_CONFIG` wins,
+/// then the platform convention.
+pub fn user_config_file() -> Option<PathBuf> {
+    if let Some(file) = env_path(CONFIG_ENV) {
+        return Some(file);
+    }
+    let dir = if let Some(dir) = env_path("XDG_CONFIG_HOME") {
+        dir.join("popo")
+    } else if cfg!(windows) {
+        env_path("APPDATA")?.join("popo")
+    } else {
+        env_path("HOME")?.join(".config").join("popo")
+    };
+    Some(dir.join("config.toml"))
+}
+
+/// A filesystem-safe name for a feed base, so caches built from different
+/// feeds never overwrite each other.
+pub fn feed_slug(base: &str) -> String {
+    base.trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn env_path(var: &str) -> Option<PathBuf> {
+    std::env::var_os(var)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn feed_slug_is_filesystem_safe() {
+        assert_eq!(feed_slug("https://example.com/data"), "example_com_data");
+        assert_eq!(feed_slug("./data"), "__data");
+    }
+}
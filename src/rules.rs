@@ -0,0 +1,835 @@
+//! User-defined alert rules.
+//!
+//! A rules file is TOML with one `[[rule]]` table per rule:
+//!
+//! ```toml
+//! [[rule]]
+//! name = "Orange or worse"
+//! when = "alert_level >= ORANGE"
+//!
+//! [[rule]]
+//! name = "Unusually many exhalations"
+//! when = "exhalations > p95(30d) and explosions >= 1"
+//! missing = "error"
+//! ```
+//!
+//! A condition compares a field with a literal, or with a statistic of the
+//! same field over the days before the report (`p95(30d)`, `median(7d)`,
+//! `max(90d)`, up to [`MAX_WINDOW_DAYS`]), and conditions combine with `and`, `or`, `not` and
+//! parentheses. Text and ashfall places are tested with `contains`, accents
+//! and case ignored.
+//!
+//! A field the report does not state makes a comparison unknown rather than
+//! false. `and` and `or` settle an unknown side where the other side decides
+//! on its own; what is left is handled by the rule's `missing` setting:
+//! `ignore` (the default) treats it as not matched, `match` as matched, and
+//! `error` reports the rule as undecided. `field is missing` tests for an
+//! absent value directly.
+
+use crate::error::{PopoError, Result};
+use crate::models::{AlertLevel, VolcanoReport, WindDirection};
+use crate::text::fold;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// The longest statistic window a condition may ask for, ten years.
+pub const MAX_WINDOW_DAYS: u32 = 3650;
+
+/// A report field a condition can refer to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Exhalations,
+    VolcanotectonicEvents,
+    TremorMinutesTotal,
+    TremorHighFrequencyMinutes,
+    TremorHarmonicMinutes,
+    Explosions,
+    So2EmissionsTonsPerDay,
+    AlertLevel,
+    AlertPhase,
+    WindDirection,
+    Ashfall,
+    Summary,
+}
+
+/// What a field holds, which decides the comparisons it allows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Number,
+    Level,
+    Direction,
+    Text,
+    Places,
+}
+
+impl Field {
+    /// The field named as in the report's JSON; `ashfall` and `summary` stand
+    /// for `ashfall_reports` and `summary_spanish`.
+    pub fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "exhalations" => Field::Exhalations,
+            "volcanotectonic_events" => Field::VolcanotectonicEvents,
+            "tremor_minutes_total" => Field::TremorMinutesTotal,
+            "tremor_high_frequency_minutes" => Field::TremorHighFrequencyMinutes,
+            "tremor_harmonic_minutes" => Field::TremorHarmonicMinutes,
+            "explosions" => Field::Explosions,
+            "so2_emissions_tons_per_day" | "so2" => Field::So2EmissionsTonsPerDay,
+            "alert_level" => Field::AlertLevel,
+            "alert_phase" => Field::AlertPhase,
+            "wind_direction" => Field::WindDirection,
+            "ashfall" | "ashfall_reports" => Field::Ashfall,
+            "summary" | "summary_spanish" => Field::Summary,
+            _ => return None,
+        })
+    }
+
+    fn kind(self) -> Kind {
+        match self {
+            Field::AlertLevel => Kind::Level,
+            Field::WindDirection => Kind::Direction,
+            Field::AlertPhase | Field::Summary => Kind::Text,
+            Field::Ashfall => Kind::Places,
+            _ => Kind::Number,
+        }
+    }
+
+    fn number(self, report: &VolcanoReport) -> Option<f64> {
+        let count = match self {
+            Field::Exhalations => report.exhalations,
+            Field::VolcanotectonicEvents => report.volcanotectonic_events,
+            Field::TremorMinutesTotal => report.tremor_minutes_total,
+            Field::TremorHighFrequencyMinutes => report.tremor_high_frequency_minutes,
+            Field::TremorHarmonicMinutes => report.tremor_harmonic_minutes,
+            Field::Explosions => report.explosions,
+            Field::So2EmissionsTonsPerDay => return report.so2_emissions_tons_per_day,
+            _ => None,
+        };
+        count.map(f64::from)
+    }
+
+    /// The texts `contains` searches. A partial record has no ashfall or
+    /// narrative at all, which is unknown rather than empty.
+    fn texts(self, report: &VolcanoReport) -> Option<Vec<String>> {
+        match self {
+            Field::AlertPhase => report.alert_phase.clone().map(|p| vec![p]),
+            Field::Summary => report.summary_spanish.clone().map(|s| vec![s]),
+            Field::Ashfall if report.partial => None,
+            Field::Ashfall => {
+                let mut texts = report.ashfall_places().names();
+                texts.extend(report.ashfall_reports.iter().cloned());
+                Some(texts)
+            }
+            _ => None,
+        }
+    }
+
+    fn is_missing(self, report: &VolcanoReport) -> bool {
+        match self.kind() {
+            Kind::Number => self.number(report).is_none(),
+            Kind::Level => report.alert_level.is_none(),
+            Kind::Direction => report.wind_direction.is_none(),
+            Kind::Text | Kind::Places => self.texts(report).is_none(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl Op {
+    fn holds<T: PartialOrd>(self, left: T, right: T) -> bool {
+        match self {
+            Op::Lt => left < right,
+            Op::Le => left <= right,
+            Op::Gt => left > right,
+            Op::Ge => left >= right,
+            Op::Eq => left == right,
+            Op::Ne => left != right,
+        }
+    }
+}
+
+/// A summary of one field over the days before a report.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stat {
+    /// Nearest-rank percentile, 1 to 100. `median` is the 50th.
+    Percentile(u8),
+    Mean,
+    Min,
+    Max,
+}
+
+impl Stat {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "mean" | "avg" => Some(Stat::Mean),
+            "min" => Some(Stat::Min),
+            "max" => Some(Stat::Max),
+            "median" => Some(Stat::Percentile(50)),
+            _ => name
+                .strip_prefix('p')
+                .and_then(|n| n.parse().ok())
+                .filter(|n| (1..=100).contains(n))
+                .map(Stat::Percentile),
+        }
+    }
+
+    fn of(self, mut values: Vec<f64>) -> Option<f64> {
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_by(f64::total_cmp);
+        Some(match self {
+            Stat::Percentile(p) => {
+                let rank = (f64::from(p) / 100.0 * values.len() as f64).ceil() as usize;
+                values[rank.max(1) - 1]
+            }
+            Stat::Mean => values.iter().sum::<f64>() / values.len() as f64,
+            Stat::Min => values[0],
+            Stat::Max => values[values.len() - 1],
+        })
+    }
+}
+
+/// The right-hand side of a comparison.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Number(f64),
+    Level(AlertLevel),
+    Direction(WindDirection),
+    Text(String),
+    /// The field's own statistic over the `days` before the report.
+    Stat {
+        stat: Stat,
+        days: u32,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    Compare { field: Field, op: Op, value: Value },
+    Contains { field: Field, needle: String },
+    Missing(Field),
+    And(Vec<Condition>),
+    Or(Vec<Condition>),
+    Not(Box<Condition>),
+}
+
+impl Condition {
+    /// Read a condition such as `explosions >= 5 and alert_level >= ORANGE`.
+    pub fn parse(input: &str) -> Result<Condition> {
+        parse_condition(input).map_err(PopoError::InvalidRules)
+    }
+
+    /// True or false where the report decides it, `None` where a value it
+    /// needs is missing.
+    pub fn evaluate(&self, report: &VolcanoReport, history: &[VolcanoReport]) -> Option<bool> {
+        match self {
+            Condition::Compare { field, op, value } => compare(*field, *op, value, report, history),
+            Condition::Contains { field, needle } => {
+                let needle = fold(needle);
+                let texts = field.texts(report)?;
+                Some(texts.iter().any(|t| fold(t).contains(&needle)))
+            }
+            Condition::Missing(field) => Some(field.is_missing(report)),
+            // Kleene logic: one false settles `and`, one true settles `or`.
+            Condition::And(parts) => {
+                let results: Vec<_> = parts.iter().map(|p| p.evaluate(report, history)).collect();
+                if results.contains(&Some(false)) {
+                    Some(false)
+                } else if results.contains(&None) {
+                    None
+                } else {
+                    Some(true)
+                }
+            }
+            Condition::Or(parts) => {
+                let results: Vec<_> = parts.iter().map(|p| p.evaluate(report, history)).collect();
+                if results.contains(&Some(true)) {
+                    Some(true)
+                } else if results.contains(&None) {
+                    None
+                } else {
+                    Some(false)
+                }
+            }
+            Condition::Not(inner) => inner.evaluate(report, history).map(|b| !b),
+        }
+    }
+
+    /// The longest statistic window the condition uses, in days.
+    fn history_days(&self) -> u32 {
+        match self {
+            Condition::Compare {
+                value: Value::Stat { days, .. },
+                ..
+            } => *days,
+            Condition::And(parts) | Condition::Or(parts) => {
+                parts.iter().map(Condition::history_days).max().unwrap_or(0)
+            }
+            Condition::Not(inner) => inner.history_days(),
+            _ => 0,
+        }
+    }
+}
+
+/// Parse errors carry only the reason; [`RuleSet::parse`] adds the rule name.
+type Parsed<T> = std::result::Result<T, String>;
+
+fn parse_condition(input: &str) -> Parsed<Condition> {
+    let lexemes = lex(input)?;
+    if lexemes.is_empty() {
+        return Err("the condition is empty".to_string());
+    }
+    let mut parser = Parser { lexemes, pos: 0 };
+    let condition = parser.or_expr()?;
+    match parser.peek() {
+        None => Ok(condition),
+        Some(Lexeme::Close) => Err("unbalanced ')'".to_string()),
+        Some(_) => Err("expected 'and' or 'or' between comparisons".to_string()),
+    }
+}
+
+fn compare(
+    field: Field,
+    op: Op,
+    value: &Value,
+    report: &VolcanoReport,
+    history: &[VolcanoReport],
+) -> Option<bool> {
+    match value {
+        Value::Number(n) => Some(op.holds(field.number(report)?, *n)),
+        Value::Stat { stat, days } => {
+            let actual = field.number(report)?;
+            let since = report
+                .date
+                .checked_sub_days(chrono::Days::new(u64::from(*days)))
+                .unwrap_or(chrono::NaiveDate::MIN);
+            let window = history
+                .iter()
+                .filter(|r| r.date >= since && r.date < report.date)
+                .filter_map(|r| field.number(r))
+                .collect();
+            Some(op.holds(actual, stat.of(window)?))
+        }
+        Value::Level(level) => Some(op.holds(report.alert_level?, *level)),
+        Value::Direction(dir) => Some(op.holds(report.wind_direction?, *dir)),
+        Value::Text(text) => {
+            let actual = field.texts(report)?.into_iter().next()?;
+            Some(op.holds(fold(&actual), fold(text)))
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Lexeme {
+    Word(String),
+    Number(f64),
+    Days(u32),
+    Text(String),
+    Op(Op),
+    And,
+    Or,
+    Not,
+    Is,
+    Contains,
+    Open,
+    Close,
+}
+
+fn lex(input: &str) -> Parsed<Vec<Lexeme>> {
+    let mut out = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '(' => out.push(Lexeme::Open),
+            ')' => out.push(Lexeme::Close),
+            '"' => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => text.push(c),
+                        None => return Err("unterminated string".to_string()),
+                    }
+                }
+                out.push(Lexeme::Text(text));
+            }
+            '<' | '>' | '=' | '!' => {
+                let equals = chars.next_if_eq(&'=').is_some();
+                out.push(Lexeme::Op(match (c, equals) {
+                    ('<', false) => Op::Lt,
+                    ('<', true) => Op::Le,
+                    ('>', false) => Op::Gt,
+                    ('>', true) => Op::Ge,
+                    ('=', _) => Op::Eq,
+                    ('!', true) => Op::Ne,
+                    _ => return Err("'!' must be followed by '='".to_string()),
+                }));
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '.' => {
+                let mut word = c.to_string();
+                while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_' || *c == '.')
+                {
+                    word.push(c);
+                }
+                out.push(word_lexeme(word)?);
+            }
+            other => return Err(format!("unexpected '{}'", other)),
+        }
+    }
+
+    Ok(out)
+}
+
+fn word_lexeme(word: String) -> Parsed<Lexeme> {
+    if word.starts_with(|c: char| c.is_ascii_digit()) {
+        if let Some(days) = word.strip_suffix('d') {
+            return days
+                .parse()
+                .map(Lexeme::Days)
+                .map_err(|_| format!("'{}' is not a number of days", word));
+        }
+        return word
+            .parse()
+            .map(Lexeme::Number)
+            .map_err(|_| format!("'{}' is not a number", word));
+    }
+    Ok(match word.to_lowercase().as_str() {
+        "and" => Lexeme::And,
+        "or" => Lexeme::Or,
+        "not" => Lexeme::Not,
+        "is" => Lexeme::Is,
+        "contains" => Lexeme::Contains,
+        _ => Lexeme::Word(word),
+    })
+}
+
+struct Parser {
+    lexemes: Vec<Lexeme>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Lexeme> {
+        self.lexemes.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Lexeme> {
+        let lexeme = self.lexemes.get(self.pos).cloned();
+        self.pos += 1;
+        lexeme
+    }
+
+    fn or_expr(&mut self) -> Parsed<Condition> {
+        let mut parts = vec![self.and_expr()?];
+        while self.peek() == Some(&Lexeme::Or) {
+            self.pos += 1;
+            parts.push(self.and_expr()?);
+        }
+        Ok(flatten(parts, Condition::Or))
+    }
+
+    fn and_expr(&mut self) -> Parsed<Condition> {
+        let mut parts = vec![self.unary()?];
+        while self.peek() == Some(&Lexeme::And) {
+            self.pos += 1;
+            parts.push(self.unary()?);
+        }
+        Ok(flatten(parts, Condition::And))
+    }
+
+    fn unary(&mut self) -> Parsed<Condition> {
+        match self.next() {
+            Some(Lexeme::Not) => Ok(Condition::Not(Box::new(self.unary()?))),
+            Some(Lexeme::Open) => {
+                let inner = self.or_expr()?;
+                if self.next() != Some(Lexeme::Close) {
+                    return Err("missing ')'".to_string());
+                }
+                Ok(inner)
+            }
+            Some(Lexeme::Word(name)) => {
+                let field =
+                    Field::parse(&name).ok_or_else(|| format!("unknown field '{}'", name))?;
+                self.comparison(field)
+            }
+            Some(_) => Err("expected a field name".to_string()),
+            None => Err("the condition ends where a field name was expected".to_string()),
+        }
+    }
+
+    fn comparison(&mut self, field: Field) -> Parsed<Condition> {
+        let kind = field.kind();
+        match self.next() {
+            Some(Lexeme::Is) => {
+                let negated = self.peek() == Some(&Lexeme::Not);
+                if negated {
+                    self.pos += 1;
+                }
+                match self.next() {
+                    Some(Lexeme::Word(w)) if w.eq_ignore_ascii_case("missing") => {}
+                    _ => return Err("expected 'is missing' or 'is not missing'".to_string()),
+                }
+                let missing = Condition::Missing(field);
+                Ok(if negated {
+                    Condition::Not(Box::new(missing))
+                } else {
+                    missing
+                })
+            }
+            Some(Lexeme::Contains) => match (kind, self.next()) {
+                (Kind::Text | Kind::Places, Some(Lexeme::Text(needle))) => {
+                    Ok(Condition::Contains { field, needle })
+                }
+                (Kind::Text | Kind::Places, _) => {
+                    Err("'contains' needs a \"quoted\" string".to_string())
+                }
+                _ => Err("'contains' only applies to text and ashfall".to_string()),
+            },
+            Some(Lexeme::Op(op)) => {
+                let value = self.value(kind)?;
+                let ordered = !matches!(op, Op::Eq | Op::Ne);
+                if ordered && matches!(kind, Kind::Direction | Kind::Text) {
+                    return Err("only '==' and '!=' compare wind and text".to_string());
+                }
+                if kind == Kind::Places {
+                    return Err("use 'ashfall contains \"place\"'".to_string());
+                }
+                Ok(Condition::Compare { field, op, value })
+            }
+            _ => Err("expected a comparison, 'contains' or 'is missing'".to_string()),
+        }
+    }
+
+    fn value(&mut self, kind: Kind) -> Parsed<Value> {
+        match (kind, self.next()) {
+            (Kind::Number, Some(Lexeme::Number(n))) => Ok(Value::Number(n)),
+            (Kind::Number, Some(Lexeme::Word(name))) => {
+                let stat = Stat::parse(&name.to_lowercase())
+                    .ok_or_else(|| format!("unknown statistic '{}'", name))?;
+                let days = match (self.next(), self.next(), self.next()) {
+                    (Some(Lexeme::Open), Some(Lexeme::Days(days)), Some(Lexeme::Close))
+                        if days > 0 =>
+                    {
+                        days
+                    }
+                    _ => return Err(format!("write the window as {}(30d)", name)),
+                };
+                if days > MAX_WINDOW_DAYS {
+                    return Err(format!(
+                        "a window of {} days is too long; the limit is {}d",
+                        days, MAX_WINDOW_DAYS
+                    ));
+                }
+                Ok(Value::Stat { stat, days })
+            }
+            (Kind::Level, Some(Lexeme::Word(name))) => {
+                let level = match name.to_uppercase().as_str() {
+                    "GREEN" => AlertLevel::Green,
+                    "YELLOW" => AlertLevel::Yellow,
+                    "ORANGE" => AlertLevel::Orange,
+                    "RED" => AlertLevel::Red,
+                    _ => return Err(format!("'{}' is not an alert level", name)),
+                };
+                Ok(Value::Level(level))
+            }
+            (Kind::Direction, Some(Lexeme::Word(name))) => WindDirection::ALL
+                .into_iter()
+                .find(|d| format!("{:?}", d).eq_ignore_ascii_case(&name))
+                .map(Value::Direction)
+                .ok_or_else(|| format!("'{}' is not a compass point", name)),
+            (Kind::Text, Some(Lexeme::Text(text))) => Ok(Value::Text(text)),
+            (Kind::Number, _) => Err("expected a number or a statistic".to_string()),
+            (Kind::Level, _) => Err("expected GREEN, YELLOW, ORANGE or RED".to_string()),
+            (Kind::Direction, _) => Err("expected a compass point such as NE".to_string()),
+            (Kind::Text | Kind::Places, _) => Err("expected a \"quoted\" string".to_string()),
+        }
+    }
+}
+
+fn flatten(mut parts: Vec<Condition>, combine: fn(Vec<Condition>) -> Condition) -> Condition {
+    if parts.len() == 1 {
+        parts.pop().unwrap()
+    } else {
+        combine(parts)
+    }
+}
+
+/// What an undecided rule means.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OnMissing {
+    /// Not matched.
+    #[default]
+    Ignore,
+    Match,
+    /// Reported as undecided.
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub name: String,
+    /// The condition as written.
+    pub when: String,
+    pub condition: Condition,
+    pub missing: OnMissing,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Matched,
+    NotMatched,
+    Undecided,
+}
+
+/// How one rule came out against one report.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RuleResult {
+    pub name: String,
+    pub when: String,
+    pub outcome: Outcome,
+    /// Whether missing data left the condition open and `missing` decided.
+    pub missing_data: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulesFile {
+    #[serde(default)]
+    rule: Vec<RawRule>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawRule {
+    name: String,
+    when: String,
+    #[serde(default)]
+    missing: OnMissing,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RuleSet {
+    pub rules: Vec<Rule>,
+}
+
+impl RuleSet {
+    pub fn parse(toml: &str) -> Result<Self> {
+        let file: RulesFile =
+            toml::from_str(toml).map_err(|e| PopoError::InvalidRules(e.message().to_string()))?;
+        let rules = file
+            .rule
+            .into_iter()
+            .map(|raw| {
+                let condition = parse_condition(&raw.when).map_err(|reason| {
+                    PopoError::InvalidRules(format!("rule '{}': {}", raw.name, reason))
+                })?;
+                Ok(Rule {
+                    name: raw.name,
+                    when: raw.when,
+                    condition,
+                    missing: raw.missing,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if rules.is_empty() {
+            return Err(PopoError::InvalidRules(
+                "no [[rule]] tables in the file".to_string(),
+            ));
+        }
+        Ok(RuleSet { rules })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let toml = std::fs::read_to_string(path).map_err(|e| {
+            PopoError::InvalidRules(format!("cannot read {}: {}", path.display(), e))
+        })?;
+        Self::parse(&toml)
+    }
+
+    /// How many days before a report [`check`](Self::check) needs to see.
+    pub fn history_days(&self) -> u32 {
+        self.rules
+            .iter()
+            .map(|r| r.condition.history_days())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Every rule against `report`. `history` should hold the
+    /// [`history_days`](Self::history_days) before it; other days are ignored.
+    pub fn check(&self, report: &VolcanoReport, history: &[VolcanoReport]) -> Vec<RuleResult> {
+        self.rules
+            .iter()
+            .map(|rule| {
+                let result = rule.condition.evaluate(report, history);
+                let outcome = match (result, rule.missing) {
+                    (Some(true), _) | (None, OnMissing::Match) => Outcome::Matched,
+                    (Some(false), _) | (None, OnMissing::Ignore) => Outcome::NotMatched,
+                    (None, OnMissing::Error) => Outcome::Undecided,
+                };
+                RuleResult {
+                    name: rule.name.clone(),
+                    when: rule.when.clone(),
+                    outcome,
+                    missing_data: result.is_none(),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(date: &str, exhalations: Option<u32>) -> VolcanoReport {
+        let mut report = VolcanoReport::blank(date);
+        report.alert_level = Some(AlertLevel::Yellow);
+        report.alert_phase = Some("AMARILLO FASE 2".into());
+        report.exhalations = exhalations;
+        report.explosions = Some(2);
+        report.wind_direction = Some(WindDirection::W);
+        report.ashfall_reports = vec!["San Pedro Cholula".into()];
+        report
+    }
+
+    fn holds(when: &str, report: &VolcanoReport) -> Option<bool> {
+        Condition::parse(when).unwrap().evaluate(report, &[])
+    }
+
+    #[test]
+    fn comparisons_by_field_kind() {
+        let today = report("2026-08-22", Some(40));
+        assert_eq!(holds("explosions >= 2", &today), Some(true));
+        assert_eq!(holds("exhalations < 10", &today), Some(false));
+        assert_eq!(holds("alert_level >= yellow", &today), Some(true));
+        assert_eq!(holds("alert_level >= ORANGE", &today), Some(false));
+        assert_eq!(holds("wind_direction == W", &today), Some(true));
+        assert_eq!(
+            holds("alert_phase == \"amarillo fase 2\"", &today),
+            Some(true)
+        );
+        assert_eq!(holds("ashfall contains \"puebla\"", &today), Some(true));
+        assert_eq!(holds("summary is missing", &today), Some(true));
+    }
+
+    #[test]
+    fn missing_values_are_unknown_until_settled() {
+        let today = report("2026-08-22", None);
+        assert_eq!(holds("exhalations > 5", &today), None);
+        assert_eq!(holds("not exhalations > 5", &today), None);
+        assert_eq!(
+            holds("exhalations > 5 or explosions > 1", &today),
+            Some(true)
+        );
+        assert_eq!(
+            holds("exhalations > 5 and explosions > 9", &today),
+            Some(false)
+        );
+        assert_eq!(holds("exhalations is not missing", &today), Some(false));
+
+        let mut partial = VolcanoReport::blank("2026-08-22");
+        partial.partial = true;
+        assert_eq!(holds("ashfall contains \"Atlixco\"", &partial), None);
+    }
+
+    #[test]
+    fn statistics_use_the_days_before_the_report() {
+        let history: Vec<_> = (1..=10)
+            .map(|day| report(&format!("2026-08-{:02}", day), Some(day * 10)))
+            .collect();
+        let p90 = Condition::parse("exhalations > p90(10d)").unwrap();
+        let today = report("2026-08-11", Some(95));
+        assert_eq!(p90.evaluate(&today, &history), Some(true));
+        let quiet = report("2026-08-11", Some(85));
+        assert_eq!(p90.evaluate(&quiet, &history), Some(false));
+        // Nothing in the window is unknown, not zero.
+        let later = report("2026-09-30", Some(95));
+        assert_eq!(p90.evaluate(&later, &history), None);
+
+        assert_eq!(Stat::Percentile(50).of(vec![3.0, 1.0, 2.0]), Some(2.0));
+        assert_eq!(Stat::Mean.of(vec![1.0, 2.0]), Some(1.5));
+    }
+
+    #[test]
+    fn rejects_malformed_conditions() {
+        for bad in [
+            "",
+            "lava > 3",
+            "explosions >",
+            "explosions > 3 explosions < 5",
+            "(explosions > 3",
+            "alert_level > PURPLE",
+            "wind_direction > N",
+            "ashfall == \"Atlixco\"",
+            "exhalations > p95",
+            "exhalations > p95(0d)",
+            "exhalations > p95(3651d)",
+            "exhalations > p95(4000000000d)",
+            "explosions contains \"3\"",
+        ] {
+            assert!(Condition::parse(bad).is_err(), "{:?} parsed", bad);
+        }
+    }
+
+    #[test]
+    fn rule_files_and_missing_policies() {
+        let rules = RuleSet::parse(
+            r#"
+            [[rule]]
+            name = "Busy"
+            when = "explosions >= 2"
+
+            [[rule]]
+            name = "Quiet tremor"
+            when = "tremor_minutes_total < 60"
+            missing = "error"
+
+            [[rule]]
+            name = "Above normal"
+            when = "exhalations > mean(30d)"
+            missing = "match"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(rules.history_days(), 30);
+
+        let outcomes: Vec<_> = rules
+            .check(&report("2026-08-22", Some(5)), &[])
+            .into_iter()
+            .map(|r| (r.outcome, r.missing_data))
+            .collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                (Outcome::Matched, false),
+                (Outcome::Undecided, true),
+                (Outcome::Matched, true),
+            ]
+        );
+
+        let err = RuleSet::parse("[[rule]]\nname = \"x\"\nwhen = \"lava > 1\"\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid rules: rule 'x': unknown field 'lava'"
+        );
+        assert!(RuleSet::parse("").is_err());
+        assert!(RuleSet::parse(
+            "[[rule]]\nname = \"x\"\nwhen = \"explosions > 1\"\nmissing = \"maybe\"\n"
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,705 @@
+//! Full-text search over the Spanish narratives.
+//!
+//! Matching is accent- and case-insensitive, and every query word matches as a
+//! prefix, so `lahar` finds "lahares" and `ceniza` finds "cenizas". Spanish
+//! inflects mostly by suffix, which makes prefix matching a cheap stand-in for
+//! stemming that never invents a match the reader cannot see in the snippet.
+//!
+//! The index persists between runs. Reading every report over HTTP is the slow
+//! part, so [`SearchIndex::refresh`] only fetches days it has not seen before.
+//! It also keeps each full report's ashfall, which is what `popo here` and
+//! `popo ashfall --top` read from the archive.
+
+use crate::ashfall::AshfallDay;
+use crate::error::{PopoError, Result};
+use crate::feed::Feed;
+use crate::format::Tabular;
+use crate::models::VolcanoReport;
+use crate::text::{fold, tokens};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Bumped whenever the on-disk layout or tokenisation changes, so an old index
+/// is rebuilt rather than misread.
+const INDEX_VERSION: u32 = 2;
+
+/// Characters of context either side of the first match in a snippet.
+const SNIPPET_CONTEXT: usize = 70;
+
+/// A parsed search query.
+///
+/// Words are ANDed by default. `OR` and `NOT` (or a leading `-`) are
+/// recognised in uppercase only, so the Spanish "o" and "no" stay searchable.
+/// Double quotes make a phrase; parentheses group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Query {
+    Term(String),
+    Phrase(Vec<String>),
+    And(Vec<Query>),
+    Or(Vec<Query>),
+    Not(Box<Query>),
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Result<Query> {
+        let lexemes = lex(input)?;
+        if lexemes.is_empty() {
+            return Err(PopoError::InvalidQuery("the query is empty".to_string()));
+        }
+        let mut parser = Parser { lexemes, pos: 0 };
+        let query = parser.or_expr()?;
+        if parser.pos < parser.lexemes.len() {
+            return Err(PopoError::InvalidQuery(
+                "unbalanced ')' in query".to_string(),
+            ));
+        }
+        if !query.has_positive() {
+            return Err(PopoError::InvalidQuery(
+                "a query needs at least one word that is not negated".to_string(),
+            ));
+        }
+        Ok(query)
+    }
+
+    /// Whether any part of the query must be present, as opposed to only
+    /// excluding things. A purely negative query would match the whole archive.
+    fn has_positive(&self) -> bool {
+        match self {
+            Query::Term(_) | Query::Phrase(_) => true,
+            Query::And(parts) | Query::Or(parts) => parts.iter().any(Query::has_positive),
+            Query::Not(_) => false,
+        }
+    }
+
+    /// Terms and phrases a document is shown for, i.e. everything not negated.
+    fn positives<'a>(&'a self, out: &mut Vec<&'a [String]>) {
+        match self {
+            Query::Term(t) => out.push(std::slice::from_ref(t)),
+            Query::Phrase(words) => out.push(words),
+            Query::And(parts) | Query::Or(parts) => {
+                parts.iter().for_each(|p| p.positives(out));
+            }
+            Query::Not(_) => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Lexeme {
+    Word(String),
+    Phrase(Vec<String>),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+fn lex(input: &str) -> Result<Vec<Lexeme>> {
+    let mut out = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                out.push(Lexeme::Open);
+            }
+            ')' => {
+                chars.next();
+                out.push(Lexeme::Close);
+            }
+            '-' => {
+                chars.next();
+                out.push(Lexeme::Not);
+            }
+            '"' => {
+                chars.next();
+                let phrase: String = chars.by_ref().take_while(|&c| c != '"').collect();
+                let words = words_of(&phrase);
+                if words.is_empty() {
+                    return Err(PopoError::InvalidQuery("empty phrase in query".to_string()));
+                }
+                out.push(Lexeme::Phrase(words));
+            }
+            _ => {
+                let mut raw = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' || c == '"' {
+                        break;
+                    }
+                    raw.push(c);
+                    chars.next();
+                }
+                match raw.as_str() {
+                    "AND" => out.push(Lexeme::And),
+                    "OR" => out.push(Lexeme::Or),
+                    "NOT" => out.push(Lexeme::Not),
+                    _ => {
+                        // "km." or "12," fold to a single word; "Xalitzintla-Nexapa"
+                        // to two, which then must both appear.
+                        let words = words_of(&raw);
+                        match words.len() {
+                            0 => {}
+                            1 => out.push(Lexeme::Word(words.into_iter().next().unwrap())),
+                            _ => out.push(Lexeme::Phrase(words)),
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+fn words_of(text: &str) -> Vec<String> {
+    tokens(&fold(text)).into_iter().map(|t| t.text).collect()
+}
+
+struct Parser {
+    lexemes: Vec<Lexeme>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Lexeme> {
+        self.lexemes.get(self.pos)
+    }
+
+    fn or_expr(&mut self) -> Result<Query> {
+        let mut parts = vec![self.and_expr()?];
+        while self.peek() == Some(&Lexeme::Or) {
+            self.pos += 1;
+            parts.push(self.and_expr()?);
+        }
+        Ok(flatten(parts, Query::Or))
+    }
+
+    fn and_expr(&mut self) -> Result<Query> {
+        let mut parts = vec![self.unary()?];
+        loop {
+            match self.peek() {
+                Some(Lexeme::And) => {
+                    self.pos += 1;
+                    parts.push(self.unary()?);
+                }
+                Some(Lexeme::Or) | Some(Lexeme::Close) | None => break,
+                Some(_) => parts.push(self.unary()?),
+            }
+        }
+        Ok(flatten(parts, Query::And))
+    }
+
+    fn unary(&mut self) -> Result<Query> {
+        let lexeme = self.peek().cloned();
+        self.pos += 1;
+        match lexeme {
+            Some(Lexeme::Not) => Ok(Query::Not(Box::new(self.unary()?))),
+            Some(Lexeme::Word(w)) => Ok(Query::Term(w)),
+            Some(Lexeme::Phrase(words)) => Ok(Query::Phrase(words)),
+            Some(Lexeme::Open) => {
+                let inner = self.or_expr()?;
+                if self.peek() != Some(&Lexeme::Close) {
+                    return Err(PopoError::InvalidQuery("missing ')' in query".to_string()));
+                }
+                self.pos += 1;
+                Ok(inner)
+            }
+            Some(Lexeme::Close) => Err(PopoError::InvalidQuery(
+                "unexpected ')' in query".to_string(),
+            )),
+            Some(Lexeme::And) | Some(Lexeme::Or) => Err(PopoError::InvalidQuery(
+                "AND/OR needs a word on both sides".to_string(),
+            )),
+            None => Err(PopoError::InvalidQuery(
+                "query ends where a word was expected".to_string(),
+            )),
+        }
+    }
+}
+
+fn flatten(mut parts: Vec<Query>, combine: fn(Vec<Query>) -> Query) -> Query {
+    if parts.len() == 1 {
+        parts.pop().unwrap()
+    } else {
+        combine(parts)
+    }
+}
+
+/// A narrative excerpt around the matches.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Snippet {
+    pub text: String,
+    /// Matched spans within `text`, as character offsets `[start, end)`.
+    pub highlights: Vec<(usize, usize)>,
+}
+
+/// One matching report.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SearchHit {
+    pub date: NaiveDate,
+    /// How many times the query's terms and phrases occur in the narrative.
+    pub matches: usize,
+    pub snippet: Snippet,
+}
+
+impl Tabular for SearchHit {
+    type Row<'a> = &'a SearchHit;
+
+    const COLUMNS: &'static [&'static str] = &["date", "matches", "snippet.text"];
+    const RECORD: bool = true;
+
+    fn rows(&self) -> Vec<&SearchHit> {
+        vec![self]
+    }
+}
+
+/// A persistent inverted index over `summary_spanish`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchIndex {
+    version: u32,
+    /// Feed base the index was built from.
+    feed: String,
+    /// Every date already fetched, including those with nothing to index, so
+    /// a refresh never fetches them again.
+    seen: BTreeSet<NaiveDate>,
+    /// Original narratives, kept for snippets and phrase checks.
+    documents: BTreeMap<NaiveDate, String>,
+    /// Folded token to the dates it appears on.
+    postings: BTreeMap<String, BTreeSet<NaiveDate>>,
+    /// `ashfall_reports` of every full report; partial records are absent.
+    ashfall: BTreeMap<NaiveDate, Vec<String>>,
+}
+
+impl SearchIndex {
+    pub fn new(feed: impl Into<String>) -> Self {
+        Self {
+            version: INDEX_VERSION,
+            feed: feed.into(),
+            seen: BTreeSet::new(),
+            documents: BTreeMap::new(),
+            postings: BTreeMap::new(),
+            ashfall: BTreeMap::new(),
+        }
+    }
+
+    /// Where the index for a given feed lives, under `cache_dir` or the
+    /// platform's cache directory.
+    pub fn default_path(feed: &str, cache_dir: Option<&Path>) -> Option<PathBuf> {
+        crate::paths::cache_dir(cache_dir)
+            .map(|dir| dir.join(format!("search-{}.json", crate::paths::feed_slug(feed))))
+    }
+
+    /// Load a saved index, or start afresh if there is none, it was built from
+    /// a different feed, or it predates the current layout.
+    pub fn open(path: &Path, feed: &str) -> Result<Self> {
+        let body = match std::fs::read_to_string(path) {
+            Ok(body) => body,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::new(feed)),
+            Err(e) => {
+                return Err(PopoError::Cache {
+                    path: path.display().to_string(),
+                    source: e,
+                })
+            }
+        };
+        match serde_json::from_str::<SearchIndex>(&body) {
+            Ok(index) if index.version == INDEX_VERSION && index.feed == feed => Ok(index),
+            // A stale or corrupt cache is rebuilt, never an error.
+            _ => Ok(Self::new(feed)),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let cache_err = |source| PopoError::Cache {
+            path: path.display().to_string(),
+            source,
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(cache_err)?;
+        }
+        let json = serde_json::to_string(self).map_err(|e| PopoError::Serialize(e.to_string()))?;
+        // Write then rename, so an interrupted save leaves the old index intact.
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json).map_err(cache_err)?;
+        std::fs::rename(&tmp, path).map_err(cache_err)
+    }
+
+    /// Fetch and index every day the feed lists that this index has not seen.
+    /// Returns how many days were fetched.
+    pub fn refresh(&mut self, feed: &Feed) -> Result<usize> {
+        let index = feed.index()?;
+        let mut fetched = 0;
+        for date in index.dates {
+            if self.seen.contains(&date) {
+                continue;
+            }
+            match feed.get(date) {
+                Ok(report) => self.add(&report),
+                // Listed but not published yet; try again next refresh.
+                Err(PopoError::NotFound(_)) => continue,
+                Err(e) => return Err(e),
+            }
+            fetched += 1;
+        }
+        Ok(fetched)
+    }
+
+    /// Index a single report. Reports without a narrative are only marked
+    /// seen, and only full reports have their ashfall kept.
+    pub fn add(&mut self, report: &VolcanoReport) {
+        self.seen.insert(report.date);
+        if let Some(day) = AshfallDay::of(report) {
+            self.ashfall.insert(day.date, day.reports);
+        }
+        let Some(summary) = report.summary_spanish.as_deref().filter(|s| !s.is_empty()) else {
+            return;
+        };
+        for token in tokens(&fold(summary)) {
+            self.postings
+                .entry(token.text)
+                .or_default()
+                .insert(report.date);
+        }
+        self.documents.insert(report.date, summary.to_string());
+    }
+
+    /// Number of narratives indexed.
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    /// What every full report from `from` to `to`, inclusive, said about
+    /// ashfall, oldest first.
+    pub fn ashfall(&self, from: NaiveDate, to: NaiveDate) -> Vec<AshfallDay> {
+        if from > to {
+            return Vec::new();
+        }
+        self.ashfall
+            .range(from..=to)
+            .map(|(&date, reports)| AshfallDay {
+                date,
+                reports: reports.clone(),
+            })
+            .collect()
+    }
+
+    /// Matching reports, newest first.
+    pub fn search(&self, query: &Query) -> Vec<SearchHit> {
+        let mut positives = Vec::new();
+        query.positives(&mut positives);
+
+        self.evaluate(query)
+            .into_iter()
+            .rev()
+            .filter_map(|date| {
+                let text = self.documents.get(&date)?;
+                let spans = find_spans(text, &positives);
+                Some(SearchHit {
+                    date,
+                    matches: spans.len(),
+                    snippet: snippet(text, &spans),
+                })
+            })
+            .collect()
+    }
+
+    fn evaluate(&self, query: &Query) -> BTreeSet<NaiveDate> {
+        match query {
+            Query::Term(term) => self.dates_with_prefix(term),
+            Query::Phrase(words) => {
+                let mut candidates = self.dates_with_prefix(&words[0]);
+                for word in &words[1..] {
+                    let next = self.dates_with_prefix(word);
+                    candidates.retain(|d| next.contains(d));
+                }
+                candidates
+                    .into_iter()
+                    .filter(|d| !find_spans(&self.documents[d], &[words.as_slice()]).is_empty())
+                    .collect()
+            }
+            Query::And(parts) => {
+                let mut iter = parts.iter();
+                let first = iter.next().map(|q| self.evaluate(q)).unwrap_or_default();
+                iter.fold(first, |acc, q| {
+                    let next = self.evaluate(q);
+                    acc.intersection(&next).copied().collect()
+                })
+            }
+            Query::Or(parts) => parts.iter().flat_map(|q| self.evaluate(q)).collect(),
+            Query::Not(inner) => {
+                let excluded = self.evaluate(inner);
+                self.documents
+                    .keys()
+                    .filter(|d| !excluded.contains(d))
+                    .copied()
+                    .collect()
+            }
+        }
+    }
+
+    fn dates_with_prefix(&self, prefix: &str) -> BTreeSet<NaiveDate> {
+        self.postings
+            .range(prefix.to_string()..)
+            .take_while(|(token, _)| token.starts_with(prefix))
+            .flat_map(|(_, dates)| dates.iter().copied())
+            .collect()
+    }
+}
+
+/// Character spans in `text` where any of the word sequences occur, each word
+/// matching as a prefix of consecutive tokens. Sorted and non-overlapping.
+fn find_spans(text: &str, sequences: &[&[String]]) -> Vec<(usize, usize)> {
+    let toks = tokens(&fold(text));
+    let mut spans = Vec::new();
+
+    for i in 0..toks.len() {
+        let longest = sequences
+            .iter()
+            .filter(|seq| {
+                i + seq.len() <= toks.len()
+                    && seq
+                        .iter()
+                        .zip(&toks[i..])
+                        .all(|(word, tok)| tok.text.starts_with(word.as_str()))
+            })
+            .map(|seq| seq.len())
+            .max();
+        if let Some(len) = longest {
+            let span = (toks[i].start, toks[i + len - 1].end);
+            if spans.last().map_or(true, |&(_, end)| span.0 >= end) {
+                spans.push(span);
+            }
+        }
+    }
+
+    spans
+}
+
+fn snippet(text: &str, spans: &[(usize, usize)]) -> Snippet {
+    let chars: Vec<char> = text.chars().collect();
+    let anchor = spans.first().map_or(0, |&(start, _)| start);
+
+    let mut start = anchor.saturating_sub(SNIPPET_CONTEXT);
+    let mut end = (anchor + SNIPPET_CONTEXT * 2).min(chars.len());
+    // Pull both edges in to whole words.
+    if start > 0 {
+        while start < anchor && !chars[start - 1].is_whitespace() {
+            start += 1;
+        }
+    }
+    if end < chars.len() {
+        while end > anchor && !chars[end].is_whitespace() {
+            end -= 1;
+        }
+    }
+
+    let prefix = if start > 0 { "…" } else { "" };
+    let suffix = if end < chars.len() { "…" } else { "" };
+    let offset = prefix.chars().count();
+
+    let body: String = chars[start..end]
+        .iter()
+        .map(|&c| if c.is_whitespace() { ' ' } else { c })
+        .collect();
+
+    Snippet {
+        text: format!("{}{}{}", prefix, body, suffix),
+        highlights: spans
+            .iter()
+            .filter(|&&(s, e)| s >= start && e <= end)
+            .map(|&(s, e)| (s - start + offset, e - start + offset))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index_of(docs: &[(&str, &str)]) -> SearchIndex {
+        let mut index = SearchIndex::new("test");
+        for (date, text) in docs {
+            index.add(&VolcanoReport {
+                summary_spanish: Some(text.to_string()),
+                ..VolcanoReport::blank(date)
+            });
+        }
+        index
+    }
+
+    fn dates(hits: &[SearchHit]) -> Vec<String> {
+        hits.iter().map(|h| h.date.to_string()).collect()
+    }
+
+    fn sample() -> SearchIndex {
+        index_of(&[
+            (
+                "2023-01-01",
+                "Podría esperarse la ocurrencia de lahares en las cañadas.",
+            ),
+            ("2023-01-02", "Se registró un LAHAR pequeño."),
+            ("2023-01-03", "Ligera caída de ceniza en Amecameca."),
+            ("2023-01-04", "Caída de ceniza y un láhar al sur."),
+        ])
+    }
+
+    #[test]
+    fn matches_ignore_accents_case_and_inflection() {
+        let index = sample();
+        for query in ["lahar", "LAHAR", "láhar"] {
+            let hits = index.search(&Query::parse(query).unwrap());
+            assert_eq!(
+                dates(&hits),
+                vec!["2023-01-04", "2023-01-02", "2023-01-01"],
+                "query {query}"
+            );
+        }
+    }
+
+    #[test]
+    fn boolean_operators() {
+        let index = sample();
+        let search = |q: &str| dates(&index.search(&Query::parse(q).unwrap()));
+
+        assert_eq!(search("lahar ceniza"), vec!["2023-01-04"]);
+        assert_eq!(search("lahar AND ceniza"), vec!["2023-01-04"]);
+        assert_eq!(
+            search("amecameca OR pequeño"),
+            vec!["2023-01-03", "2023-01-02"]
+        );
+        assert_eq!(search("ceniza NOT lahar"), vec!["2023-01-03"]);
+        assert_eq!(search("ceniza -lahar"), vec!["2023-01-03"]);
+        assert_eq!(search("(amecameca OR pequeño) lahar"), vec!["2023-01-02"]);
+    }
+
+    #[test]
+    fn phrases_must_be_contiguous() {
+        let index = sample();
+        let search = |q: &str| dates(&index.search(&Query::parse(q).unwrap()));
+
+        assert_eq!(
+            search("\"caida de ceniza\""),
+            vec!["2023-01-04", "2023-01-03"]
+        );
+        assert!(search("\"ceniza de caida\"").is_empty());
+    }
+
+    #[test]
+    fn snippet_highlights_original_wording() {
+        let index = sample();
+        let hits = index.search(&Query::parse("lahar").unwrap());
+        let hit = hits
+            .iter()
+            .find(|h| h.date.to_string() == "2023-01-04")
+            .unwrap();
+
+        let (start, end) = hit.snippet.highlights[0];
+        let highlighted: String = hit
+            .snippet
+            .text
+            .chars()
+            .skip(start)
+            .take(end - start)
+            .collect();
+        assert_eq!(highlighted, "láhar");
+        assert_eq!(hit.matches, 1);
+    }
+
+    #[test]
+    fn long_narratives_are_trimmed_around_the_match() {
+        let filler = "palabra ".repeat(60);
+        let text = format!("{filler}explosión moderada {filler}");
+        let index = index_of(&[("2023-05-20", &text)]);
+        let hit = &index.search(&Query::parse("explosion").unwrap())[0];
+
+        assert!(hit.snippet.text.starts_with('…'));
+        assert!(hit.snippet.text.ends_with('…'));
+        assert!(hit.snippet.text.chars().count() < text.chars().count());
+        let (start, end) = hit.snippet.highlights[0];
+        let highlighted: String = hit
+            .snippet
+            .text
+            .chars()
+            .skip(start)
+            .take(end - start)
+            .collect();
+        assert_eq!(highlighted, "explosión");
+    }
+
+    #[test]
+    fn rejects_malformed_queries() {
+        for bad in [
+            "",
+            "   ",
+            "(lahar",
+            "lahar)",
+            "NOT lahar",
+            "lahar OR",
+            "\"\"",
+        ] {
+            assert!(
+                matches!(Query::parse(bad), Err(PopoError::InvalidQuery(_))),
+                "query {bad:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn lowercase_operators_are_ordinary_words() {
+        assert_eq!(
+            Query::parse("no o").unwrap(),
+            Query::And(vec![Query::Term("no".into()), Query::Term("o".into())])
+        );
+    }
+
+    #[test]
+    fn index_round_trips_through_disk() {
+        let dir = std::env::temp_dir().join("popo-search-test-roundtrip");
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("index.json");
+
+        let index = sample();
+        index.save(&path).unwrap();
+        let reopened = SearchIndex::open(&path, "test").unwrap();
+        assert_eq!(reopened.len(), 4);
+        assert_eq!(
+            reopened.search(&Query::parse("amecameca").unwrap()).len(),
+            1
+        );
+
+        // An index built from another feed is not reused.
+        assert!(SearchIndex::open(&path, "elsewhere").unwrap().is_empty());
+    }
+
+    #[test]
+    fn keeps_the_ashfall_of_full_reports_only() {
+        let mut index = SearchIndex::new("test");
+        let mut partial = VolcanoReport::blank("2023-05-19");
+        partial.partial = true;
+        index.add(&partial);
+        let mut full = VolcanoReport::blank("2023-05-20");
+        full.ashfall_reports = vec!["Ozumba, Estado de México".into()];
+        index.add(&full);
+        index.add(&VolcanoReport::blank("2023-05-21"));
+
+        let date = |s: &str| s.parse::<NaiveDate>().unwrap();
+        let days = index.ashfall(date("2023-05-01"), date("2023-05-20"));
+        assert_eq!(days.len(), 1);
+        assert_eq!(days[0].reports, ["Ozumba, Estado de México"]);
+        assert_eq!(
+            index.ashfall(date("2023-05-19"), date("2023-05-31")).len(),
+            2
+        );
+    }
+}
@@ -0,0 +1,396 @@
+//! How the human-readable views are drawn.
+//!
+//! Three styles. `rich` is the default on a terminal: box-drawing banners,
+//! emoji, and the alert level in its colour. `plain` keeps the layout but
+//! draws it in ASCII with no emoji or colour, for Windows consoles and log
+//! files, and is the default when stdout is not a terminal. `accessible` is
+//! linear text for screen readers: no decoration or column padding, one line
+//! per fact, and paragraphs left unwrapped so nothing breaks mid-sentence.
+//!
+//! Colour also needs a terminal, and is off whenever `NO_COLOR` is set
+//! (<https://no-color.org>) or `TERM` is `dumb`.
+
+use crate::models::{AlertLevel, WindDirection};
+use std::fmt;
+use std::io::IsTerminal;
+use std::str::FromStr;
+
+/// Environment variable choosing the style.
+pub const STYLE_ENV: &str = "POPO_STYLE";
+
+/// Width assumed when stdout is not a terminal, which is what the fixed
+/// layout was drawn for.
+pub const DEFAULT_WIDTH: usize = 65;
+
+/// Columns the label of a field is padded to.
+const LABEL_WIDTH: usize = 27;
+const SUBLABEL_WIDTH: usize = 23;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Style {
+    #[default]
+    Rich,
+    Plain,
+    Accessible,
+}
+
+impl Style {
+    pub const ALL: [Style; 3] = [Style::Rich, Style::Plain, Style::Accessible];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Style::Rich => "rich",
+            Style::Plain => "plain",
+            Style::Accessible => "accessible",
+        }
+    }
+}
+
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Style {
+    type Err = String;
+
+    fn from_str(text: &str) -> std::result::Result<Self, String> {
+        let text = text.trim().to_lowercase();
+        Style::ALL
+            .into_iter()
+            .find(|s| s.name() == text)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Style::ALL.iter().map(|s| s.name()).collect();
+                format!("unknown style '{}'; use {}", text, names.join(", "))
+            })
+    }
+}
+
+/// A style, whether to colour, and how wide the terminal is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Renderer {
+    pub style: Style,
+    pub colour: bool,
+    pub width: usize,
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Renderer {
+            style: Style::Rich,
+            colour: false,
+            width: DEFAULT_WIDTH,
+        }
+    }
+}
+
+impl Renderer {
+    /// The `--style` flag if given, then the configured `style` (a config
+    /// file or `POPO_STYLE`), then `rich` on a terminal and `plain` elsewhere.
+    pub fn detect(flag: Option<Style>, configured: Option<&str>) -> Self {
+        let var = |name| std::env::var(name).ok().filter(|v: &String| !v.is_empty());
+        let tty = std::io::stdout().is_terminal();
+        let style = flag
+            .or_else(|| configured.and_then(|s| s.parse().ok()))
+            .unwrap_or(if tty { Style::Rich } else { Style::Plain });
+        let colour = style == Style::Rich
+            && tty
+            && var("NO_COLOR").is_none()
+            && var("TERM").as_deref() != Some("dumb");
+        let width = terminal_size::terminal_size_of(std::io::stdout())
+            .map(|(w, _)| w.0 as usize)
+            .or_else(|| var("COLUMNS").and_then(|c| c.parse().ok()))
+            .unwrap_or(DEFAULT_WIDTH);
+        Renderer {
+            style,
+            colour,
+            width,
+        }
+    }
+
+    /// A title set apart from what follows.
+    pub fn banner(&self, title: &str) -> String {
+        match self.style {
+            Style::Rich => format!("╔{rule}╗\n║{:^63}║\n╚{rule}╝", title, rule = "═".repeat(63)),
+            Style::Plain => format!("+{rule}+\n|{:^63}|\n+{rule}+", title, rule = "-".repeat(63)),
+            Style::Accessible => title.to_string(),
+        }
+    }
+
+    /// The heading of a section within a view.
+    pub fn section(&self, title: &str) -> String {
+        match self.style {
+            Style::Rich => format!("{rule}\n  {}\n{rule}", title, rule = "━".repeat(63)),
+            Style::Plain => format!("{rule}\n  {}\n{rule}", title, rule = "=".repeat(63)),
+            Style::Accessible => title.to_string(),
+        }
+    }
+
+    /// `label: value`, lined up with the other fields when there is a
+    /// layout to line up.
+    pub fn field(&self, icon: &str, label: &str, value: &str) -> String {
+        let label = format!("{}:", label);
+        match self.style {
+            Style::Rich => format!("  {}{:<w$}{}", icon, label, value, w = LABEL_WIDTH),
+            Style::Plain => format!("  {:<w$}{}", label, value, w = LABEL_WIDTH + 3),
+            Style::Accessible => format!("{} {}", label, value),
+        }
+    }
+
+    /// A field that breaks down the one above it.
+    pub fn subfield(&self, label: &str, value: &str) -> String {
+        let label = format!("{}:", label);
+        match self.style {
+            Style::Rich => format!("     └─ {:<w$}{}", label, value, w = SUBLABEL_WIDTH),
+            Style::Plain => format!("       - {:<w$}{}", label, value, w = SUBLABEL_WIDTH),
+            Style::Accessible => format!("{} {}", label, value),
+        }
+    }
+
+    /// A line of its own, led by `icon` in the rich style.
+    pub fn line(&self, icon: &str, text: &str) -> String {
+        match self.style {
+            Style::Rich => format!("  {}{}", icon, text),
+            Style::Plain => format!("  {}", text),
+            Style::Accessible => text.to_string(),
+        }
+    }
+
+    /// A line at the left margin, such as the date under a banner.
+    pub fn lead(&self, icon: &str, text: &str) -> String {
+        match self.style {
+            Style::Rich => format!("{}{}", icon, text),
+            Style::Plain | Style::Accessible => text.to_string(),
+        }
+    }
+
+    /// A line belonging to the one above it, such as a URL in a list.
+    pub fn item(&self, text: &str) -> String {
+        match self.style {
+            Style::Rich | Style::Plain => format!("     {}", text),
+            Style::Accessible => text.to_string(),
+        }
+    }
+
+    /// `text` wrapped to the terminal and indented by two columns.
+    pub fn paragraph(&self, text: &str) -> Vec<String> {
+        self.wrapped("  ", "  ", text)
+    }
+
+    /// `text` wrapped to the terminal and indented by `indent` columns.
+    pub fn indented(&self, text: &str, indent: usize) -> Vec<String> {
+        let lead = " ".repeat(indent);
+        self.wrapped(&lead, &lead, text)
+    }
+
+    /// A note led by `icon`, with continuation lines indented under its text.
+    pub fn note(&self, icon: &str, text: &str) -> Vec<String> {
+        match self.style {
+            Style::Rich => self.wrapped(&format!("  {}", icon), "      ", text),
+            Style::Plain | Style::Accessible => self.paragraph(text),
+        }
+    }
+
+    /// One bullet, wrapped with its continuation lines under the text.
+    pub fn bullet(&self, text: &str) -> Vec<String> {
+        match self.style {
+            Style::Rich => self.wrapped("  • ", "    ", text),
+            Style::Plain | Style::Accessible => self.wrapped("  - ", "    ", text),
+        }
+    }
+
+    /// Lines of `text`, the first led by `first` and the rest by `rest`.
+    /// The accessible style leaves the text whole and unindented.
+    fn wrapped(&self, first: &str, rest: &str, text: &str) -> Vec<String> {
+        if self.style == Style::Accessible {
+            let lead = if first.trim().is_empty() {
+                ""
+            } else {
+                first.trim_start()
+            };
+            return vec![format!("{}{}", lead, text)];
+        }
+        let width = self.wrap_width(rest.chars().count());
+        wrap(text, width)
+            .into_iter()
+            .enumerate()
+            .map(|(i, line)| format!("{}{}", if i == 0 { first } else { rest }, line))
+            .collect()
+    }
+
+    /// Columns left for text after `indent`, on the terminal in use.
+    pub fn wrap_width(&self, indent: usize) -> usize {
+        self.width.saturating_sub(indent).max(20)
+    }
+
+    /// `text` in the colour of `level`, when colour is on.
+    pub fn level(&self, level: AlertLevel, text: &str) -> String {
+        if !self.colour {
+            return text.to_string();
+        }
+        let code = match level {
+            AlertLevel::Green => "1;32",
+            AlertLevel::Yellow => "1;33",
+            AlertLevel::Orange => "1;38;5;208",
+            AlertLevel::Red => "1;31",
+        };
+        format!("\x1b[{}m{}\x1b[0m", code, text)
+    }
+
+    /// Markers around a search match: bold yellow when colouring, brackets
+    /// otherwise.
+    pub fn emphasis(&self) -> (&'static str, &'static str) {
+        if self.colour {
+            ("\x1b[1;33m", "\x1b[0m")
+        } else {
+            ("[", "]")
+        }
+    }
+
+    /// The level's coloured dot, followed by a space, in the rich style.
+    pub fn level_icon(&self, level: AlertLevel) -> String {
+        match self.style {
+            Style::Rich => format!("{} ", level.emoji()),
+            Style::Plain | Style::Accessible => String::new(),
+        }
+    }
+
+    /// The arrow the plume travels along, followed by a space, in the rich
+    /// style.
+    pub fn arrow(&self, dir: WindDirection) -> String {
+        match self.style {
+            Style::Rich => format!("{} ", dir.arrow()),
+            Style::Plain | Style::Accessible => String::new(),
+        }
+    }
+}
+
+/// Wrap on whitespace at `width` columns, counting characters rather than
+/// bytes so accented Spanish text does not wrap short.
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut current_line = String::new();
+    let mut current_len = 0;
+
+    for word in text.split_whitespace() {
+        let word_len = word.chars().count();
+        if current_line.is_empty() {
+            current_line = word.to_string();
+            current_len = word_len;
+        } else if current_len + word_len < width {
+            current_line.push(' ');
+            current_line.push_str(word);
+            current_len += word_len + 1;
+        } else {
+            result.push(std::mem::take(&mut current_line));
+            current_line = word.to_string();
+            current_len = word_len;
+        }
+    }
+
+    if !current_line.is_empty() {
+        result.push(current_line);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn renderer(style: Style) -> Renderer {
+        Renderer {
+            style,
+            ..Renderer::default()
+        }
+    }
+
+    #[test]
+    fn plain_output_is_ascii_apart_from_the_text() {
+        let plain = renderer(Style::Plain);
+        let drawn = [
+            plain.banner("REPORT"),
+            plain.section("SEISMIC ACTIVITY"),
+            plain.field("💨 ", "Exhalations", "6"),
+            plain.subfield("Harmonic", "10 minutes"),
+            plain.line("🔗 ", "https://example.org"),
+            plain.level_icon(AlertLevel::Red),
+            plain.arrow(WindDirection::W),
+        ];
+        for text in drawn {
+            assert!(text.is_ascii(), "{}", text);
+        }
+        assert_eq!(
+            plain.field("💨 ", "Exhalations", "6").trim_end(),
+            "  Exhalations:                  6"
+        );
+    }
+
+    #[test]
+    fn accessible_output_is_linear() {
+        let accessible = renderer(Style::Accessible);
+        assert_eq!(accessible.banner("REPORT"), "REPORT");
+        assert_eq!(
+            accessible.field("💨 ", "Exhalations", "6"),
+            "Exhalations: 6"
+        );
+        assert_eq!(
+            accessible.subfield("Harmonic", "10 minutes"),
+            "Harmonic: 10 minutes"
+        );
+        let long = "palabra ".repeat(40);
+        assert_eq!(accessible.paragraph(&long).len(), 1);
+        assert_eq!(
+            accessible.bullet("Evitar el paso"),
+            vec!["- Evitar el paso"]
+        );
+    }
+
+    #[test]
+    fn wrapping_follows_the_terminal_width() {
+        let text = "uno dos tres cuatro cinco seis siete ocho nueve diez";
+        let narrow = Renderer {
+            width: 20,
+            ..Renderer::default()
+        };
+        let wide = Renderer {
+            width: 200,
+            ..Renderer::default()
+        };
+        assert!(narrow.paragraph(text).len() > 1);
+        assert_eq!(wide.paragraph(text), vec![format!("  {}", text)]);
+        assert_eq!(narrow.bullet(text)[1].find(|c| c != ' '), Some(4));
+    }
+
+    #[test]
+    fn colour_only_when_enabled() {
+        let mut rich = renderer(Style::Rich);
+        assert_eq!(rich.level(AlertLevel::Yellow, "YELLOW"), "YELLOW");
+        rich.colour = true;
+        assert_eq!(
+            rich.level(AlertLevel::Yellow, "YELLOW"),
+            "\x1b[1;33mYELLOW\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn wraps_on_word_boundaries() {
+        let lines = wrap("uno dos tres cuatro cinco", 9);
+        assert_eq!(lines, vec!["uno dos", "tres", "cuatro", "cinco"]);
+    }
+
+    /// Accented text must wrap by characters, not bytes.
+    #[test]
+    fn wraps_accented_text_by_chars() {
+        let lines = wrap("ceniza volcánica ácida", 17);
+        assert_eq!(lines, vec!["ceniza volcánica", "ácida"]);
+    }
+
+    #[test]
+    fn styles_parse_by_name() {
+        assert_eq!("Plain".parse::<Style>(), Ok(Style::Plain));
+        assert!("fancy".parse::<Style>().is_err());
+    }
+}
@@ -0,0 +1,294 @@
+//! User templates for custom bulletins.
+//!
+//! A template is a [Tera](https://keats.github.io/tera/) file rendered with
+//! one report as `report`, or a range of them as `reports` with `from` and
+//! `to`. Either way `lang` holds the language code. The fields are those of
+//! the JSON feed, so `{{ report.explosions }}` is a number or null.
+//!
+//! A few filters do what the built-in views do:
+//!
+//! - `counter`: the value, or "not reported" when it is null
+//! - `alert_emoji` and `alert_name`: 🟡 and "Yellow" for `report.alert_level`
+//! - `wind_arrow` and `wind_name`: ← and "West" for `report.wind_direction`
+//! - `date`: "Tuesday, 22 March 2022", or `date(format="%d/%m/%Y")` for a
+//!   strftime pattern
+//!
+//! Names and dates follow `--lang`, and the "not reported" of `counter` too.
+
+use crate::error::{PopoError, Result};
+use crate::i18n::Lang;
+use crate::models::{AlertLevel, VolcanoReport, WindDirection};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use tera::{Context, Tera};
+
+pub struct Template {
+    tera: Tera,
+    name: String,
+    lang: Lang,
+}
+
+impl Template {
+    /// The template file at `path`.
+    pub fn load(path: &Path, lang: Lang) -> Result<Self> {
+        let text = std::fs::read_to_string(path).map_err(|e| {
+            PopoError::Template(format!("failed to read {}: {}", path.display(), e))
+        })?;
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+        Self::parse(&name, &text, lang)
+    }
+
+    /// A template from its text. A `name` ending in `.html` or `.xml` turns on
+    /// escaping, as Tera does for files.
+    pub fn parse(name: &str, text: &str, lang: Lang) -> Result<Self> {
+        let mut tera = Tera::default();
+        tera.add_raw_template(name, text).map_err(describe)?;
+        register_filters(&mut tera, lang);
+        Ok(Template {
+            tera,
+            name: name.to_string(),
+            lang,
+        })
+    }
+
+    pub fn render_report(&self, report: &VolcanoReport) -> Result<String> {
+        let mut context = Context::new();
+        context.insert("report", report);
+        self.render(context)
+    }
+
+    /// `reports`, oldest first, with the first and last dates they cover.
+    pub fn render_range(&self, reports: &[VolcanoReport]) -> Result<String> {
+        let mut context = Context::new();
+        context.insert("reports", reports);
+        context.insert("from", &reports.first().map(|r| r.date));
+        context.insert("to", &reports.last().map(|r| r.date));
+        self.render(context)
+    }
+
+    fn render(&self, mut context: Context) -> Result<String> {
+        context.insert("lang", self.lang.code());
+        self.tera.render(&self.name, &context).map_err(describe)
+    }
+}
+
+/// The file `spec` refers to: a template named in the config, or else a path.
+pub fn locate(spec: &str, named: &BTreeMap<String, PathBuf>) -> Result<PathBuf> {
+    if let Some(path) = named.get(spec) {
+        return Ok(path.clone());
+    }
+    let path = PathBuf::from(spec);
+    if path.is_file() {
+        return Ok(path);
+    }
+    let names: Vec<&str> = named.keys().map(String::as_str).collect();
+    Err(PopoError::Template(match names.is_empty() {
+        true => format!("no template file at {}", spec),
+        false => format!(
+            "no template file at {} and no template of that name; named templates are {}",
+            spec,
+            names.join(", ")
+        ),
+    }))
+}
+
+fn register_filters(tera: &mut Tera, lang: Lang) {
+    let not_reported = lang.messages().not_reported;
+    tera.register_filter(
+        "counter",
+        move |value: &Value, _: &HashMap<String, Value>| {
+            Ok(match value {
+                Value::Null => Value::from(not_reported),
+                other => other.clone(),
+            })
+        },
+    );
+    tera.register_filter(
+        "alert_emoji",
+        |value: &Value, _: &HashMap<String, Value>| {
+            let level: Option<AlertLevel> = typed(value, "alert_emoji")?;
+            Ok(Value::from(level.map_or("", AlertLevel::emoji)))
+        },
+    );
+    tera.register_filter(
+        "alert_name",
+        move |value: &Value, _: &HashMap<String, Value>| {
+            let level: Option<AlertLevel> = typed(value, "alert_name")?;
+            Ok(Value::from(
+                level.map_or(not_reported, |level| lang.alert_level(level)),
+            ))
+        },
+    );
+    tera.register_filter("wind_arrow", |value: &Value, _: &HashMap<String, Value>| {
+        let dir: Option<WindDirection> = typed(value, "wind_arrow")?;
+        Ok(Value::from(
+            dir.map_or(String::new(), |dir| dir.arrow().to_string()),
+        ))
+    });
+    tera.register_filter(
+        "wind_name",
+        move |value: &Value, _: &HashMap<String, Value>| {
+            let dir: Option<WindDirection> = typed(value, "wind_name")?;
+            Ok(Value::from(
+                dir.map_or(not_reported, |dir| lang.wind_direction(dir)),
+            ))
+        },
+    );
+    tera.register_filter(
+        "date",
+        move |value: &Value, args: &HashMap<String, Value>| {
+            let Some(text) = value.as_str() else {
+                return match value {
+                    Value::Null => Ok(Value::from(not_reported)),
+                    _ => Err(tera::Error::msg(format!(
+                        "filter `date` expects a date, got {}",
+                        value
+                    ))),
+                };
+            };
+            let (date, time) = match text.parse::<DateTime<Utc>>() {
+                Ok(time) => (time.date_naive(), Some(time)),
+                Err(_) => match text.parse::<NaiveDate>() {
+                    Ok(date) => (date, None),
+                    Err(_) => {
+                        return Err(tera::Error::msg(format!(
+                            "filter `date` expects a date, got '{}'",
+                            text
+                        )))
+                    }
+                },
+            };
+            let Some(format) = args.get("format") else {
+                return Ok(Value::from(lang.date(date)));
+            };
+            let format = format
+                .as_str()
+                .ok_or_else(|| tera::Error::msg("filter `date` expects `format` to be a string"))?;
+            // An unknown specifier would make chrono panic while formatting.
+            if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+                return Err(tera::Error::msg(format!(
+                    "filter `date`: '{}' is not a valid strftime format",
+                    format
+                )));
+            }
+            Ok(Value::from(match time {
+                Some(time) => time.format(format).to_string(),
+                None => date.format(format).to_string(),
+            }))
+        },
+    );
+}
+
+/// A filter's input as the feed type it serialises, with null as `None`.
+fn typed<T: DeserializeOwned>(value: &Value, filter: &str) -> tera::Result<Option<T>> {
+    serde_json::from_value(value.clone())
+        .map_err(|_| tera::Error::msg(format!("filter `{}` does not understand {}", filter, value)))
+}
+
+/// Tera nests the cause of an error, such as the line that failed to parse,
+/// below a generic message, so join the whole chain.
+fn describe(error: tera::Error) -> PopoError {
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(&error);
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(cause.to_string().trim_end());
+        source = cause.source();
+    }
+    PopoError::Template(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> VolcanoReport {
+        serde_json::from_value(serde_json::json!({
+            "date": "2022-03-22",
+            "exhalations": 32,
+            "explosions": null,
+            "alert_level": "YELLOW",
+            "wind_direction": "W",
+            "ingested_at": "2022-03-22T15:04:00Z"
+        }))
+        .unwrap()
+    }
+
+    fn render(text: &str, lang: Lang) -> Result<String> {
+        Template::parse("test.tera", text, lang)?.render_report(&report())
+    }
+
+    #[test]
+    fn filters_match_the_built_in_views() {
+        let text = "{{ report.alert_level | alert_emoji }} {{ report.alert_level | alert_name }} \
+                    {{ report.wind_direction | wind_arrow }} {{ report.wind_direction | wind_name }} \
+                    {{ report.exhalations | counter }}/{{ report.explosions | counter }}";
+        assert_eq!(
+            render(text, Lang::En).unwrap(),
+            "🟡 Yellow ← West 32/not reported"
+        );
+        assert_eq!(
+            render(text, Lang::Es).unwrap(),
+            "🟡 Amarillo ← Oeste 32/sin dato"
+        );
+    }
+
+    #[test]
+    fn dates_are_localised_or_formatted() {
+        assert_eq!(
+            render("{{ report.date | date }}", Lang::Es).unwrap(),
+            "martes 22 de marzo de 2022"
+        );
+        assert_eq!(
+            render(
+                "{{ report.date | date(format=\"%d/%m/%Y\") }} {{ report.ingested_at | date(format=\"%H:%M\") }}",
+                Lang::En
+            )
+            .unwrap(),
+            "22/03/2022 15:04"
+        );
+        assert!(render("{{ report.date | date(format=\"%Q\") }}", Lang::En).is_err());
+    }
+
+    #[test]
+    fn ranges_get_every_report_and_their_span() {
+        let mut later = report();
+        later.date = NaiveDate::from_ymd_opt(2022, 3, 23).unwrap();
+        let template = Template::parse(
+            "range.tera",
+            "{{ from }} to {{ to }}:{% for r in reports %} {{ r.exhalations }}{% endfor %}",
+            Lang::En,
+        )
+        .unwrap();
+        assert_eq!(
+            template.render_range(&[report(), later]).unwrap(),
+            "2022-03-22 to 2022-03-23: 32 32"
+        );
+    }
+
+    #[test]
+    fn errors_say_what_went_wrong() {
+        let err = render("{{ report.date | nonsense }}", Lang::En).unwrap_err();
+        assert!(err.to_string().contains("nonsense"), "{}", err);
+        let err = render("{% if %}", Lang::En).unwrap_err();
+        assert!(err.to_string().contains("test.tera"), "{}", err);
+    }
+
+    #[test]
+    fn names_from_the_config_come_before_paths() {
+        let named = BTreeMap::from([("bulletin".to_string(), PathBuf::from("/srv/b.tera"))]);
+        assert_eq!(
+            locate("bulletin", &named).unwrap(),
+            PathBuf::from("/srv/b.tera")
+        );
+        let err = locate("weekly", &named).unwrap_err();
+        assert!(err.to_string().contains("bulletin"), "{}", err);
+    }
+}
@@ -0,0 +1,125 @@
+//! Helpers for matching Spanish text typed by people who may not bother with
+//! accents, against text published by people who sometimes don't either.
+
+/// Lowercase and strip diacritics, one character in, one character out.
+///
+/// Keeping the mapping 1:1 means a character offset into the folded text is
+/// also an offset into the original, which is what lets callers highlight the
+/// original wording after matching on the folded form.
+pub fn fold(text: &str) -> String {
+    text.chars().map(fold_char).collect()
+}
+
+fn fold_char(c: char) -> char {
+    // Some uppercase letters lowercase to more than one character. Taking the
+    // first keeps offsets aligned, and none of them occur in CENAPRED text.
+    let c = c.to_lowercase().next().unwrap_or(c);
+    match c {
+        'á' | 'à' | 'ä' | 'â' | 'ã' => 'a',
+        'é' | 'è' | 'ë' | 'ê' => 'e',
+        'í' | 'ì' | 'ï' | 'î' => 'i',
+        'ó' | 'ò' | 'ö' | 'ô' | 'õ' => 'o',
+        'ú' | 'ù' | 'ü' | 'û' => 'u',
+        'ñ' => 'n',
+        'ç' => 'c',
+        _ => c,
+    }
+}
+
+/// A word in folded text, with its position in characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub text: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Split folded text into alphanumeric runs.
+pub fn tokens(folded: &str) -> Vec<Token> {
+    let mut out = Vec::new();
+    let mut current = String::new();
+    let mut start = 0;
+
+    for (i, c) in folded.chars().enumerate() {
+        if c.is_alphanumeric() {
+            if current.is_empty() {
+                start = i;
+            }
+            current.push(c);
+        } else if !current.is_empty() {
+            out.push(Token {
+                text: std::mem::take(&mut current),
+                start,
+                end: i,
+            });
+        }
+    }
+    if !current.is_empty() {
+        let end = start + current.chars().count();
+        out.push(Token {
+            text: current,
+            start,
+            end,
+        });
+    }
+
+    out
+}
+
+/// Split into sentences with their byte offsets. A period ends a sentence
+/// only when followed by whitespace, so "magnitud de 1.3" stays whole.
+pub fn sentences(text: &str) -> Vec<(usize, &str)> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    let bytes = text.as_bytes();
+    for (i, &b) in bytes.iter().enumerate() {
+        if b == b'.' && bytes.get(i + 1).map_or(true, |n| n.is_ascii_whitespace()) {
+            let sentence = &text[start..=i];
+            if !sentence.trim().is_empty() {
+                let lead = sentence.len() - sentence.trim_start().len();
+                out.push((start + lead, sentence.trim()));
+            }
+            start = i + 1;
+        }
+    }
+    let rest = &text[start..];
+    if !rest.trim().is_empty() {
+        let lead = rest.len() - rest.trim_start().len();
+        out.push((start + lead, rest.trim()));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folds_accents_and_case() {
+        assert_eq!(fold("LÁHAR Tétela Ñ"), "lahar tetela n");
+    }
+
+    #[test]
+    fn fold_preserves_character_count() {
+        let original = "Expulsión de fragmentos incandescentes, cañadas";
+        assert_eq!(fold(original).chars().count(), original.chars().count());
+    }
+
+    #[test]
+    fn sentences_keep_decimals_whole() {
+        let text = "Un sismo con magnitud de 1.3. Otra frase.";
+        assert_eq!(
+            sentences(text),
+            vec![(0, "Un sismo con magnitud de 1.3."), (30, "Otra frase.")]
+        );
+    }
+
+    #[test]
+    fn tokens_carry_character_offsets() {
+        let toks = tokens(&fold("Caída de ceniza, 12 km"));
+        let words: Vec<_> = toks.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(words, vec!["caida", "de", "ceniza", "12", "km"]);
+        assert_eq!((toks[2].start, toks[2].end), (9, 15));
+        assert_eq!((toks[4].start, toks[4].end), (20, 22));
+    }
+}
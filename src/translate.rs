@@ -0,0 +1,1107 @@
+//! Offline English rendering of `summary_spanish`.
+//!
+//! CENAPRED writes its reports from a small set of templates: the same
+//! scenario and recommendation bullets every day, and observations assembled
+//! from a few dozen recurring clauses ("Se detectaron 45 exhalaciones,
+//! acompañadas de vapor de agua…"). Rendering is a greedy longest-match over a
+//! fixed glossary of those clauses, falling back to single words. It is not
+//! translation, and the output says so: any run of words the glossary does not
+//! know is kept in Spanish as `[es: …]`, and a sentence that is mostly unknown
+//! is kept whole rather than rendered as word salad.
+
+use crate::gazetteer::{self, State, MUNICIPALITIES};
+use crate::models::WindDirection;
+use crate::narrative::Narrative;
+use crate::text::{fold, sentences};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Shown alongside every rendering.
+pub const NOTICE: &str = "Machine-rendered from the Spanish original with a fixed glossary. \
+Passages marked [es: …] were left untranslated. The Spanish text is authoritative.";
+
+/// Text rendered into English, with a count of the fragments left in Spanish.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Rendered {
+    pub text: String,
+    pub untranslated: usize,
+}
+
+impl Rendered {
+    /// Whether every fragment was rendered.
+    pub fn is_complete(&self) -> bool {
+        self.untranslated == 0
+    }
+}
+
+/// A [`Narrative`] rendered into English, section by section.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct EnglishNarrative {
+    pub headline: Option<String>,
+    pub observations: Vec<String>,
+    pub scenarios: Vec<String>,
+    pub recommendations: Vec<String>,
+    pub disclaimer: Option<String>,
+    /// Fragments left in Spanish across all sections.
+    pub untranslated: usize,
+}
+
+impl Narrative {
+    /// Render every section into English. See the module docs for how, and
+    /// for what the result can and cannot be trusted with.
+    pub fn to_english(&self) -> EnglishNarrative {
+        let mut untranslated = 0;
+        let mut one = |text: &str| {
+            let rendered = render(text);
+            untranslated += rendered.untranslated;
+            rendered.text
+        };
+
+        let headline = self.headline.as_deref().map(&mut one);
+        let observations = self.observations.iter().map(|p| one(p)).collect();
+        let scenarios = self.scenarios.iter().map(|p| one(p)).collect();
+        let recommendations = self.recommendations.iter().map(|p| one(p)).collect();
+        let disclaimer = self.disclaimer.as_deref().map(&mut one);
+
+        EnglishNarrative {
+            headline,
+            observations,
+            scenarios,
+            recommendations,
+            disclaimer,
+            untranslated,
+        }
+    }
+}
+
+/// Render a Spanish passage into English, sentence by sentence.
+pub fn render(spanish: &str) -> Rendered {
+    let mut parts = Vec::new();
+    let mut untranslated = 0;
+    for (_, sentence) in sentences(spanish) {
+        let rendered = render_sentence(sentence);
+        untranslated += rendered.untranslated;
+        parts.push(rendered.text);
+    }
+    Rendered {
+        text: parts.join(" "),
+        untranslated,
+    }
+}
+
+/// Recurring clauses and words, Spanish first. `#` stands for a number,
+/// carried into the English in order. Longer entries win over shorter ones,
+/// so a clause only needs listing when its word-by-word rendering reads badly.
+const GLOSSARY: &[(&str, &str)] = &[
+    // Scenario bullets.
+    ("Los escenarios previstos para esta fase son:", "The scenarios expected for this phase are:"),
+    ("Algunas explosiones de tamaño menor a moderado", "Some minor to moderate explosions"),
+    ("Ocurrencia de tremor de amplitud variable", "Tremor of varying amplitude"),
+    (
+        "Lluvias de ceniza leves a moderadas en poblaciones circundantes y en algunas ciudades más lejanas",
+        "Light to moderate ash fall in surrounding towns and some more distant cities",
+    ),
+    ("Lluvias de ceniza leves a moderadas en poblaciones cercanas", "Light to moderate ash fall in nearby towns"),
+    (
+        "Expulsión de fragmentos incandescentes dentro del radio de exclusión de # kilómetros",
+        "Incandescent fragments thrown out within the # km exclusion radius",
+    ),
+    (
+        "Podría esperarse la ocurrencia de lahares que bajen por las cañadas, debido a la acumulación de ceniza en las laderas y su interacción con las lluvias esperadas en las próximas semanas",
+        "Lahars may run down the ravines as ash built up on the slopes meets the rain expected in the coming weeks",
+    ),
+    ("Que continúe la actividad explosiva de escala baja a intermedia", "Continued explosive activity of low to intermediate scale"),
+    (
+        "Posibilidad de flujos piroclásticos y flujos de lodo de corto alcance",
+        "Possible short-range pyroclastic flows and mudflows",
+    ),
+    (
+        "Posibilidad de actividad explosiva importante de intensidad creciente que lanza fragmentos a distancias considerables",
+        "Possible significant explosive activity of increasing intensity, throwing fragments over considerable distances",
+    ),
+    ("Lluvias de ceniza notorias sobre poblaciones y ciudades", "Noticeable ash fall on towns and cities"),
+    ("Crecimiento importante de domos y posibilidad de expulsión de magma", "Significant dome growth and possible ejection of magma"),
+    (
+        "Podría esperarse la ocurrencia de lahares que bajen por las cañadas, debido a la acumulación de ceniza en las laderas y su interacción con las lluvias esperadas en caso de una lluvia abundante o deshielos",
+        "Lahars may run down the ravines as ash built up on the slopes meets heavy rain or meltwater",
+    ),
+    // Recommendations.
+    ("RECOMENDACIONES:", "RECOMMENDATIONS:"),
+    (
+        "Se hace especial énfasis en las siguientes recomendaciones:",
+        "Special emphasis is placed on the following recommendations:",
+    ),
+    (
+        "Las recomendaciones para la población ante esta actividad son:",
+        "The recommendations for the public given this activity are:",
+    ),
+    (
+        "Continuar con el radio de seguridad de # km, por lo que la permanencia en esa área no está permitida",
+        "Keep to the # km safety radius; staying inside that area is not permitted",
+    ),
+    (
+        "Controlar el tránsito entre Santiago Xalitzintla y San Pedro Nexapa, vía Paso de Cortés",
+        "Control traffic between Santiago Xalitzintla and San Pedro Nexapa via Paso de Cortés",
+    ),
+    (
+        "A las autoridades de Protección Civil, mantener sus procedimientos preventivos, de acuerdo con sus planes operativos",
+        "Civil Protection authorities should keep to their preventive procedures, in line with their operational plans",
+    ),
+    (
+        "A la población se le recomienda elevar la atención a los avisos de las autoridades de su localidad y se exhorta a no acercarse al volcán, y mucho menos al cráter",
+        "The public is advised to pay close attention to notices from local authorities and urged not to approach the volcano, much less the crater",
+    ),
+    (
+        "No hacer caso a rumores y estar atentos a la información que emita la Coordinación Nacional de Protección Civil por sus canales y cuentas oficiales",
+        "Ignore rumours and follow the information issued by the National Civil Protection Coordination through its official channels and accounts",
+    ),
+    (
+        "No hacer caso a rumores y estar atentos a la información oficial que emita el Cenapred y la Coordinación Nacional de Protección Civil en sus cuentas oficiales",
+        "Ignore rumours and follow the official information issued by Cenapred and the National Civil Protection Coordination on their official accounts",
+    ),
+    ("Ante la probable caída de ceniza, se recomienda", "If ash falls, you are advised to"),
+    ("Ante la probable caída de ceniza se recomienda", "If ash falls, you are advised to"),
+    ("Cubrir nariz y boca con pañuelo o cubreboca", "Cover your nose and mouth with a handkerchief or face mask"),
+    ("Limpiar ojos y garganta con agua pura", "Rinse your eyes and throat with clean water"),
+    (
+        "Utilizar lentes de armazón y evitar los de contacto para reducir la irritación ocular",
+        "Wear glasses rather than contact lenses to reduce eye irritation",
+    ),
+    (
+        "Cerrar ventanas o cubrirlas y permanecer lo más posible dentro de la casa",
+        "Close or cover windows and stay indoors as much as possible",
+    ),
+    (
+        "Mantenerse fuera de cañadas ante posibles flujos de lodo, principalmente en temporada de lluvias",
+        "Keep out of ravines in case of mudflows, especially in the rainy season",
+    ),
+    (
+        "El CENAPRED exhorta a NO ACERCARSE al volcán y sobre todo al cráter, por el peligro que implica la caída de fragmentos balísticos",
+        "CENAPRED urges people NOT TO APPROACH the volcano, and above all the crater, because of the danger of falling ballistic fragments",
+    ),
+    (
+        "El CENAPRED reitera enfáticamente la recomendación de no ascender al cráter del volcán toda vez que existe la posibilidad de que ocurran explosiones, como se ha visto en varias ocasiones en el pasado, que implican la emisión de fragmentos incandescentes",
+        "CENAPRED firmly repeats its advice not to climb to the crater, since explosions throwing out incandescent fragments may occur, as has been seen several times in the past",
+    ),
+    (
+        "El CENAPRED reitera enfáticamente la recomendación de NO ASCENDER al cráter del volcán, toda vez que existe la posibilidad de que ocurran explosiones, como se ha visto en varias ocasiones en el pasado, que implican la emisión de fragmentos incandescentes",
+        "CENAPRED firmly repeats its advice NOT TO CLIMB to the crater, since explosions throwing out incandescent fragments may occur, as has been seen several times in the past",
+    ),
+    (
+        "en caso de lluvias fuertes, alejarse de los fondos de barrancas por el peligro de flujos de lodo y escombro",
+        "in heavy rain, keep away from ravine floors because of the danger of mud and debris flows",
+    ),
+    ("Por lo que se insiste en respetar el radio de exclusión de # km", "The # km exclusion radius must therefore be respected"),
+    ("Recursos informativos de riesgos volcánicos", "Information resources on volcanic hazards"),
+    ("Para consulta de reportes anteriores dé clic aquí", "Earlier reports are available via the link"),
+    // Disclaimer.
+    (
+        "La información aquí publicada es el resultado del monitoreo permanente al volcán Popocatépetl, que se realiza coordinadamente con la Universidad Nacional Autónoma de México",
+        "The information published here comes from the permanent monitoring of Popocatépetl, carried out jointly with the National Autonomous University of Mexico",
+    ),
+    (
+        "El reporte se publica diariamente a las # de la mañana, aquí se resume la actividad registrada durante las últimas # horas; esto es, de # de la mañana del día anterior a las # de la mañana del día en curso",
+        "The report is published daily at # a.m. and summarises the activity of the last # hours, that is, from # a.m. the previous day to # a.m. the current day",
+    ),
+    ("Si ocurre un evento extraordinario, se reportará inmediatamente", "Any extraordinary event will be reported immediately"),
+    (
+        "El monitoreo del volcán Popocatépetl se realiza de forma continua las # horas",
+        "Popocatépetl is monitored continuously, # hours a day",
+    ),
+    ("Cualquier cambio en la actividad será reportado oportunamente", "Any change in activity will be reported promptly"),
+    ("Cualquier cambio en la actividad del volcán se reportará oportunamente", "Any change in activity will be reported promptly"),
+    ("Cualquier cambio en la actividad, se informará de manera oportuna", "Any change in activity will be reported promptly"),
+    (
+        "El nivel del Semáforo de Alerta Volcánica dependerá de la evolución de la actividad del volcán",
+        "The Volcanic Alert level will depend on how the volcano's activity evolves",
+    ),
+    // The phase statement and the headline.
+    (
+        "El Semáforo de Alerta Volcánica del Popocatépetl se encuentra en",
+        "The Popocatépetl Volcanic Alert Traffic Light stands at",
+    ),
+    ("Semáforo de Alerta Volcánica", "Volcanic Alert Traffic Light"),
+    ("Verde Fase #", "Green Phase #"),
+    ("Amarillo Fase #", "Yellow Phase #"),
+    ("Naranja Fase #", "Orange Phase #"),
+    ("Rojo Fase #", "Red Phase #"),
+    ("Reporte del monitoreo del Cenapred al volcán Popocatépetl", "Cenapred monitoring report on Popocatépetl volcano"),
+    ("# de enero de #", "January #, #"),
+    ("# de febrero de #", "February #, #"),
+    ("# de marzo de #", "March #, #"),
+    ("# de abril de #", "April #, #"),
+    ("# de mayo de #", "May #, #"),
+    ("# de junio de #", "June #, #"),
+    ("# de julio de #", "July #, #"),
+    ("# de agosto de #", "August #, #"),
+    ("# de septiembre de #", "September #, #"),
+    ("# de octubre de #", "October #, #"),
+    ("# de noviembre de #", "November #, #"),
+    ("# de diciembre de #", "December #, #"),
+    // Counting what the instruments picked up.
+    ("En las últimas # horas", "In the last # hours"),
+    ("mediante los sistemas de monitoreo del volcán Popocatépetl se", "the Popocatépetl monitoring systems"),
+    ("mediante los sistemas de monitoreo del volcán Popocatépetl, se", "the Popocatépetl monitoring systems"),
+    ("mediante el sistema de monitoreo del volcán Popocatépetl se", "the Popocatépetl monitoring system"),
+    ("mediante el sistema de monitoreo del volcán Popocatépetl, se", "the Popocatépetl monitoring system"),
+    ("se identificaron", "there were"),
+    ("se detectaron", "there were"),
+    ("se registraron", "there were"),
+    ("se contabilizaron", "there were"),
+    ("se calcularon", "there were"),
+    ("se clasificaron", "there were"),
+    ("se presentaron", "there were"),
+    ("fueron registradas", "there were"),
+    ("se registró", "there was"),
+    ("se identificó", "there was"),
+    ("se detectó", "there was"),
+    ("acompañadas de", "accompanied by"),
+    ("acompañados de", "accompanied by"),
+    ("acompañada de", "accompanied by"),
+    ("acompañado de", "accompanied by"),
+    ("acompañadas por", "accompanied by"),
+    ("acompañados por", "accompanied by"),
+    ("fue acompañado por", "was accompanied by"),
+    ("fueron acompañados por", "were accompanied by"),
+    ("estos eventos", "these events"),
+    ("vapor de agua", "water vapour"),
+    ("gases volcánicos", "volcanic gases"),
+    ("otros gases volcánicos", "other volcanic gases"),
+    ("ligeras cantidades de ceniza", "light amounts of ash"),
+    ("ligera cantidad de ceniza", "a light amount of ash"),
+    ("bajas cantidades de ceniza", "small amounts of ash"),
+    ("bajo contenido de ceniza", "a low ash content"),
+    ("en ocasiones", "at times"),
+    ("en algunas ocasiones", "at times"),
+    ("algunas de ellas", "some of them"),
+    ("de baja intensidad", "of low intensity"),
+    ("minutos de tremor", "minutes of tremor"),
+    ("min de tremor", "minutes of tremor"),
+    ("de baja amplitud", "of low amplitude"),
+    ("de baja y mediana amplitud", "of low and medium amplitude"),
+    ("de alta frecuencia", "of high frequency"),
+    ("tremor armónico", "harmonic tremor"),
+    ("explosión menor", "minor explosion"),
+    ("explosiones menores", "minor explosions"),
+    ("explosión moderada", "moderate explosion"),
+    ("sismo volcanotectónico", "volcanotectonic earthquake"),
+    ("sismos volcanotectónicos", "volcanotectonic earthquakes"),
+    ("sismo vulcanotectónico", "volcanotectonic earthquake"),
+    ("con magnitud calculada de", "of calculated magnitude"),
+    ("con magnitud calculada", "of calculated magnitude"),
+    ("con magnitud preliminar", "of preliminary magnitude"),
+    ("con magnitud de", "of magnitude"),
+    ("con magnitudes de", "of magnitudes"),
+    ("con magnitud", "of magnitude"),
+    ("hora local", "local time"),
+    ("a las # h", "at #"),
+    ("a la # h", "at #"),
+    ("a las # hrs", "at #"),
+    ("a las #", "at #"),
+    ("a la #", "at #"),
+    ("# h", "#"),
+    ("el día de ayer", "yesterday"),
+    ("del día de ayer", "yesterday"),
+    ("el día de hoy", "today"),
+    ("del día de hoy", "today"),
+    ("de ayer", "yesterday"),
+    ("de hoy", "today"),
+    ("ayer por la noche", "last night"),
+    // What could be seen.
+    ("Al momento de este reporte", "At the time of this report"),
+    ("al momento de este reporte", "at the time of this report"),
+    ("hasta el momento de este reporte", "up to the time of this report"),
+    ("Durante la mañana", "During the morning"),
+    ("Durante esta mañana", "This morning"),
+    ("Durante la noche", "During the night"),
+    ("Durante la madrugada", "In the early hours"),
+    ("Desde hoy por la mañana", "Since this morning"),
+    ("Desde esta mañana", "Since this morning"),
+    ("Desde la mañana", "Since the morning"),
+    ("Desde las primeras horas de la mañana", "Since the early hours of the morning"),
+    ("durante las primeras horas de la mañana", "during the early hours of the morning"),
+    ("se observa", "there is"),
+    ("se presenta", "there is"),
+    ("se ha observado", "there has been"),
+    ("se han presentado", "there have been"),
+    ("se observó", "there was"),
+    ("se observaron", "there were"),
+    ("se pudo observar", "it was possible to see"),
+    ("se pudo apreciar", "it was possible to see"),
+    ("se puso apreciar", "it was possible to see"),
+    ("no se ha podido ver", "it has not been possible to see"),
+    ("no ha sido posible observar", "it has not been possible to see"),
+    ("se tiene visibilidad", "there is visibility"),
+    ("no se tiene visibilidad", "there is no visibility"),
+    ("se tuvo visibilidad", "there was visibility"),
+    ("se tuvo", "there was"),
+    ("hay visibilidad", "there is visibility"),
+    ("visibilidad parcial", "partial visibility"),
+    ("buena visibilidad", "good visibility"),
+    ("por nublados", "because of cloud"),
+    ("debido a las condiciones de nubosidad", "because of cloud cover"),
+    ("condiciones de nubosidad", "cloud cover"),
+    ("sin embargo", "however"),
+    ("no obstante", "however"),
+    ("una emisión de", "an emission of"),
+    ("emisión continua", "continuous emission"),
+    ("emisión continúa", "continuous emission"),
+    ("emisiones continuas", "continuous emissions"),
+    ("emisión constante", "steady emission"),
+    ("emisión intermitente", "intermittent emission"),
+    ("ligera emisión", "light emission"),
+    ("leve emisión", "faint emission"),
+    ("pequeña emisión", "small emission"),
+    ("emisiones de ceniza", "ash emissions"),
+    ("que se dispersan", "dispersing"),
+    ("que se dispersa", "dispersing"),
+    ("que se dispersaron", "which dispersed"),
+    ("que se dispersaba", "dispersing"),
+    ("que se dispersaban", "dispersing"),
+    ("se dispersaron", "dispersed"),
+    ("que se dirige", "heading"),
+    ("que se desplazó", "which drifted"),
+    ("con dispersión", "dispersing"),
+    ("con dirección al", "heading"),
+    ("con dirección", "heading"),
+    ("en dirección", "heading"),
+    ("dirección preferencial", "mainly"),
+    ("desplazándose", "drifting"),
+    ("en el sector", "in the sector"),
+    ("fragmentos incandescentes", "incandescent fragments"),
+    ("fragmentos balísticos", "ballistic fragments"),
+    ("sobre el cráter", "above the crater"),
+    ("sobre del cráter", "above the crater"),
+    ("en el cráter", "in the crater"),
+    ("cráter interno", "inner crater"),
+    ("cráter interior", "inner crater"),
+    ("la ladera del volcán", "the slope of the volcano"),
+    ("columna eruptiva", "eruptive column"),
+    ("altura de columna", "column height"),
+    ("con altura cercana a", "reaching about"),
+    ("que arrojó", "which threw out"),
+    ("que arrojo", "which threw out"),
+    ("que cayeron", "which fell"),
+    // Ash fall.
+    ("Derivado de la actividad del volcán", "As a result of the volcano's activity"),
+    (
+        "Centro Nacional de Comunicación y Operación de Protección Civil",
+        "National Civil Protection Communication and Operations Centre",
+    ),
+    ("Centro Nacional de Prevención de Desastres", "National Centre for Disaster Prevention"),
+    ("Coordinación Nacional de Protección Civil", "National Civil Protection Coordination"),
+    ("Protección Civil", "Civil Protection"),
+    ("Universidad Nacional Autónoma de México", "National Autonomous University of Mexico"),
+    ("Instituto de Geofísica", "Institute of Geophysics"),
+    ("Instituto de Geología", "Institute of Geology"),
+    ("Guardia Nacional", "National Guard"),
+    ("caída de ceniza", "ash fall"),
+    ("ligera caída de ceniza", "light ash fall"),
+    ("caída leve de ceniza", "light ash fall"),
+    ("en los municipios de", "in the municipalities of"),
+    ("en el municipio de", "in the municipality of"),
+    ("en la población de", "in the town of"),
+    ("pertenecientes al Estado de México", "in the State of Mexico"),
+    ("Estado de México", "State of Mexico"),
+    ("del estado de", "in the state of"),
+    ("se tuvo conocimiento de", "there were reports of"),
+    ("sin que hubiera riesgo para la población", "with no risk to the population"),
+    ("Así mismo", "Likewise"),
+    // Directions, beyond the compass words generated in `glossary()`.
+    ("hacia el", "towards the"),
+    ("nor", "north"),
+    // Words, and the odd short phrase that reads better kept together.
+    ("a", "to"),
+    ("abril", "April"),
+    ("acompañadas", "accompanied"),
+    ("actividad", "activity"),
+    ("Además", "In addition"),
+    ("Adicionalmente", "Additionally"),
+    ("agosto", "August"),
+    ("agua", "water"),
+    ("al", "to the"),
+    ("algunas", "some"),
+    ("algunos", "some"),
+    ("alta", "high"),
+    ("altura", "height"),
+    ("amarillo", "yellow"),
+    ("ambos", "both"),
+    ("amplitud", "amplitude"),
+    ("apoyo", "support"),
+    ("aproximada", "approximate"),
+    ("aproximado", "approximate"),
+    ("armónico", "harmonic"),
+    ("así", "thus"),
+    ("Asimismo", "Likewise"),
+    ("asociadas", "associated"),
+    ("aumentaba", "increased"),
+    ("ayer", "yesterday"),
+    ("baja", "low"),
+    ("bajas", "low"),
+    ("bajo", "low"),
+    ("base", "basis"),
+    ("cambio", "change"),
+    ("cambios", "changes"),
+    ("cantidades", "amounts"),
+    ("características sísmicas", "seismic characteristics"),
+    ("caso", "case"),
+    ("ceniza", "ash"),
+    ("cenizas", "ash"),
+    ("cercana", "nearby"),
+    ("cima", "summit"),
+    ("cinco", "five"),
+    ("ciudades", "cities"),
+    ("columna", "column"),
+    ("columnas", "columns"),
+    ("como", "as"),
+    ("con", "with"),
+    ("con base en", "based on"),
+    ("condiciones meteorológicas", "weather conditions"),
+    ("condiciones morfológicas", "morphological conditions"),
+    ("constante", "steady"),
+    ("continua", "continuous"),
+    ("cráter", "crater"),
+    ("cualquier", "any"),
+    ("cuatro", "four"),
+    ("de", "of"),
+    ("de los cuales", "of which"),
+    ("debido a", "owing to"),
+    ("del", "of the"),
+    ("desde", "since"),
+    ("detectada", "detected"),
+    ("detectado", "detected"),
+    ("detectaron", "detected"),
+    ("determinar", "determine"),
+    ("día", "day"),
+    ("diámetro", "diameter"),
+    ("días", "days"),
+    ("diciembre", "December"),
+    ("diferentes", "different"),
+    ("dirección", "direction"),
+    ("dispersó", "dispersed"),
+    ("domo", "dome"),
+    ("domos", "domes"),
+    ("dos", "two"),
+    ("durante", "during"),
+    ("e", "and"),
+    ("el", "the"),
+    ("emisión", "emission"),
+    ("emisiones", "emissions"),
+    ("en", "in"),
+    ("enero", "January"),
+    ("entre", "between"),
+    ("episodio", "episode"),
+    ("episodios", "episodes"),
+    ("es", "is"),
+    ("esta", "this"),
+    ("estado", "state"),
+    ("estas", "these"),
+    ("estos", "these"),
+    ("eventos", "events"),
+    ("exhalaciones", "exhalations"),
+    ("explosión", "explosion"),
+    ("explosiones", "explosions"),
+    ("febrero", "February"),
+    ("frecuencia", "frequency"),
+    ("fue", "was"),
+    ("fueron", "were"),
+    ("gas", "gas"),
+    ("gases", "gases"),
+    ("ha", "has"),
+    ("hacia", "towards"),
+    ("han", "have"),
+    ("hasta", "until"),
+    ("hay", "there is"),
+    ("horas", "hours"),
+    ("horas previas", "previous hours"),
+    ("hoy", "today"),
+    ("hubo", "there was"),
+    ("identificaron", "identified"),
+    ("identificó", "identified"),
+    ("imagen", "image"),
+    ("imágenes", "images"),
+    ("incandescencia", "incandescence"),
+    ("información", "information"),
+    ("intensidad", "intensity"),
+    ("julio", "July"),
+    ("junio", "June"),
+    ("la", "the"),
+    ("las", "the"),
+    ("lava", "lava"),
+    ("leve", "faint"),
+    ("ligera", "light"),
+    ("ligeras", "light"),
+    ("lo", ""),
+    ("lo observado", "what was observed"),
+    ("los", "the"),
+    ("m", "m"),
+    ("madrugada", "early hours"),
+    ("magnitud", "magnitude"),
+    ("magnitudes", "magnitudes"),
+    ("mañana", "morning"),
+    ("manera", "way"),
+    ("mantiene", "keeps"),
+    ("marzo", "March"),
+    ("material", "material"),
+    ("mayo", "May"),
+    ("mediana", "medium"),
+    ("menor", "minor"),
+    ("menores", "minor"),
+    ("metros", "metres"),
+    ("minutos", "minutes"),
+    ("moderada", "moderate"),
+    ("moderadas", "moderate"),
+    ("moderado", "moderate"),
+    ("momento", "moment"),
+    ("monitoreo", "monitoring"),
+    ("muy", "very"),
+    ("nivel", "level"),
+    ("no", "not"),
+    ("noche", "night"),
+    ("noviembre", "November"),
+    ("nubes", "clouds"),
+    ("nublados", "cloud"),
+    ("nueve", "nine"),
+    ("nuevo", "new"),
+    ("o", "or"),
+    ("observar", "see"),
+    ("observó", "observed"),
+    ("ocasionalmente", "occasionally"),
+    ("ocho", "eight"),
+    ("octubre", "October"),
+    ("ocurren", "occur"),
+    ("ocurrido", "occurred"),
+    ("para", "for"),
+    ("parámetros", "parameters"),
+    ("parcial", "partial"),
+    ("parcialmente", "partially"),
+    ("pequeña", "small"),
+    ("pequeño", "small"),
+    ("pequeños", "small"),
+    ("periodo", "period"),
+    ("personal", "staff"),
+    ("piso", "floor"),
+    ("población", "population"),
+    ("poblaciones", "towns"),
+    ("podido", "been able to"),
+    ("por", "by"),
+    ("posible", "possible"),
+    ("predominantemente", "predominantly"),
+    ("presencia", "presence"),
+    ("presenta", "presents"),
+    ("principalmente", "mainly"),
+    ("probable", "likely"),
+    ("profundidad", "depth"),
+    ("próximos", "coming"),
+    ("pudo", "could"),
+    ("que", "that"),
+    ("realizó", "carried out"),
+    ("recientes", "recent"),
+    ("registrada", "recorded"),
+    ("registradas", "recorded"),
+    ("registrado", "recorded"),
+    ("registrados", "recorded"),
+    ("reportó", "reported"),
+    ("respectivamente", "respectively"),
+    ("restante", "remaining"),
+    ("riesgo", "risk"),
+    ("se", ""),
+    ("sector", "sector"),
+    ("secuencias", "sequences"),
+    ("segmentos", "segments"),
+    ("seis", "six"),
+    ("semanas", "weeks"),
+    ("septiembre", "September"),
+    ("siete", "seven"),
+    ("similares", "similar"),
+    ("sin", "without"),
+    ("sismo", "earthquake"),
+    ("sismos", "earthquakes"),
+    ("sobre", "over"),
+    ("sobrevuelo", "overflight"),
+    ("su", "its"),
+    ("sus", "its"),
+    ("tamaño", "size"),
+    ("También", "Also"),
+    ("tiempo local", "local time"),
+    ("tiene", "has"),
+    ("tienen", "have"),
+    ("tipo", "type"),
+    ("tipo armónico", "harmonic type"),
+    ("tremor", "tremor"),
+    ("tres", "three"),
+    ("tuvo", "had"),
+    ("últimas", "last"),
+    ("un", "a"),
+    ("una", "a"),
+    ("vapor", "vapour"),
+    ("video", "video"),
+    ("visibilidad", "visibility"),
+    ("volcán", "volcano"),
+    ("volcánica", "volcanic"),
+    ("volcánicos", "volcanic"),
+    ("y", "and"),
+    ("zona", "area"),
+    ("zonas", "zones"),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token<'a> {
+    Word(&'a str),
+    Number(&'a str),
+    /// URLs and account handles, never rendered.
+    Verbatim(&'a str),
+    Punct(char),
+}
+
+fn tokenize(text: &str) -> Vec<Token<'_>> {
+    let mut out = Vec::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if c.is_whitespace() {
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+        let end = if c == '@' || rest.starts_with("www.") || rest.starts_with("http") {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            let trimmed = rest[..end].trim_end_matches(['.', ',', ';', ':', ')']);
+            out.push(Token::Verbatim(trimmed));
+            trimmed.len()
+        } else if c.is_ascii_digit() {
+            let end = number_end(rest);
+            out.push(Token::Number(&rest[..end]));
+            end
+        } else if c.is_alphabetic() {
+            let end = rest
+                .find(|c: char| !c.is_alphanumeric())
+                .unwrap_or(rest.len());
+            out.push(Token::Word(&rest[..end]));
+            end
+        } else {
+            out.push(Token::Punct(c));
+            c.len_utf8()
+        };
+        rest = &rest[end..];
+    }
+    out
+}
+
+/// Digits, with `.` or `:` kept when a digit follows ("1.7", "09:31") and a
+/// trailing degree sign ("3°") swallowed.
+fn number_end(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    let mut end = 0;
+    while end < bytes.len() {
+        let b = bytes[end];
+        let separator =
+            (b == b'.' || b == b':') && bytes.get(end + 1).is_some_and(u8::is_ascii_digit);
+        if b.is_ascii_digit() || separator {
+            end += 1;
+        } else {
+            break;
+        }
+    }
+    if text[end..].starts_with('°') {
+        end += '°'.len_utf8();
+    }
+    end
+}
+
+#[derive(Debug, PartialEq)]
+enum Slot {
+    Word(String),
+    Number,
+    Punct(char),
+}
+
+struct Entry {
+    pattern: Vec<Slot>,
+    english: String,
+}
+
+/// The glossary keyed by its first slot, longest entries first.
+fn glossary() -> &'static HashMap<String, Vec<Entry>> {
+    static GLOSSARY_INDEX: OnceLock<HashMap<String, Vec<Entry>>> = OnceLock::new();
+    GLOSSARY_INDEX.get_or_init(|| {
+        let mut entries: Vec<(String, String)> = GLOSSARY
+            .iter()
+            .map(|(es, en)| (es.to_string(), en.to_string()))
+            .collect();
+
+        // Compass words, and the Spanish abbreviations CENAPRED puts in
+        // brackets after them: "(O)" for oeste is "(W)", "(NO)" is "(NW)".
+        for dir in WindDirection::ALL {
+            let code = format!("{:?}", dir);
+            entries.push((
+                format!("({})", code.replace('W', "O")),
+                format!("({})", code),
+            ));
+        }
+        entries.push(("(O-NO)".into(), "(W-NW)".into()));
+        for (es, en) in [
+            ("norte", "north"),
+            ("sur", "south"),
+            ("este", "east"),
+            ("oeste", "west"),
+            ("noreste", "northeast"),
+            ("noroeste", "northwest"),
+            ("sureste", "southeast"),
+            ("suroeste", "southwest"),
+        ] {
+            entries.push((es.into(), en.into()));
+        }
+
+        let mut index: HashMap<String, Vec<Entry>> = HashMap::new();
+        for (es, en) in entries {
+            let pattern: Vec<Slot> = tokenize(&es)
+                .into_iter()
+                .map(|t| match t {
+                    Token::Punct('#') => Slot::Number,
+                    Token::Word(w) | Token::Verbatim(w) => Slot::Word(fold(w)),
+                    Token::Number(n) => Slot::Word(n.to_string()),
+                    Token::Punct(c) => Slot::Punct(c),
+                })
+                .collect();
+            index.entry(slot_key(&pattern[0])).or_default().push(Entry {
+                pattern,
+                english: en,
+            });
+        }
+        for list in index.values_mut() {
+            list.sort_by_key(|e| std::cmp::Reverse(e.pattern.len()));
+        }
+        index
+    })
+}
+
+fn slot_key(slot: &Slot) -> String {
+    match slot {
+        Slot::Word(w) => w.clone(),
+        Slot::Number => "#".to_string(),
+        Slot::Punct(c) => c.to_string(),
+    }
+}
+
+fn token_key(token: &Token) -> String {
+    match token {
+        Token::Word(w) => fold(w),
+        Token::Number(_) => "#".to_string(),
+        Token::Verbatim(v) => v.to_string(),
+        Token::Punct(c) => c.to_string(),
+    }
+}
+
+fn matches(slot: &Slot, token: &Token) -> bool {
+    match (slot, token) {
+        (Slot::Word(w), Token::Word(t)) => *w == fold(t),
+        (Slot::Number, Token::Number(_)) => true,
+        (Slot::Punct(a), Token::Punct(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// A piece of output, before spacing is decided.
+enum Piece {
+    Text(String),
+    Punct(char),
+    Spanish(Vec<String>),
+}
+
+fn render_sentence(sentence: &str) -> Rendered {
+    let tokens = tokenize(sentence);
+    let index = glossary();
+    let mut pieces: Vec<Piece> = Vec::new();
+    let (mut words, mut unknown) = (0, 0);
+
+    let mut i = 0;
+    while i < tokens.len() {
+        let found = index.get(&token_key(&tokens[i])).and_then(|entries| {
+            entries.iter().find(|e| {
+                e.pattern.len() <= tokens.len() - i
+                    && e.pattern
+                        .iter()
+                        .zip(&tokens[i..])
+                        .all(|(s, t)| matches(s, t))
+            })
+        });
+
+        if let Some(entry) = found {
+            let mut numbers = tokens[i..i + entry.pattern.len()]
+                .iter()
+                .filter_map(|t| match t {
+                    Token::Number(n) => Some(n.trim_end_matches('°')),
+                    _ => None,
+                });
+            let mut english = String::new();
+            for c in entry.english.chars() {
+                match c {
+                    '#' => english.push_str(numbers.next().unwrap_or("#")),
+                    c => english.push(c),
+                }
+            }
+            words += entry
+                .pattern
+                .iter()
+                .filter(|s| matches!(s, Slot::Word(_)))
+                .count();
+            if !english.is_empty() {
+                pieces.push(Piece::Text(english));
+            }
+            i += entry.pattern.len();
+            continue;
+        }
+
+        match tokens[i] {
+            Token::Word(w) => {
+                words += 1;
+                if is_proper_noun(w) || is_signature(&tokens) {
+                    pieces.push(Piece::Text(w.to_string()));
+                } else {
+                    unknown += 1;
+                    match pieces.last_mut() {
+                        Some(Piece::Spanish(run)) => run.push(w.to_string()),
+                        _ => pieces.push(Piece::Spanish(vec![w.to_string()])),
+                    }
+                }
+            }
+            Token::Number(n) => pieces.push(Piece::Text(n.trim_end_matches('°').to_string())),
+            Token::Verbatim(v) => pieces.push(Piece::Text(v.to_string())),
+            Token::Punct(c) => pieces.push(Piece::Punct(c)),
+        }
+        i += 1;
+    }
+
+    // Mostly unknown reads worse rendered than not.
+    if unknown * 2 > words {
+        return Rendered {
+            text: format!("[es: {}]", sentence),
+            untranslated: 1,
+        };
+    }
+
+    let untranslated = pieces
+        .iter()
+        .filter(|p| matches!(p, Piece::Spanish(_)))
+        .count();
+    Rendered {
+        text: capitalize(&join(&pieces)),
+        untranslated,
+    }
+}
+
+/// Names the reports use that read the same in English: institutions,
+/// volcanoes, the GMT time given alongside local time, and its months.
+const KNOWN_NAMES: &[&str] = &[
+    "cenapred",
+    "cenacom",
+    "cnpc",
+    "unam",
+    "gmt",
+    "vt",
+    "popocatepetl",
+    "iztaccihuatl",
+    "facebook",
+    "twitter",
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// Place names and known names pass through unmarked. Being capitalised is
+/// not enough: headlines are in capitals ("NARANJA FASE 2") and so are
+/// institutions the glossary lacks.
+fn is_proper_noun(word: &str) -> bool {
+    if !word.chars().next().is_some_and(char::is_uppercase) {
+        return false;
+    }
+    let folded = fold(word);
+    KNOWN_NAMES.contains(&folded.as_str())
+        || State::ALL
+            .iter()
+            .flat_map(|s| s.name().split(' '))
+            .chain(MUNICIPALITIES.iter().flat_map(|m| m.name.split(' ')))
+            .any(|part| part.len() > 3 && fold(part) == folded)
+        || gazetteer::lookup(word, None).is_some()
+}
+
+/// The initials CENAPRED signs some reports with, alone after the last
+/// sentence ("… del volcán. JCJV").
+fn is_signature(tokens: &[Token]) -> bool {
+    match tokens {
+        [Token::Word(w)] => (2..=6).contains(&w.len()) && w.chars().all(|c| c.is_ascii_uppercase()),
+        _ => false,
+    }
+}
+
+fn join(pieces: &[Piece]) -> String {
+    let mut out = String::new();
+    let mut glue = true;
+    let mut last_word = String::new();
+
+    for piece in pieces {
+        let (text, space_before, space_after) = match piece {
+            Piece::Text(t) => (t.clone(), true, true),
+            Piece::Spanish(run) => (format!("[es: {}]", run.join(" ")), true, true),
+            Piece::Punct(c @ ('(' | '[' | '¿' | '¡')) => (c.to_string(), true, false),
+            Piece::Punct(c @ ('-' | '/')) => (c.to_string(), false, false),
+            Piece::Punct(c) => (c.to_string(), false, true),
+        };
+        if space_before && !glue && !out.is_empty() {
+            out.push(' ');
+        }
+        // "a" before a vowel sound, as best a glossary can tell.
+        if last_word == "a" && text.starts_with(['a', 'e', 'i', 'o', 'u']) {
+            out.insert(out.len() - 1, 'n');
+        }
+        last_word = text.rsplit(' ').next().unwrap_or_default().to_string();
+        out.push_str(&text);
+        glue = !space_after;
+    }
+    out
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_bullets_render_whole() {
+        let rendered = render("Algunas explosiones de tamaño menor a moderado");
+        assert_eq!(rendered.text, "Some minor to moderate explosions");
+        assert!(rendered.is_complete());
+
+        let rendered = render("Limpiar ojos y garganta con agua pura.");
+        assert_eq!(
+            rendered.text,
+            "Rinse your eyes and throat with clean water."
+        );
+    }
+
+    #[test]
+    fn exhalation_counts_keep_their_numbers() {
+        let rendered = render(
+            "Se detectaron 45 exhalaciones, acompañadas de vapor de agua, gases volcánicos \
+             y ligeras cantidades de ceniza (imagen 1), (vídeo 2).",
+        );
+        assert_eq!(
+            rendered.text,
+            "There were 45 exhalations, accompanied by water vapour, volcanic gases \
+             and light amounts of ash (image 1), (video 2)."
+        );
+    }
+
+    #[test]
+    fn phase_and_plume_direction() {
+        let rendered = render(
+            "El Semáforo de Alerta Volcánica del Popocatépetl se encuentra en AMARILLO FASE 2.",
+        );
+        assert_eq!(
+            rendered.text,
+            "The Popocatépetl Volcanic Alert Traffic Light stands at Yellow Phase 2."
+        );
+
+        let rendered = render(
+            "Al momento de este reporte se observa una emisión de vapor de agua con dirección oeste (O).",
+        );
+        assert_eq!(
+            rendered.text,
+            "At the time of this report there is an emission of water vapour heading west (W)."
+        );
+    }
+
+    #[test]
+    fn every_alert_colour_renders() {
+        for (spanish, english) in [
+            ("VERDE FASE 1", "Green Phase 1"),
+            ("AMARILLO FASE 3", "Yellow Phase 3"),
+            ("NARANJA FASE 2", "Orange Phase 2"),
+            ("ROJO FASE 1", "Red Phase 1"),
+        ] {
+            let rendered = render(&format!(
+                "El Semáforo de Alerta Volcánica del Popocatépetl se encuentra en {}.",
+                spanish
+            ));
+            assert_eq!(
+                rendered.text,
+                format!(
+                    "The Popocatépetl Volcanic Alert Traffic Light stands at {}.",
+                    english
+                )
+            );
+            assert!(rendered.is_complete(), "{}", spanish);
+        }
+    }
+
+    #[test]
+    fn capitals_alone_do_not_make_a_name() {
+        let rendered = render(
+            "El Semáforo de Alerta Volcánica del Popocatépetl se encuentra en MORADO FASE 2.",
+        );
+        assert!(rendered.text.contains("[es: MORADO"), "{}", rendered.text);
+        assert!(!rendered.is_complete());
+
+        let rendered = render("Se registraron 3 explosiones en la Zona Centro.");
+        assert!(rendered.text.contains("[es: Centro]"), "{}", rendered.text);
+
+        let rendered = render("Se registró ceniza en Tétela del Volcán y Chilchota, Puebla.");
+        assert!(rendered.is_complete(), "{}", rendered.text);
+    }
+
+    #[test]
+    fn unknown_words_are_kept_and_counted() {
+        let rendered = render(
+            "Adicionalmente se registraron 120 minutos de tremor azolvado. Se visualizó tefra azolvada.",
+        );
+        assert_eq!(
+            rendered.text,
+            "Additionally there were 120 minutes of tremor [es: azolvado]. \
+             [es: Se visualizó tefra azolvada.]"
+        );
+        assert_eq!(rendered.untranslated, 2);
+    }
+
+    #[test]
+    fn place_names_pass_through() {
+        let rendered = render(
+            "Derivado de la actividad del volcán el Centro Nacional de Comunicación y Operación \
+             de Protección Civil (CENACOM) reportó ligera caída de ceniza en los municipios de \
+             Amecameca y Ozumba pertenecientes al Estado de México.",
+        );
+        assert_eq!(
+            rendered.text,
+            "As a result of the volcano's activity the National Civil Protection Communication \
+             and Operations Centre (CENACOM) reported light ash fall in the municipalities of \
+             Amecameca and Ozumba in the State of Mexico."
+        );
+        assert!(rendered.is_complete());
+    }
+
+    #[test]
+    fn headline_dates_are_reordered() {
+        let rendered = render(
+            "Reporte del monitoreo del Cenapred al volcán Popocatépetl 22 de Agosto de 2026",
+        );
+        assert_eq!(
+            rendered.text,
+            "Cenapred monitoring report on Popocatépetl volcano August 22, 2026"
+        );
+    }
+}
@@ -0,0 +1,706 @@
+//! `popo tui`: a full-screen dashboard over the feed.
+//!
+//! One screen per report date: the alert level, the day's counters,
+//! sparklines and a wind rose over the [`HISTORY_DAYS`] before it, the towns
+//! that reported ashfall, and the narrative in a scrollable pane. The arrow
+//! keys step through the dates the index lists. The index is fetched again
+//! every refresh interval, and a dashboard showing the newest report moves on
+//! to the next one when it is published.
+//!
+//! [`Dashboard`] holds the state and draws it, without touching the terminal
+//! or the feed, so it can be driven from tests. [`run`] wires it to both.
+
+use crate::error::{PopoError, Result};
+use crate::feed::Feed;
+use crate::i18n::{fill, Lang};
+use crate::models::{AlertLevel, FeedIndex, VolcanoReport};
+use crate::windrose::WindRose;
+use chrono::{Duration, NaiveDate};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use ratatui::{Frame, Terminal};
+use std::collections::BTreeMap;
+use std::io::IsTerminal;
+use std::time::Instant;
+
+/// Days of history behind the sparklines and wind rose, the selected day
+/// included.
+pub const HISTORY_DAYS: i64 = 60;
+
+/// What the event loop should do after a key press.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    None,
+    Refresh,
+    Quit,
+}
+
+pub struct Dashboard {
+    dates: Vec<NaiveDate>,
+    selected: usize,
+    reports: BTreeMap<NaiveDate, VolcanoReport>,
+    scroll: u16,
+    /// Labels, dates and level names.
+    lang: Lang,
+    /// The narrative: the Spanish original, or the offline English rendering.
+    narrative_lang: Lang,
+    colour: bool,
+    status: Option<String>,
+}
+
+impl Dashboard {
+    /// A dashboard on the newest date in `index`.
+    pub fn new(index: FeedIndex, lang: Lang, narrative_lang: Lang, colour: bool) -> Self {
+        let mut dates = index.dates;
+        dates.sort_unstable();
+        dates.dedup();
+        Dashboard {
+            selected: dates.len().saturating_sub(1),
+            dates,
+            reports: BTreeMap::new(),
+            scroll: 0,
+            lang,
+            narrative_lang,
+            colour,
+            status: None,
+        }
+    }
+
+    pub fn selected_date(&self) -> Option<NaiveDate> {
+        self.dates.get(self.selected).copied()
+    }
+
+    pub fn report(&self) -> Option<&VolcanoReport> {
+        self.reports.get(&self.selected_date()?)
+    }
+
+    /// The listed dates within the history window ending on the selected one.
+    pub fn window(&self) -> &[NaiveDate] {
+        let Some(last) = self.selected_date() else {
+            return &[];
+        };
+        let first = last - Duration::days(HISTORY_DAYS - 1);
+        let start = self.dates.partition_point(|d| *d < first);
+        &self.dates[start..=self.selected]
+    }
+
+    /// Dates in the window with no report loaded yet, the selected one first.
+    pub fn missing(&self) -> Vec<NaiveDate> {
+        self.window()
+            .iter()
+            .rev()
+            .filter(|d| !self.reports.contains_key(d))
+            .copied()
+            .collect()
+    }
+
+    pub fn insert(&mut self, report: VolcanoReport) {
+        self.reports.insert(report.date, report);
+    }
+
+    /// Take a fresh index. The same date stays selected, unless the newest
+    /// was selected, in which case the new newest is. The selected report is
+    /// dropped so it is fetched again, in case it was republished.
+    pub fn set_index(&mut self, index: FeedIndex) {
+        let following = self.selected + 1 >= self.dates.len();
+        let current = self.selected_date();
+        let mut dates = index.dates;
+        dates.sort_unstable();
+        dates.dedup();
+        self.dates = dates;
+        self.selected = match current {
+            Some(date) if !following => self
+                .dates
+                .partition_point(|d| *d < date)
+                .min(self.dates.len().saturating_sub(1)),
+            _ => self.dates.len().saturating_sub(1),
+        };
+        if self.selected_date() != current {
+            self.scroll = 0;
+        }
+        if let Some(date) = self.selected_date() {
+            self.reports.remove(&date);
+        }
+    }
+
+    pub fn set_status(&mut self, status: impl Into<String>) {
+        self.status = Some(status.into());
+    }
+
+    pub fn handle(&mut self, key: KeyEvent) -> Action {
+        if key.kind == KeyEventKind::Release {
+            return Action::None;
+        }
+        let last = self.dates.len().saturating_sub(1);
+        let before = self.selected;
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Action::Quit,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Action::Quit
+            }
+            KeyCode::Char('r') => return Action::Refresh,
+            KeyCode::Left | KeyCode::Char('h') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Right | KeyCode::Char('l') => self.selected = (self.selected + 1).min(last),
+            KeyCode::Home => self.selected = 0,
+            KeyCode::End => self.selected = last,
+            KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll = self.scroll.saturating_add(1),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(10),
+            _ => {}
+        }
+        if self.selected != before {
+            self.scroll = 0;
+        }
+        Action::None
+    }
+
+    pub fn draw(&self, frame: &mut Frame) {
+        let [header, top, bottom, footer] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(11),
+            Constraint::Min(8),
+            Constraint::Length(1),
+        ])
+        .areas(frame.size());
+        let [counters, history] =
+            Layout::horizontal([Constraint::Length(34), Constraint::Min(20)]).areas(top);
+        let [rose, ashfall, narrative] = Layout::horizontal([
+            Constraint::Length(24),
+            Constraint::Length(28),
+            Constraint::Min(20),
+        ])
+        .areas(bottom);
+
+        self.draw_header(frame, header);
+        self.draw_counters(frame, counters);
+        self.draw_history(frame, history);
+        self.draw_rose(frame, rose);
+        self.draw_ashfall(frame, ashfall);
+        self.draw_narrative(frame, narrative);
+
+        let m = self.lang.messages();
+        let mut keys = m.dashboard_keys.to_string();
+        if let Some(status) = &self.status {
+            keys = format!("{}   {}", keys, status);
+        }
+        frame.render_widget(
+            Paragraph::new(keys).style(Style::new().add_modifier(Modifier::DIM)),
+            footer,
+        );
+    }
+
+    fn draw_header(&self, frame: &mut Frame, area: Rect) {
+        let m = self.lang.messages();
+        let mut spans = vec![Span::styled(
+            "Popocatépetl",
+            Style::new().add_modifier(Modifier::BOLD),
+        )];
+        if let Some(date) = self.selected_date() {
+            spans.push(Span::raw(format!(
+                "  {}  ({}/{})",
+                self.lang.date(date),
+                self.selected + 1,
+                self.dates.len()
+            )));
+        }
+        match self.report() {
+            Some(report) => {
+                if let Some(level) = report.alert_level {
+                    spans.push(Span::raw("   "));
+                    spans.push(Span::styled(
+                        format!(" {} ", self.lang.alert_level(level).to_uppercase()),
+                        self.level_style(level),
+                    ));
+                }
+                if let Some(phase) = &report.alert_phase {
+                    spans.push(Span::raw(format!("  {}", phase)));
+                }
+            }
+            None => spans.push(Span::raw(format!("   {}", m.loading))),
+        }
+        frame.render_widget(
+            Paragraph::new(Line::from(spans)).block(Block::new().borders(Borders::ALL)),
+            area,
+        );
+    }
+
+    fn draw_counters(&self, frame: &mut Frame, area: Rect) {
+        let m = self.lang.messages();
+        let block = Block::new().borders(Borders::ALL).title(m.counters);
+        let Some(report) = self.report() else {
+            frame.render_widget(block, area);
+            return;
+        };
+        let counter = |value: Option<u32>| match value {
+            Some(v) => v.to_string(),
+            None => m.not_reported.to_string(),
+        };
+        let mut rows = vec![
+            (m.exhalations, counter(report.exhalations)),
+            (m.explosions, counter(report.explosions)),
+            (
+                m.volcanotectonic_events,
+                counter(report.volcanotectonic_events),
+            ),
+            (
+                m.tremor_total,
+                format!("{} min", counter(report.tremor_minutes_total)),
+            ),
+        ];
+        if let Some(dir) = report.wind_direction {
+            rows.push((m.wind_direction, self.lang.wind_direction(dir).to_string()));
+        }
+        let lines: Vec<Line> = rows
+            .into_iter()
+            .map(|(label, value)| Line::from(format!("{:<25}{}", format!("{}:", label), value)))
+            .collect();
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn draw_history(&self, frame: &mut Frame, area: Rect) {
+        let m = self.lang.messages();
+        let block = Block::new()
+            .borders(Borders::ALL)
+            .title(fill(m.last_days, &[&HISTORY_DAYS]));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let rows = Layout::vertical([Constraint::Length(3); 3]).split(inner);
+        let series = [
+            (m.exhalations, self.series(|r| r.exhalations), Color::Cyan),
+            (m.explosions, self.series(|r| r.explosions), Color::Red),
+            (
+                m.tremor_total,
+                self.series(|r| r.tremor_minutes_total),
+                Color::Magenta,
+            ),
+        ];
+        for ((label, data, colour), row) in series.into_iter().zip(rows.iter()) {
+            let max = data.iter().flatten().max().copied().unwrap_or(0);
+            // The newest days are the ones worth seeing when the pane is narrow.
+            let shown = &data[data.len().saturating_sub(row.width as usize)..];
+            let (bar, gap) = if self.colour {
+                (Style::new().fg(colour), Style::new().fg(Color::DarkGray))
+            } else {
+                (Style::new(), Style::new())
+            };
+            let block = Block::new().title(format!("{} (max {})", label, max));
+            let height = block.inner(*row).height;
+            frame.render_widget(
+                Paragraph::new(sparkline(shown, height, bar, gap)).block(block),
+                *row,
+            );
+        }
+    }
+
+    /// One value per calendar day of the window, oldest first, with `None`
+    /// for a day with no report or no value.
+    fn series(&self, value: impl Fn(&VolcanoReport) -> Option<u32>) -> Vec<Option<u64>> {
+        let Some(last) = self.selected_date() else {
+            return Vec::new();
+        };
+        (0..HISTORY_DAYS)
+            .rev()
+            .map(|back| last - Duration::days(back))
+            .map(|day| self.reports.get(&day).and_then(&value).map(u64::from))
+            .collect()
+    }
+
+    fn draw_rose(&self, frame: &mut Frame, area: Rect) {
+        let m = self.lang.messages();
+        let rose = WindRose::from_reports(
+            self.window()
+                .iter()
+                .filter_map(|date| self.reports.get(date)),
+        );
+        let widest = rose
+            .petals
+            .iter()
+            .map(|p| p.count)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let lines: Vec<Line> = rose
+            .petals
+            .iter()
+            .map(|petal| {
+                let bar = (f64::from(petal.count) / f64::from(widest) * 12.0).ceil() as usize;
+                Line::from(format!(
+                    "{:<4}{:>3} {}",
+                    format!("{:?}", petal.direction),
+                    petal.count,
+                    "█".repeat(bar)
+                ))
+            })
+            .collect();
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::new().borders(Borders::ALL).title(m.wind_rose)),
+            area,
+        );
+    }
+
+    fn draw_ashfall(&self, frame: &mut Frame, area: Rect) {
+        let m = self.lang.messages();
+        let lines: Vec<Line> = match self.report().map(|r| r.ashfall_places()) {
+            Some(places) if !places.is_empty() => places
+                .resolved
+                .iter()
+                .map(|p| Line::from(p.canonical.clone()))
+                .chain(places.unresolved.iter().map(|name| {
+                    Line::from(Span::styled(
+                        format!("{} ?", name),
+                        Style::new().add_modifier(Modifier::ITALIC),
+                    ))
+                }))
+                .collect(),
+            Some(_) => vec![Line::from(m.no_ashfall)],
+            None => Vec::new(),
+        };
+        frame.render_widget(
+            Paragraph::new(lines)
+                .wrap(Wrap { trim: true })
+                .block(Block::new().borders(Borders::ALL).title(m.ashfall)),
+            area,
+        );
+    }
+
+    fn draw_narrative(&self, frame: &mut Frame, area: Rect) {
+        let m = self.lang.messages();
+        let mut text = Text::default();
+        if let Some(narrative) = self.report().and_then(|r| r.narrative()) {
+            let (note, sections) = match self.narrative_lang {
+                Lang::Es => (
+                    m.narrative_spanish,
+                    [
+                        (m.summary, narrative.observations),
+                        (m.expected_scenarios, narrative.scenarios),
+                        (m.recommendations, narrative.recommendations),
+                    ],
+                ),
+                Lang::En => {
+                    let english = narrative.to_english();
+                    (
+                        m.narrative_english,
+                        [
+                            (m.summary, english.observations),
+                            (m.expected_scenarios, english.scenarios),
+                            (m.recommendations, english.recommendations),
+                        ],
+                    )
+                }
+            };
+            for (i, (title, paragraphs)) in sections.into_iter().enumerate() {
+                if paragraphs.is_empty() {
+                    continue;
+                }
+                // Observations are prose; scenarios and recommendations are
+                // lists.
+                let bullet = if i == 0 { "" } else { "• " };
+                text.lines.push(Line::styled(
+                    format!("{} ({})", title, note),
+                    Style::new().add_modifier(Modifier::BOLD),
+                ));
+                for paragraph in paragraphs {
+                    text.lines
+                        .push(Line::from(format!("{}{}", bullet, paragraph)));
+                }
+                text.lines.push(Line::default());
+            }
+        }
+        frame.render_widget(
+            Paragraph::new(text)
+                .wrap(Wrap { trim: true })
+                .scroll((self.scroll, 0))
+                .block(Block::new().borders(Borders::ALL).title(m.narrative)),
+            area,
+        );
+    }
+
+    fn level_style(&self, level: AlertLevel) -> Style {
+        let style = Style::new().add_modifier(Modifier::BOLD | Modifier::REVERSED);
+        if !self.colour {
+            return style;
+        }
+        style.fg(match level {
+            AlertLevel::Green => Color::Green,
+            AlertLevel::Yellow => Color::Yellow,
+            AlertLevel::Orange => Color::Indexed(208),
+            AlertLevel::Red => Color::Red,
+        })
+    }
+}
+
+/// Puts the terminal back however the dashboard ends.
+struct Screen;
+
+impl Screen {
+    fn enter() -> Result<Self> {
+        terminal::enable_raw_mode().map_err(PopoError::Terminal)?;
+        if let Err(err) = execute!(std::io::stdout(), EnterAlternateScreen) {
+            let _ = terminal::disable_raw_mode();
+            return Err(PopoError::Terminal(err));
+        }
+        Ok(Screen)
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(std::io::stdout(), LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Bars `height` rows tall, one column per value and scaled to the largest.
+/// A day with nothing to show is a dot on the baseline, so it cannot be read
+/// as a quiet day; a zero is an empty column.
+fn sparkline(data: &[Option<u64>], height: u16, bar: Style, gap: Style) -> Vec<Line<'static>> {
+    const EIGHTHS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = data.iter().flatten().max().copied().unwrap_or(0).max(1);
+    let steps = u64::from(height) * 8;
+    (0..u64::from(height))
+        .rev()
+        .map(|level| {
+            let spans: Vec<Span> = data
+                .iter()
+                .map(|value| match value {
+                    Some(value) => {
+                        let filled = ((value * steps + max - 1) / max).saturating_sub(level * 8);
+                        Span::styled(EIGHTHS[filled.min(8) as usize].to_string(), bar)
+                    }
+                    None if level == 0 => Span::styled("·", gap),
+                    None => Span::raw(" "),
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect()
+}
+
+/// Run the dashboard until the user quits, fetching the index again every
+/// `refresh`. Feed errors once it is running go to the status line rather
+/// than ending it.
+pub fn run(
+    feed: &Feed,
+    lang: Lang,
+    narrative_lang: Lang,
+    colour: bool,
+    refresh: std::time::Duration,
+) -> Result<()> {
+    if !std::io::stdout().is_terminal() {
+        return Err(PopoError::Terminal(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "popo tui needs an interactive terminal",
+        )));
+    }
+    let mut dashboard = Dashboard::new(feed.index()?, lang, narrative_lang, colour);
+    let _screen = Screen::enter()?;
+    let mut terminal =
+        Terminal::new(CrosstermBackend::new(std::io::stdout())).map_err(PopoError::Terminal)?;
+    let mut refreshed = Instant::now();
+
+    loop {
+        // Draw before each fetch so the selected day appears as soon as it
+        // arrives, then the history behind it.
+        let mut missing = dashboard.missing().into_iter();
+        loop {
+            terminal
+                .draw(|frame| dashboard.draw(frame))
+                .map_err(PopoError::Terminal)?;
+            if event::poll(std::time::Duration::ZERO).map_err(PopoError::Terminal)? {
+                break;
+            }
+            let Some(date) = missing.next() else {
+                break;
+            };
+            match feed.get(date) {
+                Ok(report) => dashboard.insert(report),
+                // A date the index lists but the feed lacks: leave it empty.
+                Err(PopoError::NotFound(_)) => {}
+                Err(err) => {
+                    dashboard.set_status(err.to_string());
+                    break;
+                }
+            }
+        }
+
+        let wait = refresh.saturating_sub(refreshed.elapsed());
+        let mut action = Action::None;
+        if event::poll(wait).map_err(PopoError::Terminal)? {
+            if let Event::Key(key) = event::read().map_err(PopoError::Terminal)? {
+                action = dashboard.handle(key);
+            }
+        } else {
+            action = Action::Refresh;
+        }
+
+        match action {
+            Action::Quit => return Ok(()),
+            Action::Refresh => {
+                refreshed = Instant::now();
+                match feed.index() {
+                    Ok(index) => {
+                        dashboard.set_index(index);
+                        let now = chrono::Local::now().format("%H:%M");
+                        dashboard.set_status(fill(lang.messages().refreshed, &[&now]));
+                    }
+                    Err(err) => dashboard.set_status(err.to_string()),
+                }
+            }
+            Action::None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+
+    fn index(dates: &[&str]) -> FeedIndex {
+        let dates: Vec<NaiveDate> = dates.iter().map(|d| d.parse().unwrap()).collect();
+        FeedIndex {
+            schema_version: crate::models::SCHEMA_VERSION,
+            updated_at: chrono::Utc::now(),
+            earliest: dates[0],
+            latest: *dates.last().unwrap(),
+            count: dates.len(),
+            dates,
+        }
+    }
+
+    fn report(json: &str) -> VolcanoReport {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn date(text: &str) -> NaiveDate {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn arrows_step_through_the_index() {
+        let mut dashboard = Dashboard::new(
+            index(&["2026-08-20", "2026-08-22", "2026-08-21"]),
+            Lang::En,
+            Lang::Es,
+            false,
+        );
+        assert_eq!(dashboard.selected_date(), Some(date("2026-08-22")));
+        dashboard.handle(key(KeyCode::Right));
+        assert_eq!(dashboard.selected_date(), Some(date("2026-08-22")));
+        dashboard.handle(key(KeyCode::Left));
+        assert_eq!(dashboard.selected_date(), Some(date("2026-08-21")));
+        dashboard.handle(key(KeyCode::Home));
+        assert_eq!(dashboard.selected_date(), Some(date("2026-08-20")));
+        assert_eq!(dashboard.handle(key(KeyCode::Char('r'))), Action::Refresh);
+        assert_eq!(dashboard.handle(key(KeyCode::Char('q'))), Action::Quit);
+    }
+
+    #[test]
+    fn history_covers_sixty_days_up_to_the_selected_one() {
+        let dashboard = Dashboard::new(
+            index(&["2026-06-01", "2026-06-24", "2026-06-25", "2026-08-22"]),
+            Lang::En,
+            Lang::Es,
+            false,
+        );
+        assert_eq!(
+            dashboard.window(),
+            &[date("2026-06-24"), date("2026-06-25"), date("2026-08-22")]
+        );
+        assert_eq!(dashboard.missing()[0], date("2026-08-22"));
+        assert_eq!(dashboard.series(|r| r.exhalations).len(), 60);
+    }
+
+    #[test]
+    fn days_without_a_value_are_gaps_not_zeros() {
+        let mut dashboard = Dashboard::new(
+            index(&["2026-08-20", "2026-08-22"]),
+            Lang::En,
+            Lang::Es,
+            false,
+        );
+        dashboard.insert(report(r#"{"date": "2026-08-20", "exhalations": 0}"#));
+        dashboard.insert(report(r#"{"date": "2026-08-22", "exhalations": 16}"#));
+        let series = dashboard.series(|r| r.exhalations);
+        assert_eq!(&series[57..], &[Some(0), None, Some(16)]);
+
+        let bars = sparkline(&series[57..], 2, Style::new(), Style::new());
+        let text: Vec<String> = bars
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(text, ["  █", " ·█"]);
+    }
+
+    #[test]
+    fn refresh_follows_the_newest_report_only_when_on_it() {
+        let mut dashboard = Dashboard::new(
+            index(&["2026-08-21", "2026-08-22"]),
+            Lang::En,
+            Lang::Es,
+            false,
+        );
+        dashboard.set_index(index(&["2026-08-21", "2026-08-22", "2026-08-23"]));
+        assert_eq!(dashboard.selected_date(), Some(date("2026-08-23")));
+
+        dashboard.handle(key(KeyCode::Home));
+        dashboard.set_index(index(&[
+            "2026-08-21",
+            "2026-08-22",
+            "2026-08-23",
+            "2026-08-24",
+        ]));
+        assert_eq!(dashboard.selected_date(), Some(date("2026-08-21")));
+    }
+
+    #[test]
+    fn draws_the_selected_report() {
+        let mut dashboard = Dashboard::new(
+            index(&["2026-08-21", "2026-08-22"]),
+            Lang::Es,
+            Lang::Es,
+            false,
+        );
+        dashboard.insert(report(
+            r#"{"date": "2026-08-22", "exhalations": 45, "alert_level": "YELLOW",
+                "wind_direction": "W", "ashfall_reports": ["Amecameca"],
+                "summary_spanish": "Se detectaron 45 exhalaciones."}"#,
+        ));
+        let mut terminal = Terminal::new(TestBackend::new(120, 32)).unwrap();
+        terminal.draw(|frame| dashboard.draw(frame)).unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        for expected in [
+            "AMARILLO",
+            "Exhalaciones:",
+            "45",
+            "Oeste",
+            "Amecameca",
+            "Se detectaron",
+        ] {
+            assert!(screen.contains(expected), "missing {}", expected);
+        }
+    }
+}
@@ -0,0 +1,317 @@
+//! Polling the feed for what changed since last time.
+//!
+//! `popo watch` keeps the last report it saw in a small state file, so a
+//! restart does not announce the same report twice. Deleting the file is
+//! harmless: the next poll records a fresh baseline without announcing it.
+
+use crate::error::{PopoError, Result};
+use crate::models::{AlertLevel, VolcanoReport};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::time::Duration;
+
+/// Bumped whenever the state file layout changes; older files are discarded.
+const STATE_VERSION: u32 = 1;
+
+/// What the watcher remembers between polls.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchState {
+    version: u32,
+    feed: String,
+    pub last: Option<VolcanoReport>,
+}
+
+impl WatchState {
+    pub fn new(feed: impl Into<String>) -> Self {
+        Self {
+            version: STATE_VERSION,
+            feed: feed.into(),
+            last: None,
+        }
+    }
+
+    /// Where the state for a given feed lives, under `cache_dir` or the
+    /// platform's cache directory.
+    pub fn default_path(feed: &str, cache_dir: Option<&Path>) -> Option<PathBuf> {
+        crate::paths::cache_dir(cache_dir)
+            .map(|dir| dir.join(format!("watch-{}.json", crate::paths::feed_slug(feed))))
+    }
+
+    /// Load saved state, or start with none if there is no file, it belongs
+    /// to another feed, or it cannot be read.
+    pub fn open(path: &Path, feed: &str) -> Result<Self> {
+        let body = match std::fs::read_to_string(path) {
+            Ok(body) => body,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::new(feed)),
+            Err(e) => {
+                return Err(PopoError::Cache {
+                    path: path.display().to_string(),
+                    source: e,
+                })
+            }
+        };
+        match serde_json::from_str::<WatchState>(&body) {
+            Ok(state) if state.version == STATE_VERSION && state.feed == feed => Ok(state),
+            _ => Ok(Self::new(feed)),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let cache_err = |source| PopoError::Cache {
+            path: path.display().to_string(),
+            source,
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(cache_err)?;
+        }
+        let json = serde_json::to_string(self).map_err(|e| PopoError::Serialize(e.to_string()))?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json).map_err(cache_err)?;
+        std::fs::rename(&tmp, path).map_err(cache_err)
+    }
+
+    /// Record `report` as seen and return how it differs from the previous
+    /// one. Empty on the first poll, which only sets the baseline.
+    pub fn update(&mut self, report: VolcanoReport) -> Vec<Change> {
+        let changes = match &self.last {
+            Some(previous) => changes(previous, &report),
+            None => Vec::new(),
+        };
+        self.last = Some(report);
+        changes
+    }
+}
+
+/// One thing that differs between two reports.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Change {
+    NewReport {
+        previous: NaiveDate,
+        date: NaiveDate,
+    },
+    AlertLevel {
+        from: Option<AlertLevel>,
+        to: Option<AlertLevel>,
+    },
+    AlertPhase {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    Explosions {
+        from: Option<u32>,
+        to: Option<u32>,
+    },
+    /// Places named in the new report and not the previous one.
+    Ashfall {
+        places: Vec<String>,
+    },
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let or_dash = |v: Option<String>| v.unwrap_or_else(|| "-".to_string());
+        match self {
+            Change::NewReport { previous, date } => {
+                write!(f, "New report for {} (previous {})", date, previous)
+            }
+            Change::AlertLevel { from, to } => write!(
+                f,
+                "Alert level {} → {}",
+                or_dash(from.map(|l| format!("{:?}", l).to_uppercase())),
+                or_dash(to.map(|l| format!("{:?}", l).to_uppercase()))
+            ),
+            Change::AlertPhase { from, to } => write!(
+                f,
+                "Alert phase {} → {}",
+                or_dash(from.clone()),
+                or_dash(to.clone())
+            ),
+            Change::Explosions { from, to } => write!(
+                f,
+                "Explosions {} → {}",
+                or_dash(from.map(|n| n.to_string())),
+                or_dash(to.map(|n| n.to_string()))
+            ),
+            Change::Ashfall { places } => write!(f, "Ashfall in {}", places.join("; ")),
+        }
+    }
+}
+
+/// How `current` differs from `previous` in the fields worth announcing.
+pub fn changes(previous: &VolcanoReport, current: &VolcanoReport) -> Vec<Change> {
+    let mut changes = Vec::new();
+    if current.date != previous.date {
+        changes.push(Change::NewReport {
+            previous: previous.date,
+            date: current.date,
+        });
+    }
+    if current.alert_level != previous.alert_level {
+        changes.push(Change::AlertLevel {
+            from: previous.alert_level,
+            to: current.alert_level,
+        });
+    }
+    if current.alert_phase != previous.alert_phase {
+        changes.push(Change::AlertPhase {
+            from: previous.alert_phase.clone(),
+            to: current.alert_phase.clone(),
+        });
+    }
+    if current.explosions != previous.explosions {
+        changes.push(Change::Explosions {
+            from: previous.explosions,
+            to: current.explosions,
+        });
+    }
+    // Canonical names where they resolve, so a respelling is not news.
+    let before = previous.ashfall_places().names();
+    let places: Vec<String> = current
+        .ashfall_places()
+        .names()
+        .into_iter()
+        .filter(|p| !before.contains(p))
+        .collect();
+    if !places.is_empty() {
+        changes.push(Change::Ashfall { places });
+    }
+    changes
+}
+
+/// Read "10m", "90s", "1h" or a bare number of seconds.
+pub fn parse_interval(text: &str) -> Result<Duration> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let scale = match unit.trim() {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        _ => 0,
+    };
+    match number
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(scale))
+    {
+        Some(seconds) if seconds > 0 => Ok(Duration::from_secs(seconds)),
+        _ => Err(PopoError::InvalidInterval(text.to_string())),
+    }
+}
+
+/// Run `command` through the platform shell with `input` on its stdin.
+pub fn run_command(command: &str, input: &str) -> std::io::Result<ExitStatus> {
+    let mut child = if cfg!(windows) {
+        Command::new("cmd")
+            .args(["/C", command])
+            .stdin(Stdio::piped())
+            .spawn()?
+    } else {
+        Command::new("sh")
+            .args(["-c", command])
+            .stdin(Stdio::piped())
+            .spawn()?
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        // A command that exits without reading its input is not our error.
+        match stdin.write_all(input.as_bytes()) {
+            Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => return Err(e),
+            _ => {}
+        }
+    }
+    child.wait()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(date: &str) -> VolcanoReport {
+        let mut report = VolcanoReport::blank(date);
+        report.alert_level = Some(AlertLevel::Yellow);
+        report.alert_phase = Some("AMARILLO FASE 2".into());
+        report.explosions = Some(0);
+        report
+    }
+
+    #[test]
+    fn the_first_poll_only_sets_a_baseline() {
+        let mut state = WatchState::new("test");
+        assert!(state.update(report("2023-05-20")).is_empty());
+        assert_eq!(state.update(report("2023-05-20")), vec![]);
+        let changes = state.update(report("2023-05-21"));
+        assert_eq!(changes.len(), 1);
+        assert_eq!(
+            changes[0].to_string(),
+            "New report for 2023-05-21 (previous 2023-05-20)"
+        );
+    }
+
+    #[test]
+    fn changes_in_alert_explosions_and_ashfall_are_reported() {
+        let before = report("2023-05-20");
+        let mut after = report("2023-05-20");
+        after.alert_phase = Some("AMARILLO FASE 3".into());
+        after.explosions = Some(4);
+        after.ashfall_reports = vec!["Amecameca, Estado de México".into()];
+
+        let summary: Vec<String> = changes(&before, &after)
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                "Alert phase AMARILLO FASE 2 → AMARILLO FASE 3",
+                "Explosions 0 → 4",
+                "Ashfall in Amecameca, Estado de México",
+            ]
+        );
+
+        // Naming the same place again, however spelt, is not a change.
+        let mut again = after.clone();
+        again.ashfall_reports = vec!["Amécameca".into()];
+        assert!(changes(&after, &again).is_empty());
+    }
+
+    #[test]
+    fn intervals_take_a_unit() {
+        assert_eq!(parse_interval("10m").unwrap(), Duration::from_secs(600));
+        assert_eq!(parse_interval("90").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_interval("1h").unwrap(), Duration::from_secs(3600));
+        assert!(parse_interval("0s").is_err());
+        assert!(parse_interval("ten minutes").is_err());
+        assert!(parse_interval("18446744073709551615h").is_err());
+        assert!(parse_interval("99999999999999999999").is_err());
+    }
+
+    #[test]
+    fn state_round_trips_through_disk() {
+        let dir = std::env::temp_dir().join("popo-watch-test-roundtrip");
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("watch.json");
+
+        let mut state = WatchState::new("test");
+        state.update(report("2023-05-20"));
+        state.save(&path).unwrap();
+        let reopened = WatchState::open(&path, "test").unwrap();
+        assert_eq!(reopened.last.unwrap().date.to_string(), "2023-05-20");
+        assert!(WatchState::open(&path, "elsewhere").unwrap().last.is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn commands_get_the_report_on_stdin() {
+        let status = run_command("grep -q 2023-05-20", r#"{"date":"2023-05-20"}"#).unwrap();
+        assert!(status.success());
+        let status = run_command("grep -q 1999", r#"{"date":"2023-05-20"}"#).unwrap();
+        assert!(!status.success());
+    }
+}
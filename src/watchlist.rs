@@ -0,0 +1,163 @@
+//! Towns to keep an eye on.
+//!
+//! A watchlist is a comma-separated list of place names, given on the command
+//! line or as the `watchlist` setting (or `POPO_WATCHLIST`). A watched name that the gazetteer knows
+//! matches a report when it resolves to the same municipality as one of the
+//! report's ashfall places. A name it does not know matches when its words
+//! appear together in the raw ashfall text, with accents and case ignored,
+//! which keeps towns outside the gazetteer watchable.
+
+use crate::gazetteer;
+use crate::models::VolcanoReport;
+use crate::text::{fold, tokens};
+use chrono::NaiveDate;
+use serde::Serialize;
+
+/// Environment variable holding the default watchlist.
+pub const WATCHLIST_ENV: &str = "POPO_WATCHLIST";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Watchlist {
+    pub towns: Vec<String>,
+}
+
+/// A report that named at least one watched town.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WatchHit {
+    pub date: NaiveDate,
+    /// The watched names that matched, as the watchlist spells them.
+    pub towns: Vec<String>,
+    pub ashfall_reports: Vec<String>,
+}
+
+impl Watchlist {
+    /// "Amecameca, Atlixco" → two towns. Blank entries are dropped.
+    pub fn parse(list: &str) -> Self {
+        Watchlist {
+            towns: list
+                .split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(String::from)
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.towns.is_empty()
+    }
+
+    /// The watched towns named in `report`'s ashfall reports.
+    pub fn matches(&self, report: &VolcanoReport) -> Vec<String> {
+        if report.ashfall_reports.is_empty() {
+            return Vec::new();
+        }
+        let places = report.ashfall_places();
+        let raw = fold(&report.ashfall_reports.join(" | "));
+
+        self.towns
+            .iter()
+            .filter(|town| match gazetteer::lookup(town, None) {
+                // A state name or a longer municipality containing this one
+                // must not count, so known towns match by resolution only.
+                Some(m) => places
+                    .resolved
+                    .iter()
+                    .any(|p| p.municipality == m.name && p.state == m.state),
+                None => contains_words(&raw, &fold(town)),
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Every report that named a watched town, in the order given.
+    pub fn scan(&self, reports: &[VolcanoReport]) -> Vec<WatchHit> {
+        reports
+            .iter()
+            .filter_map(|report| {
+                let towns = self.matches(report);
+                (!towns.is_empty()).then(|| WatchHit {
+                    date: report.date,
+                    towns,
+                    ashfall_reports: report.ashfall_reports.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Whether the words of `needle` appear one after another in `haystack`, both
+/// already folded.
+fn contains_words(haystack: &str, needle: &str) -> bool {
+    let words = |text: &str| -> Vec<String> { tokens(text).into_iter().map(|t| t.text).collect() };
+    let (haystack, needle) = (words(haystack), words(needle));
+    !needle.is_empty()
+        && haystack
+            .windows(needle.len())
+            .any(|run| run == needle.as_slice())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(ashfall: &[&str]) -> VolcanoReport {
+        let mut report = VolcanoReport::blank("2023-05-21");
+        report.ashfall_reports = ashfall.iter().map(|s| s.to_string()).collect();
+        report
+    }
+
+    #[test]
+    fn parse_trims_and_drops_blanks() {
+        let list = Watchlist::parse(" Amecameca, ,Atlixco ,");
+        assert_eq!(list.towns, vec!["Amecameca", "Atlixco"]);
+    }
+
+    #[test]
+    fn matches_ignore_accents_and_case() {
+        let list = Watchlist::parse("tetela del volcan,Atlixco");
+        let hits = list.matches(&report(&["Tétela del Volcán, Morelos"]));
+        assert_eq!(hits, vec!["tetela del volcan"]);
+    }
+
+    #[test]
+    fn misspelt_watch_names_still_resolve() {
+        let list = Watchlist::parse("Chilchotla");
+        let hits = list.matches(&report(&["Chichiquila y Chilchota, del estado de Puebla"]));
+        assert_eq!(hits, vec!["Chilchotla"]);
+    }
+
+    #[test]
+    fn towns_outside_the_gazetteer_match_the_raw_text() {
+        let list = Watchlist::parse("San Juan Tehuixtitlán");
+        let hits = list.matches(&report(&["Atlautla y San Juan Tehuixtitlan"]));
+        assert_eq!(hits.len(), 1);
+        assert!(list.matches(&report(&[])).is_empty());
+
+        let list = Watchlist::parse("San Juan");
+        assert!(list
+            .matches(&report(&["Atlautla y San Juanito Tehuixtitlan"]))
+            .is_empty());
+    }
+
+    #[test]
+    fn state_names_do_not_match_the_city() {
+        let list = Watchlist::parse("Puebla");
+        assert!(list
+            .matches(&report(&["Atlixco, Puebla", "Huejotzingo, Puebla"]))
+            .is_empty());
+        assert_eq!(list.matches(&report(&["Puebla, Puebla"])), vec!["Puebla"]);
+    }
+
+    #[test]
+    fn longer_names_do_not_match_the_shorter_town() {
+        let list = Watchlist::parse("Chalco");
+        assert!(list
+            .matches(&report(&["Valle de Chalco Solidaridad, Estado de México"]))
+            .is_empty());
+        assert_eq!(
+            list.matches(&report(&["Chalco, Estado de México"])),
+            vec!["Chalco"]
+        );
+    }
+}
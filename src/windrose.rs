@@ -0,0 +1,166 @@
+//! Frequency of plume directions over a span of reports.
+//!
+//! Only full reports count. A partial record carries counters harvested from
+//! another day's chart window and never a wind direction, so including it
+//! would inflate the "unreported" bucket with days that were never observed.
+
+use crate::models::{VolcanoReport, WindDirection};
+use chrono::Datelike;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// One compass point of a [`WindRose`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Petal {
+    pub direction: WindDirection,
+    pub count: u32,
+    /// Share of the days that stated a direction, from 0.0 to 1.0.
+    pub frequency: f64,
+}
+
+/// How often the plume headed each way across a set of reports.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WindRose {
+    /// Full reports that stated a wind direction.
+    pub observed: u32,
+    /// Full reports that did not.
+    pub unreported: u32,
+    /// Always sixteen entries, clockwise from north.
+    pub petals: Vec<Petal>,
+}
+
+impl WindRose {
+    /// Count wind directions, skipping partial records.
+    pub fn from_reports<'a>(reports: impl IntoIterator<Item = &'a VolcanoReport>) -> Self {
+        let mut counts = [0u32; 16];
+        let mut unreported = 0;
+
+        for report in reports.into_iter().filter(|r| r.is_full()) {
+            match report.wind_direction {
+                Some(dir) => counts[dir as usize] += 1,
+                None => unreported += 1,
+            }
+        }
+
+        let observed: u32 = counts.iter().sum();
+        let petals = WindDirection::ALL
+            .iter()
+            .zip(counts)
+            .map(|(&direction, count)| Petal {
+                direction,
+                count,
+                frequency: if observed == 0 {
+                    0.0
+                } else {
+                    f64::from(count) / f64::from(observed)
+                },
+            })
+            .collect();
+
+        Self {
+            observed,
+            unreported,
+            petals,
+        }
+    }
+
+    /// One rose per calendar month (1 to 12), pooling every year in the input.
+    /// Months with no full reports are left out.
+    pub fn by_month<'a>(
+        reports: impl IntoIterator<Item = &'a VolcanoReport>,
+    ) -> BTreeMap<u32, WindRose> {
+        let mut months: BTreeMap<u32, Vec<&VolcanoReport>> = BTreeMap::new();
+        for report in reports.into_iter().filter(|r| r.is_full()) {
+            months.entry(report.date.month()).or_default().push(report);
+        }
+        months
+            .into_iter()
+            .map(|(month, reports)| (month, WindRose::from_reports(reports)))
+            .collect()
+    }
+
+    pub fn count(&self, direction: WindDirection) -> u32 {
+        self.petals[direction as usize].count
+    }
+
+    /// The most frequent direction, if any day stated one. Ties go to the
+    /// first point clockwise from north.
+    pub fn prevailing(&self) -> Option<WindDirection> {
+        self.petals
+            .iter()
+            .filter(|p| p.count > 0)
+            .fold(None::<&Petal>, |best, p| match best {
+                Some(b) if b.count >= p.count => Some(b),
+                _ => Some(p),
+            })
+            .map(|p| p.direction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(date: &str, wind: Option<WindDirection>, partial: bool) -> VolcanoReport {
+        VolcanoReport {
+            wind_direction: wind,
+            partial,
+            ..VolcanoReport::blank(date)
+        }
+    }
+
+    #[test]
+    fn counts_directions_and_frequencies() {
+        let reports = vec![
+            report("2023-01-01", Some(WindDirection::W), false),
+            report("2023-01-02", Some(WindDirection::W), false),
+            report("2023-01-03", Some(WindDirection::NE), false),
+            report("2023-01-04", None, false),
+        ];
+        let rose = WindRose::from_reports(&reports);
+
+        assert_eq!(rose.petals.len(), 16);
+        assert_eq!(rose.observed, 3);
+        assert_eq!(rose.unreported, 1);
+        assert_eq!(rose.count(WindDirection::W), 2);
+        assert_eq!(rose.count(WindDirection::NE), 1);
+        assert!((rose.petals[WindDirection::W as usize].frequency - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(rose.prevailing(), Some(WindDirection::W));
+    }
+
+    /// Partial records never carry wind, so they must not count as unreported.
+    #[test]
+    fn ignores_partial_days() {
+        let reports = vec![
+            report("2023-01-01", Some(WindDirection::S), false),
+            report("2023-01-02", None, true),
+            report("2023-01-03", None, true),
+        ];
+        let rose = WindRose::from_reports(&reports);
+        assert_eq!(rose.observed, 1);
+        assert_eq!(rose.unreported, 0);
+    }
+
+    #[test]
+    fn empty_input_has_no_prevailing_direction() {
+        let rose = WindRose::from_reports(&[]);
+        assert_eq!(rose.observed, 0);
+        assert_eq!(rose.prevailing(), None);
+        assert!(rose.petals.iter().all(|p| p.frequency == 0.0));
+    }
+
+    #[test]
+    fn groups_by_calendar_month_across_years() {
+        let reports = vec![
+            report("2022-06-10", Some(WindDirection::NE), false),
+            report("2023-06-11", Some(WindDirection::NE), false),
+            report("2023-12-01", Some(WindDirection::W), false),
+            report("2023-03-01", None, true),
+        ];
+        let months = WindRose::by_month(&reports);
+
+        assert_eq!(months.keys().copied().collect::<Vec<_>>(), vec![6, 12]);
+        assert_eq!(months[&6].count(WindDirection::NE), 2);
+        assert_eq!(months[&12].prevailing(), Some(WindDirection::W));
+    }
+}
@@ -0,0 +1,107 @@
+//! Exit statuses of the `popo` binary, which scripts and cron jobs branch on.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+
+/// A feed whose latest report has `alert_level`, or none at all.
+fn feed_with_alert(name: &str, alert_level: Option<&str>) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("popo-cli-test-{}", name));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let level = alert_level.map_or(String::new(), |level| {
+        format!(r#""alert_level": "{}", "#, level)
+    });
+    std::fs::write(
+        dir.join("latest.json"),
+        format!(
+            r#"{{ {}"date": "2023-05-21", "ingested_at": "2023-05-21T15:00:00Z" }}"#,
+            level
+        ),
+    )
+    .unwrap();
+    dir
+}
+
+/// `popo` with `args` against the feed at `feed`, away from any config.
+fn popo(feed: &str, args: &[&str]) -> i32 {
+    let home = std::env::temp_dir().join("popo-cli-test-home");
+    std::fs::create_dir_all(&home).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_popo"))
+        .args(["--feed", feed])
+        .args(args)
+        .current_dir(&home)
+        .env("POPO_CONFIG", home.join("none.toml"))
+        .env("POPO_CACHE_DIR", home.join("cache"))
+        .output()
+        .unwrap();
+    output.status.code().unwrap()
+}
+
+fn path(dir: &Path) -> &str {
+    dir.to_str().unwrap()
+}
+
+#[test]
+fn fail_at_exits_3_at_or_above_the_threshold() {
+    let feed = feed_with_alert("orange", Some("ORANGE"));
+    assert_eq!(popo(path(&feed), &["alert", "--fail-at", "red"]), 0);
+    assert_eq!(popo(path(&feed), &["alert", "--fail-at", "orange"]), 3);
+    assert_eq!(popo(path(&feed), &["alert", "--fail-at", "amarillo"]), 3);
+    assert_eq!(
+        popo(path(&feed), &["alert", "--quiet", "--fail-at", "yellow"]),
+        3
+    );
+}
+
+#[test]
+fn no_alert_status_exits_4() {
+    let feed = feed_with_alert("no-status", None);
+    assert_eq!(popo(path(&feed), &["alert", "--fail-at", "yellow"]), 4);
+    assert_eq!(popo(path(&feed), &["alert", "--quiet"]), 4);
+}
+
+#[test]
+fn a_feed_missing_its_report_exits_6() {
+    let dir = std::env::temp_dir().join("popo-cli-test-empty");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    assert_eq!(popo(path(&dir), &["alert"]), 6);
+}
+
+#[test]
+fn a_failing_server_exits_5_like_an_unreachable_one() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            write!(
+                stream,
+                "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            )
+            .unwrap();
+        }
+    });
+    assert_eq!(popo(&url, &["alert"]), 5);
+}
+
+#[test]
+fn template_is_refused_by_commands_that_do_not_render_one() {
+    let feed = feed_with_alert("template", Some("YELLOW"));
+    assert_eq!(
+        popo(path(&feed), &["index", "--template", "missing.tera"]),
+        2
+    );
+    assert_eq!(
+        popo(path(&feed), &["alert", "--template", "missing.tera"]),
+        1
+    );
+}
@@ -0,0 +1,129 @@
+//! Email delivery against a minimal SMTP sink on localhost. Point
+//! `POPO_SMTP_HOST` at MailHog (`POPO_SMTP_PORT=1025 POPO_SMTP_TLS=none`) to
+//! see the same messages rendered.
+
+use popo_cli::{Email, Notification, SmtpConfig, SmtpTls, VolcanoReport};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::thread;
+
+/// The envelope and message the sink accepted.
+struct Delivery {
+    commands: Vec<String>,
+    data: String,
+}
+
+/// Accept one SMTP session, answering every command with success.
+fn smtp_sink() -> (u16, mpsc::Receiver<Delivery>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut stream = stream;
+        let mut commands = Vec::new();
+        let mut data = String::new();
+        write!(stream, "220 sink ESMTP\r\n").unwrap();
+
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).unwrap() == 0 {
+                break;
+            }
+            let command = line.trim_end().to_string();
+            let verb = command
+                .split_whitespace()
+                .next()
+                .unwrap_or("")
+                .to_uppercase();
+            commands.push(command);
+            match verb.as_str() {
+                "EHLO" => write!(stream, "250-sink\r\n250 8BITMIME\r\n").unwrap(),
+                "DATA" => {
+                    write!(stream, "354 go ahead\r\n").unwrap();
+                    loop {
+                        let mut line = String::new();
+                        reader.read_line(&mut line).unwrap();
+                        if line == ".\r\n" {
+                            break;
+                        }
+                        data.push_str(&line);
+                    }
+                    write!(stream, "250 queued\r\n").unwrap();
+                }
+                "QUIT" => {
+                    write!(stream, "221 bye\r\n").unwrap();
+                    break;
+                }
+                _ => write!(stream, "250 ok\r\n").unwrap(),
+            }
+        }
+        tx.send(Delivery { commands, data }).unwrap();
+    });
+
+    (port, rx)
+}
+
+fn report() -> VolcanoReport {
+    serde_json::from_str(
+        r#"{"date": "2026-08-22", "alert_level": "YELLOW", "alert_phase": "AMARILLO FASE 2",
+            "exhalations": 6, "wind_direction": "W", "ashfall_reports": ["Atlixco, Puebla"]}"#,
+    )
+    .unwrap()
+}
+
+fn sink_config(port: u16) -> SmtpConfig {
+    let mut config = SmtpConfig::new("127.0.0.1", SmtpTls::None, "popo@example.org");
+    config.port = port;
+    config
+}
+
+#[test]
+fn alert_is_delivered_to_every_recipient_as_text_and_html() {
+    let (port, rx) = smtp_sink();
+    let report = report();
+    let email = Email::alert(&Notification {
+        report: &report,
+        reasons: vec!["New report for 2026-08-22 (previous 2026-08-21)".to_string()],
+    });
+    let to = vec![
+        "ops@example.org".to_string(),
+        "Civil Protection <pc@example.org>".to_string(),
+    ];
+    sink_config(port).send(&email, &to).unwrap();
+
+    let got = rx.recv().unwrap();
+    assert!(got
+        .commands
+        .iter()
+        .any(|c| c == "MAIL FROM:<popo@example.org>"));
+    assert!(got
+        .commands
+        .iter()
+        .any(|c| c == "RCPT TO:<ops@example.org>"));
+    assert!(got.commands.iter().any(|c| c == "RCPT TO:<pc@example.org>"));
+    assert!(got.data.contains("multipart/alternative"));
+    assert!(got.data.contains("text/plain"));
+    assert!(got.data.contains("text/html"));
+}
+
+#[test]
+fn digest_is_delivered() {
+    let (port, rx) = smtp_sink();
+    let email = Email::digest(&[report()]);
+    sink_config(port)
+        .send(&email, &["ops@example.org".to_string()])
+        .unwrap();
+
+    let got = rx.recv().unwrap();
+    assert!(got.data.contains("Digest for 2026-08-22"));
+}
+
+#[test]
+fn no_recipients_is_an_error() {
+    let err = sink_config(1).send(&Email::digest(&[]), &[]).unwrap_err();
+    assert!(err.to_string().contains("no recipients"));
+}
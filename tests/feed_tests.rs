@@ -4,7 +4,9 @@
 //! the ignored smoke test at the bottom.
 
 use chrono::{NaiveDate, Utc};
-use popo_cli::{AlertLevel, Feed, PopoError, WindDirection};
+use popo_cli::{
+    AlertLevel, Feed, FeedComparison, PopoError, Query, SearchIndex, WindDirection, WindRose,
+};
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -198,6 +200,105 @@ fn index_describes_coverage() {
     assert_eq!(index.dates.len(), 3);
 }
 
+#[test]
+fn range_reads_every_indexed_day_between_bounds() {
+    let dir = build_feed("range");
+    let feed = feed_at(&dir);
+    let from = NaiveDate::from_ymd_opt(2022, 1, 1).unwrap();
+    let to = NaiveDate::from_ymd_opt(2026, 12, 31).unwrap();
+
+    let reports = feed.range(from, to).unwrap();
+    let dates: Vec<_> = reports.iter().map(|r| r.date.to_string()).collect();
+    assert_eq!(dates, vec!["2022-04-27", "2026-08-04"]);
+
+    let rose = WindRose::from_reports(&reports);
+    assert_eq!(rose.count(WindDirection::SE), 2);
+}
+
+#[test]
+fn backwards_range_is_rejected() {
+    let dir = build_feed("backwards");
+    let from = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let to = NaiveDate::from_ymd_opt(2022, 1, 1).unwrap();
+
+    assert!(matches!(
+        feed_at(&dir).range(from, to),
+        Err(PopoError::InvalidRange { .. })
+    ));
+}
+
+/// A refreshed index only fetches days it has not seen, and reports without a
+/// narrative are remembered rather than refetched.
+#[test]
+fn search_index_refreshes_incrementally() {
+    let dir = build_feed("search");
+    let feed = feed_at(&dir);
+    let mut index = SearchIndex::new(feed.base());
+
+    assert_eq!(index.refresh(&feed).unwrap(), 3);
+    assert_eq!(index.refresh(&feed).unwrap(), 0);
+    // 2001-01-05 has no narrative, so only two are searchable.
+    assert_eq!(index.len(), 2);
+
+    let hits = index.search(&Query::parse("EXHALACIÓN").unwrap());
+    assert_eq!(hits.len(), 2);
+    assert_eq!(hits[0].date, NaiveDate::from_ymd_opt(2026, 8, 4).unwrap());
+}
+
+/// A mirror ingested at another time matches; a changed report or a date only
+/// one index lists is divergence.
+#[test]
+fn compare_feeds_finds_divergence() {
+    let upstream = build_feed("compare-upstream");
+    let mirror = build_feed("compare-mirror");
+    let (a, b) = (feed_at(&upstream), feed_at(&mirror));
+    fs::write(
+        mirror.join("reports/2026/2026-08-04.json"),
+        report_json("2026-08-04", "160", "").replace("2026-08-05T17:04", "2026-08-07T09:00"),
+    )
+    .unwrap();
+    let same = FeedComparison::run(&a, &b, None, None).unwrap();
+    assert!(!same.is_divergent());
+    assert_eq!(same.compared, 3);
+
+    fs::write(
+        mirror.join("reports/2022/2022-04-27.json"),
+        report_json("2022-04-27", "41", ""),
+    )
+    .unwrap();
+    let index = fs::read_to_string(mirror.join("index.json")).unwrap();
+    fs::write(
+        mirror.join("index.json"),
+        index.replace(r#""2001-01-05", "#, r#""2019-05-01", "#),
+    )
+    .unwrap();
+    let diverged = FeedComparison::run(&a, &b, None, None).unwrap();
+    assert!(diverged.is_divergent());
+    assert_eq!(diverged.compared, 2);
+    assert_eq!(
+        diverged.only_in_a,
+        vec![NaiveDate::from_ymd_opt(2001, 1, 5).unwrap()]
+    );
+    assert_eq!(
+        diverged.only_in_b,
+        vec![NaiveDate::from_ymd_opt(2019, 5, 1).unwrap()]
+    );
+    assert_eq!(
+        diverged.differing[0].explain(),
+        vec![
+            "Exhalations 40 → 41 (+1)",
+            "SO₂ t/day 2603 → -",
+            "SO₂ measured 2022-04-25 → -"
+        ]
+    );
+
+    // A date outside the range is not compared.
+    let from = NaiveDate::from_ymd_opt(2026, 1, 1);
+    assert!(!FeedComparison::run(&a, &b, from, None)
+        .unwrap()
+        .is_divergent());
+}
+
 /// Live check against the published feed. Ignored by default so the suite stays
 /// offline and deterministic; run with `cargo test -- --ignored`.
 #[test]
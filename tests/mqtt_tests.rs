@@ -0,0 +1,139 @@
+//! MQTT publishing against a minimal broker on localhost that speaks just
+//! enough of MQTT 3.1.1 to accept a connection and its publishes.
+
+use popo_cli::mqtt::messages;
+use popo_cli::{MqttConfig, VolcanoReport};
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc;
+use std::thread;
+
+/// One PUBLISH as the broker saw it.
+#[derive(Debug)]
+struct Published {
+    topic: String,
+    payload: String,
+    qos: u8,
+    retain: bool,
+}
+
+fn read_packet(stream: &mut TcpStream) -> Option<(u8, Vec<u8>)> {
+    let mut header = [0u8; 1];
+    stream.read_exact(&mut header).ok()?;
+    let (mut length, mut shift) = (0usize, 0);
+    loop {
+        let mut byte = [0u8; 1];
+        stream.read_exact(&mut byte).ok()?;
+        length |= usize::from(byte[0] & 0x7f) << shift;
+        if byte[0] & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    let mut body = vec![0; length];
+    stream.read_exact(&mut body).ok()?;
+    Some((header[0], body))
+}
+
+/// Accept one client and report every PUBLISH until it disconnects.
+fn stub_broker() -> (u16, mpsc::Receiver<Vec<Published>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut published = Vec::new();
+        while let Some((header, body)) = read_packet(&mut stream) {
+            match header >> 4 {
+                // CONNECT → CONNACK, session not present, accepted.
+                1 => stream.write_all(&[0x20, 0x02, 0x00, 0x00]).unwrap(),
+                // PUBLISH
+                3 => {
+                    let qos = (header >> 1) & 0x03;
+                    let topic_len = usize::from(u16::from_be_bytes([body[0], body[1]]));
+                    let topic = String::from_utf8(body[2..2 + topic_len].to_vec()).unwrap();
+                    let mut rest = &body[2 + topic_len..];
+                    if qos > 0 {
+                        let id = [rest[0], rest[1]];
+                        rest = &rest[2..];
+                        let ack = if qos == 1 { 0x40 } else { 0x50 };
+                        stream.write_all(&[ack, 0x02, id[0], id[1]]).unwrap();
+                    }
+                    published.push(Published {
+                        topic,
+                        payload: String::from_utf8(rest.to_vec()).unwrap(),
+                        qos,
+                        retain: header & 0x01 == 1,
+                    });
+                }
+                // PUBREL → PUBCOMP
+                6 => stream.write_all(&[0x70, 0x02, body[0], body[1]]).unwrap(),
+                // PINGREQ → PINGRESP
+                12 => stream.write_all(&[0xd0, 0x00]).unwrap(),
+                // DISCONNECT
+                14 => break,
+                _ => {}
+            }
+        }
+        tx.send(published).unwrap();
+    });
+
+    (port, rx)
+}
+
+fn report() -> VolcanoReport {
+    serde_json::from_str(r#"{"date": "2026-08-22", "alert_level": "YELLOW", "exhalations": 6}"#)
+        .unwrap()
+}
+
+fn publish_at(qos: u8) -> Vec<Published> {
+    let (port, rx) = stub_broker();
+    let mut config = MqttConfig::parse_broker(&format!("mqtt://127.0.0.1:{}", port)).unwrap();
+    config.qos = qos;
+    config
+        .publish(&messages(&report(), "popo").unwrap())
+        .unwrap();
+    rx.recv().unwrap()
+}
+
+#[test]
+fn every_topic_arrives_retained() {
+    let published = publish_at(0);
+    assert_eq!(published.len(), 11);
+    assert!(published.iter().all(|p| p.retain && p.qos == 0));
+
+    let alert = published
+        .iter()
+        .find(|p| p.topic == "popo/alert_level")
+        .unwrap();
+    assert_eq!(alert.payload, "YELLOW");
+    let whole = published.iter().find(|p| p.topic == "popo/report").unwrap();
+    assert!(whole.payload.contains("\"exhalations\":6"));
+}
+
+#[test]
+fn qos_1_waits_for_acknowledgements() {
+    let published = publish_at(1);
+    assert_eq!(published.len(), 11);
+    assert!(published.iter().all(|p| p.qos == 1));
+}
+
+#[test]
+fn qos_2_completes_the_handshake() {
+    let published = publish_at(2);
+    assert_eq!(published.len(), 11);
+    assert!(published.iter().all(|p| p.qos == 2));
+}
+
+#[test]
+fn unreachable_broker_is_an_error() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+    let config = MqttConfig::parse_broker(&format!("127.0.0.1:{}", port)).unwrap();
+    let err = config
+        .publish(&messages(&report(), "popo").unwrap())
+        .unwrap_err();
+    assert!(err.to_string().starts_with("MQTT error"));
+}
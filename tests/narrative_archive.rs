@@ -0,0 +1,213 @@
+//! Narrative parsing and event extraction, run over every report in the
+//! repository's own `data/` archive.
+//!
+//! The rules in `src/events.rs` were written against a handful of sentence
+//! shapes; this is what keeps them honest against the other few thousand.
+//! Skipped when `data/` is absent, as it is in the published crate.
+
+use popo_cli::{EventKind, Feed, VolcanoReport};
+use regex::Regex;
+use std::path::Path;
+
+fn archive() -> Option<Vec<VolcanoReport>> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("data");
+    if !dir.join("index.json").exists() {
+        eprintln!("no data/ archive, skipping");
+        return None;
+    }
+    let feed = Feed::with_base(dir.to_str().unwrap());
+    let index = feed.index().unwrap();
+    Some(
+        index
+            .dates
+            .iter()
+            .map(|d| feed.get(*d).unwrap())
+            .filter(|r| r.summary_spanish.is_some())
+            .collect(),
+    )
+}
+
+#[test]
+fn no_link_boilerplate_survives_parsing() {
+    let Some(reports) = archive() else { return };
+    for report in &reports {
+        let narrative = report.narrative().unwrap();
+        for paragraph in narrative.paragraphs() {
+            assert!(
+                !paragraph.starts_with("Mapa de Peligros")
+                    && !paragraph.starts_with("Monitoreo volcán")
+                    && paragraph != "Semáforo de alerta volcánica",
+                "{}: boilerplate kept: {paragraph}",
+                report.date
+            );
+        }
+    }
+}
+
+#[test]
+fn every_span_points_at_its_evidence() {
+    let Some(reports) = archive() else { return };
+    for report in &reports {
+        let summary = report.summary_spanish.as_deref().unwrap();
+        for event in report.events() {
+            let Some(source) = summary.get(event.span.start..event.span.end) else {
+                panic!("{}: span {:?} is not a slice", report.date, event.span);
+            };
+            let source = source.to_lowercase();
+            let expected = match event.kind {
+                EventKind::Explosion { .. } => "explosi",
+                EventKind::AshColumn { .. } => "columna",
+                EventKind::IncandescentFragments => "incandescentes",
+                EventKind::Volcanotectonic { .. } => "volcanotect",
+                EventKind::ExclusionRadius { .. } => "radio",
+                EventKind::Media { .. } => "(",
+            };
+            assert!(
+                source.contains(expected),
+                "{}: {:?} read from {source:?}",
+                report.date,
+                event.kind
+            );
+        }
+    }
+}
+
+/// Whenever the counters say explosions happened and the narrative has
+/// observations to read, at least one explosion is extracted.
+#[test]
+fn explosions_in_the_counters_are_found_in_the_narrative() {
+    let Some(reports) = archive() else { return };
+    let mut checked = 0;
+    for report in &reports {
+        let observed = report
+            .narrative()
+            .is_some_and(|n| !n.observations.is_empty());
+        if report.explosions.unwrap_or(0) == 0 || !observed {
+            continue;
+        }
+        checked += 1;
+        assert!(
+            report
+                .events()
+                .iter()
+                .any(|e| matches!(e.kind, EventKind::Explosion { .. })),
+            "{}: {:?} explosions counted, none extracted",
+            report.date,
+            report.explosions
+        );
+    }
+    assert!(checked > 0);
+}
+
+/// Looser than the rule in `src/events.rs` on purpose: any sentence that
+/// mentions a "radio de" and a distance in km must yield a radius of one of
+/// the distances it gives.
+#[test]
+fn every_stated_radius_is_extracted() {
+    let Some(reports) = archive() else { return };
+    let distance = Regex::new(r"(\d+(?:[.,]\d+)?)\s*(?:km\b|kil[oó]metros)").unwrap();
+    let mut checked = 0;
+    for report in &reports {
+        let summary = report.summary_spanish.as_deref().unwrap();
+        let extracted: Vec<f64> = report
+            .events()
+            .iter()
+            .filter_map(|e| match e.kind {
+                EventKind::ExclusionRadius { km } => Some(km),
+                _ => None,
+            })
+            .collect();
+        for sentence in summary.split(['\n', ';']).flat_map(|l| l.split(". ")) {
+            if !sentence.to_lowercase().contains("radio de") {
+                continue;
+            }
+            let figures: Vec<f64> = distance
+                .captures_iter(sentence)
+                .map(|c| c[1].replace(',', ".").parse().unwrap())
+                .collect();
+            if figures.is_empty() {
+                continue;
+            }
+            checked += 1;
+            assert!(
+                figures.iter().any(|km| extracted.contains(km)),
+                "{}: {:?} gives {:?}, extracted {:?}",
+                report.date,
+                sentence,
+                figures,
+                extracted
+            );
+        }
+    }
+    assert!(checked > 0);
+}
+
+/// One report read by hand, so the test above cannot pass by extracting
+/// nothing from anything.
+#[test]
+fn the_radius_of_a_known_report() {
+    let Some(reports) = archive() else { return };
+    let report = reports
+        .iter()
+        .find(|r| r.date.to_string() == "2023-03-05")
+        .unwrap();
+    assert!(report
+        .events()
+        .iter()
+        .any(|e| e.kind == EventKind::ExclusionRadius { km: 12.0 }));
+}
+
+/// Pages captured with only the scenario list describe nothing that happened.
+#[test]
+fn scenario_only_pages_yield_no_activity() {
+    let Some(reports) = archive() else { return };
+    for report in &reports {
+        let narrative = report.narrative().unwrap();
+        if !narrative.observations.is_empty() {
+            continue;
+        }
+        for event in report.events() {
+            assert!(
+                matches!(event.kind, EventKind::ExclusionRadius { .. }),
+                "{}: {:?} from a scenario bullet",
+                report.date,
+                event.kind
+            );
+        }
+    }
+}
+
+/// The scenario and recommendation lists are fixed text, so every bullet the
+/// archive has ever carried should render in full.
+#[test]
+fn standing_lists_render_completely_in_english() {
+    let Some(reports) = archive() else { return };
+    for report in &reports {
+        let narrative = report.narrative().unwrap();
+        for item in narrative.scenarios.iter().chain(&narrative.recommendations) {
+            let rendered = popo_cli::translate::render(item);
+            assert!(
+                rendered.is_complete(),
+                "{}: {item:?} rendered as {:?}",
+                report.date,
+                rendered.text
+            );
+        }
+    }
+}
+
+/// Every town CENAPRED has named in an ashfall report is in the gazetteer.
+#[test]
+fn every_archived_ashfall_place_resolves() {
+    let Some(reports) = archive() else { return };
+    for report in &reports {
+        let places = report.ashfall_places();
+        assert!(
+            places.unresolved.is_empty(),
+            "{}: {:?} from {:?}",
+            report.date,
+            places.unresolved,
+            report.ashfall_reports
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! Webhook delivery against a stub HTTP server on localhost.
+
+use popo_cli::notify::{sign, transaction_id, SIGNATURE_HEADER};
+use popo_cli::{Notification, VolcanoReport, Webhook, WebhookKind};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::thread;
+
+/// What the stub saw of one request.
+struct Received {
+    request_line: String,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl Received {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Answer one request with `status` and hand back what was sent.
+fn stub_server(status: &'static str) -> (String, mpsc::Receiver<Received>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+
+        let mut headers = Vec::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            let (k, v) = line.split_once(':').unwrap();
+            headers.push((k.trim().to_string(), v.trim().to_string()));
+        }
+        let length: usize = headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case("content-length"))
+            .map(|(_, v)| v.parse().unwrap())
+            .unwrap_or(0);
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+
+        let mut stream = stream;
+        write!(
+            stream,
+            "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            status
+        )
+        .unwrap();
+        tx.send(Received {
+            request_line: request_line.trim_end().to_string(),
+            headers,
+            body: String::from_utf8(body).unwrap(),
+        })
+        .unwrap();
+    });
+
+    (url, rx)
+}
+
+fn report() -> VolcanoReport {
+    serde_json::from_str(
+        r#"{"date": "2026-08-22", "alert_level": "YELLOW", "alert_phase": "AMARILLO FASE 2",
+            "explosions": 2, "ashfall_reports": ["Atlixco, Puebla"]}"#,
+    )
+    .unwrap()
+}
+
+fn send(webhook: &Webhook, report: &VolcanoReport) -> popo_cli::Result<()> {
+    let notification = Notification {
+        report,
+        reasons: vec!["Explosions 0 → 2".to_string()],
+    };
+    webhook.send(&popo_cli::notify::client(), &notification)
+}
+
+#[test]
+fn json_webhooks_are_signed() {
+    let (url, rx) = stub_server("200 OK");
+    let mut webhook = Webhook::new(WebhookKind::Json, url);
+    webhook.secret = Some("s3cret".to_string());
+    send(&webhook, &report()).unwrap();
+
+    let got = rx.recv().unwrap();
+    assert_eq!(got.request_line, "POST /hook HTTP/1.1");
+    assert_eq!(got.header("content-type"), Some("application/json"));
+    assert_eq!(
+        got.header(SIGNATURE_HEADER),
+        Some(sign("s3cret", &got.body).as_str())
+    );
+    let body: serde_json::Value = serde_json::from_str(&got.body).unwrap();
+    assert_eq!(body["alert_level"], "YELLOW");
+    assert_eq!(body["ashfall"][0], "Atlixco, Puebla");
+}
+
+#[test]
+fn unsigned_without_a_secret() {
+    let (url, rx) = stub_server("204 No Content");
+    send(&Webhook::new(WebhookKind::Slack, url), &report()).unwrap();
+
+    let got = rx.recv().unwrap();
+    assert!(got.header(SIGNATURE_HEADER).is_none());
+    assert!(got.body.contains("AMARILLO FASE 2"));
+}
+
+#[test]
+fn matrix_puts_with_a_transaction_id_and_token() {
+    let (url, rx) = stub_server("200 OK");
+    let mut webhook = Webhook::new(WebhookKind::Matrix, url);
+    webhook.token = Some("syt_token".to_string());
+    send(&webhook, &report()).unwrap();
+
+    let got = rx.recv().unwrap();
+    assert!(got.request_line.starts_with("PUT /hook/popo-2026-08-22-"));
+    assert_eq!(got.header("authorization"), Some("Bearer syt_token"));
+}
+
+#[test]
+fn matrix_transaction_ids_follow_the_news_not_the_clock() {
+    let report = report();
+    let notification = |reasons: &[&str]| Notification {
+        report: &report,
+        reasons: reasons.iter().map(|r| r.to_string()).collect(),
+    };
+    let first = transaction_id(&notification(&["Explosions 0 → 1"]));
+    thread::sleep(std::time::Duration::from_millis(5));
+    assert_eq!(first, transaction_id(&notification(&["Explosions 0 → 1"])));
+    assert_ne!(first, transaction_id(&notification(&["Explosions 1 → 2"])));
+}
+
+#[test]
+fn error_status_is_an_error() {
+    let (url, _rx) = stub_server("500 Internal Server Error");
+    let err = send(&Webhook::new(WebhookKind::Discord, url), &report()).unwrap_err();
+    assert!(err.to_string().contains("HTTP 500"));
+}